@@ -1,7 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use md2pptx::{convert_markdown_to_pptx, MarkdownDocument};
+use md2pptx::MarkdownDocument;
 use std::fs;
-use std::path::Path;
 use tempfile::tempdir;
 
 fn benchmark_markdown_parsing(c: &mut Criterion) {
@@ -80,6 +79,7 @@ Content for slide 3
                 black_box(&input_file),
                 black_box(&output_file),
                 black_box("default"),
+                black_box(&md2pptx::ConversionOptions::default()),
                 black_box(md2pptx::LogLevel::Quiet),
             )
         })
@@ -0,0 +1,74 @@
+use crate::converter::md_to_pptx::{
+    find_input_files, parse_and_combine_markdown_files, ConversionOptions,
+};
+use crate::converter::report::ConversionReport;
+use crate::parser::lint::{lint_document, LintConfig, LintIssue, Severity};
+use crate::parser::markdown::MarkdownDocument;
+use crate::presentation::accessibility::AccessibilityIssue;
+use crate::presentation::builder::PresentationBuilder;
+use crate::presentation::templates::SlideTemplate;
+use crate::utils::file_io;
+use crate::LogLevel;
+use crate::Result;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Lint findings for one Markdown file
+#[derive(Debug, Clone, Serialize)]
+pub struct LintReport {
+    pub file: PathBuf,
+    pub issues: Vec<LintIssue>,
+}
+
+impl LintReport {
+    pub fn count(&self, severity: Severity) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == severity)
+            .count()
+    }
+}
+
+/// Check the deck the same input and options would produce for
+/// accessibility problems, in slide order
+pub fn check_accessibility(
+    input_dir: &Path,
+    template_name: &str,
+    recursive: bool,
+    options: &ConversionOptions,
+    log_level: LogLevel,
+) -> Result<Vec<AccessibilityIssue>> {
+    let template = SlideTemplate::load(template_name)?;
+    let markdown_files = find_input_files(input_dir, recursive)?;
+    let (mut document, _) = parse_and_combine_markdown_files(
+        &markdown_files,
+        log_level,
+        &mut ConversionReport::default(),
+    )?;
+    options.apply_metadata(&mut document.metadata);
+
+    let builder = PresentationBuilder::from_markdown_with_options(
+        &document,
+        template,
+        &options.presentation,
+    )?;
+    builder.accessibility_issues()
+}
+
+/// Lint each Markdown file in a directory on its own, so findings point at
+/// lines of the file they come from
+pub fn lint_files(
+    input_dir: &Path,
+    recursive: bool,
+    config: &LintConfig,
+) -> Result<Vec<LintReport>> {
+    find_input_files(input_dir, recursive)?
+        .into_iter()
+        .map(|file| {
+            let source = file_io::read_file_to_string(&file)?;
+            let document = MarkdownDocument::parse(&source).map_err(|e| e.in_file(&file))?;
+            let issues = lint_document(&document, config);
+            Ok(LintReport { file, issues })
+        })
+        .collect()
+}
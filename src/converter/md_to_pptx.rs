@@ -1,35 +1,100 @@
-use crate::parser::markdown::MarkdownDocument;
-use crate::presentation::{builder::PresentationBuilder, templates::SlideTemplate};
+use crate::converter::report::{elapsed_ms, ConversionReport, FileReport, OutputReport};
+use crate::parser::markdown::{
+    split_front_matter, DocumentMetadata, MarkdownDocument, Section, SlideElement,
+};
+use crate::presentation::background::resolve_spec;
+use crate::presentation::builder::{PresentationBuilder, PresentationOptions};
+use crate::presentation::templates::SlideTemplate;
+use crate::utils::diagnostics::Diagnostics;
 use crate::utils::{error::Error, file_io};
 use crate::LogLevel;
 use crate::Result;
 use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Options that shape the generated decks beyond the template choice
+#[derive(Debug, Clone, Default)]
+pub struct ConversionOptions {
+    pub presentation: PresentationOptions,
+    /// Metadata given on the command line; any field set here overrides front matter
+    pub metadata: DocumentMetadata,
+    /// Metadata from the project config, for fields front matter leaves unset
+    pub metadata_defaults: DocumentMetadata,
+    /// Fail instead of writing a deck when there are warnings
+    pub strict: bool,
+}
 
-/// Convert Markdown files from a directory to a PowerPoint presentation
+impl ConversionOptions {
+    pub(crate) fn apply_metadata(&self, metadata: &mut DocumentMetadata) {
+        let defaults = &self.metadata_defaults;
+        metadata.title = metadata.title.take().or_else(|| defaults.title.clone());
+        metadata.author = metadata.author.take().or_else(|| defaults.author.clone());
+        metadata.description = metadata
+            .description
+            .take()
+            .or_else(|| defaults.description.clone());
+        metadata.date = metadata.date.take().or_else(|| defaults.date.clone());
+
+        let overrides = &self.metadata;
+        if overrides.title.is_some() {
+            metadata.title = overrides.title.clone();
+        }
+        if overrides.author.is_some() {
+            metadata.author = overrides.author.clone();
+        }
+        if overrides.description.is_some() {
+            metadata.description = overrides.description.clone();
+        }
+        if overrides.date.is_some() {
+            metadata.date = overrides.date.clone();
+        }
+        for (key, value) in &overrides.custom_properties {
+            metadata
+                .custom_properties
+                .insert(key.clone(), value.clone());
+        }
+    }
+
+    /// With `strict`, turn any warnings into an error
+    pub(crate) fn check_strict(&self, diagnostics: &Diagnostics) -> Result<()> {
+        if self.strict && !diagnostics.is_empty() {
+            return Err(Error::Warnings {
+                diagnostics: diagnostics.clone(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Convert Markdown files from a directory to a PowerPoint presentation,
+/// returning warnings about content that could not be converted as written.
+/// Per-file results go into `report`, also when the conversion fails.
 pub fn convert_markdown_to_pptx(
     input_dir: &Path,
     output_file: &Path,
     template_name: &str,
     recursive: bool,
+    options: &ConversionOptions,
     log_level: LogLevel,
-) -> Result<()> {
+    report: &mut ConversionReport,
+) -> Result<Diagnostics> {
     if log_level.should_print_info() {
         println!("Starting conversion process...");
     }
 
+    let template = SlideTemplate::load(template_name)?;
+
     // Find all Markdown files in the input directory
-    let markdown_files = if recursive {
-        file_io::find_markdown_files(input_dir)?
-    } else {
-        find_markdown_files_non_recursive(input_dir)?
-    };
+    let markdown_files = find_input_files(input_dir, recursive)?;
 
     if log_level.should_print_info() {
         println!("Found {} Markdown files to process", markdown_files.len());
     }
 
     // Parse all Markdown files and combine them into a single document
-    let combined_document = parse_and_combine_markdown_files(&markdown_files, log_level)?;
+    let (mut combined_document, mut diagnostics) =
+        parse_and_combine_markdown_files(&markdown_files, log_level, report)?;
+    options.apply_metadata(&mut combined_document.metadata);
 
     if log_level.should_print_info() {
         println!(
@@ -38,34 +103,75 @@ pub fn convert_markdown_to_pptx(
         );
     }
 
-    // Get the template
-    let template = SlideTemplate::from_name(template_name);
-
     if log_level.should_print_debug() {
         println!("Using template: {:?}", template);
     }
 
-    // Build the PowerPoint presentation
-    let presentation_builder = PresentationBuilder::from_markdown(&combined_document, template)?;
+    // Build the PowerPoint presentation and write it
+    let mut output = OutputReport::new(output_file, &markdown_files);
+    let result = build_and_write(
+        &combined_document,
+        template,
+        options,
+        output_file,
+        &mut diagnostics,
+        &mut output,
+        log_level,
+    );
+    report.add_output(output, &diagnostics, result.as_ref().err());
+    let size = result?;
+
+    if log_level.should_print_info() {
+        println!(
+            "Successfully created PowerPoint file: {}",
+            output_file.display()
+        );
+        println!("File size: {} bytes", size);
+    }
+
+    Ok(diagnostics)
+}
+
+/// Build a deck and write it, recording its slides, size and timings in
+/// `output`. Returns the size of the written file.
+fn build_and_write(
+    document: &MarkdownDocument,
+    template: SlideTemplate,
+    options: &ConversionOptions,
+    output_file: &Path,
+    diagnostics: &mut Diagnostics,
+    output: &mut OutputReport,
+    log_level: LogLevel,
+) -> Result<u64> {
+    let started = Instant::now();
+    let presentation_builder =
+        PresentationBuilder::from_markdown_with_options(document, template, &options.presentation)?;
+    diagnostics.extend(presentation_builder.diagnostics().clone());
+    output.slides = presentation_builder.outline().len();
+    options.check_strict(diagnostics)?;
 
     if log_level.should_print_info() {
         println!("Building PowerPoint presentation...");
     }
 
     let pptx_data = presentation_builder.build()?;
+    output.build_ms = elapsed_ms(started);
 
     // Write the output file
+    let started = Instant::now();
     file_io::write_file(output_file, &pptx_data)?;
+    output.write_ms = elapsed_ms(started);
+    output.size = Some(pptx_data.len() as u64);
+    Ok(pptx_data.len() as u64)
+}
 
-    if log_level.should_print_info() {
-        println!(
-            "Successfully created PowerPoint file: {}",
-            output_file.display()
-        );
-        println!("File size: {} bytes", pptx_data.len());
+/// Find the Markdown files to convert, optionally in subdirectories too
+pub(crate) fn find_input_files(input_dir: &Path, recursive: bool) -> Result<Vec<PathBuf>> {
+    if recursive {
+        file_io::find_markdown_files(input_dir)
+    } else {
+        find_markdown_files_non_recursive(input_dir)
     }
-
-    Ok(())
 }
 
 /// Find Markdown files in a directory (non-recursive)
@@ -111,11 +217,17 @@ fn find_markdown_files_non_recursive(dir: &Path) -> Result<Vec<PathBuf>> {
 }
 
 /// Parse multiple Markdown files and combine them into a single document
-fn parse_and_combine_markdown_files(
+///
+/// Each file becomes a section named after its front matter title or file stem,
+/// unless the file already splits itself into H1 sections.
+pub(crate) fn parse_and_combine_markdown_files(
     markdown_files: &[PathBuf],
     log_level: LogLevel,
-) -> Result<MarkdownDocument> {
+    report: &mut ConversionReport,
+) -> Result<(MarkdownDocument, Diagnostics)> {
+    let mut diagnostics = Diagnostics::default();
     let mut combined_slides = Vec::new();
+    let mut combined_sections = Vec::new();
     let mut combined_metadata = crate::parser::markdown::DocumentMetadata::default();
 
     // Track if we've set the main metadata yet
@@ -131,13 +243,8 @@ fn parse_and_combine_markdown_files(
             );
         }
 
-        // Read the file content
-        let content = file_io::read_file_to_string(file_path)?;
-
-        // Parse the Markdown document
-        let document = MarkdownDocument::parse(&content).map_err(|e| {
-            Error::conversion(format!("Failed to parse {}: {}", file_path.display(), e))
-        })?;
+        let (content, document, warnings) = parse_file(file_path, report)?;
+        diagnostics.extend(warnings);
 
         // Use metadata from the first file that has it
         if !metadata_set && (document.metadata.title.is_some() || document.metadata.author.is_some()) {
@@ -145,6 +252,29 @@ fn parse_and_combine_markdown_files(
             metadata_set = true;
         }
 
+        // Record sections before the slides are appended
+        let section_offset = combined_slides.len();
+        if document.sections.is_empty() {
+            let name = split_front_matter(&content)
+                .0
+                .and_then(|front_matter| front_matter.title)
+                .or_else(|| {
+                    file_path
+                        .file_stem()
+                        .map(|stem| stem.to_string_lossy().to_string())
+                })
+                .unwrap_or_else(|| format!("File {}", index + 1));
+            combined_sections.push(Section {
+                name,
+                start_slide: section_offset,
+            });
+        } else {
+            combined_sections.extend(document.sections.iter().map(|section| Section {
+                name: section.name.clone(),
+                start_slide: section_offset + section.start_slide,
+            }));
+        }
+
         // Add all slides from this document
         let slide_count = document.slides.len();
         for mut slide in document.slides {
@@ -184,30 +314,90 @@ fn parse_and_combine_markdown_files(
         return Err(Error::conversion("No slides found in any Markdown files"));
     }
 
-    Ok(MarkdownDocument {
+    let document = MarkdownDocument {
         slides: combined_slides,
         metadata: combined_metadata,
-    })
+        sections: combined_sections,
+    };
+    Ok((document, diagnostics))
+}
+
+/// Read and parse one Markdown file, recording how it went in `report`
+fn parse_file(
+    file_path: &Path,
+    report: &mut ConversionReport,
+) -> Result<(String, MarkdownDocument, Diagnostics)> {
+    let started = Instant::now();
+    let mut file = FileReport::new(file_path);
+    let parsed = file_io::read_file_to_string(file_path).and_then(|content| {
+        let (document, warnings) = MarkdownDocument::parse_with_diagnostics(&content)?;
+        Ok((content, document, warnings))
+    });
+    file.parse_ms = elapsed_ms(started);
+
+    match parsed {
+        Ok((content, mut document, warnings)) => {
+            let warnings = warnings.in_file(file_path);
+            resolve_relative_paths(&mut document, file_path);
+            file.slides = document.slides.len();
+            file.warnings = warnings.clone();
+            report.files.push(file);
+            Ok((content, document, warnings))
+        }
+        Err(e) => {
+            let e = e.in_file(file_path);
+            file.error = Some((&e).into());
+            report.files.push(file);
+            Err(e)
+        }
+    }
 }
 
-/// Convert each Markdown file in a directory to separate PowerPoint presentations
+/// Take relative image paths, in background settings and image elements, as
+/// relative to the Markdown file, and record the file on each slide
+fn resolve_relative_paths(document: &mut MarkdownDocument, file_path: &Path) {
+    let dir = file_path.parent().unwrap_or_else(|| Path::new(""));
+    for slide in &mut document.slides {
+        slide.source = Some(file_path.to_path_buf());
+        for element in &mut slide.content {
+            if let SlideElement::Image { url, .. } = &mut element.node {
+                if !url.contains(':') && Path::new(url.as_str()).is_relative() {
+                    *url = dir.join(&*url).display().to_string();
+                }
+            }
+        }
+    }
+    let settings = std::iter::once(&mut document.metadata.custom_properties).chain(
+        document
+            .slides
+            .iter_mut()
+            .map(|slide| &mut slide.directives),
+    );
+    for properties in settings {
+        if let Some(spec) = properties.get_mut("background") {
+            *spec = resolve_spec(spec, dir);
+        }
+    }
+}
+
+/// Convert each Markdown file in a directory to separate PowerPoint presentations,
+/// returning how many were written and the warnings for all of them. Each
+/// file's result, failures included, goes into `report`.
 pub fn convert_separate_files(
     input_dir: &Path,
     output_dir: &Path,
     template_name: &str,
     recursive: bool,
+    options: &ConversionOptions,
     log_level: LogLevel,
-) -> Result<usize> {
+    report: &mut ConversionReport,
+) -> Result<(usize, Diagnostics)> {
     if log_level.should_print_info() {
         println!("Starting separate file conversion process...");
     }
 
     // Find all Markdown files in the input directory
-    let markdown_files = if recursive {
-        file_io::find_markdown_files(input_dir)?
-    } else {
-        find_markdown_files_non_recursive(input_dir)?
-    };
+    let markdown_files = find_input_files(input_dir, recursive)?;
 
     if log_level.should_print_info() {
         println!(
@@ -217,6 +407,7 @@ pub fn convert_separate_files(
     }
 
     let mut processed_count = 0;
+    let mut diagnostics = Diagnostics::default();
 
     // Process each markdown file separately
     for file_path in &markdown_files {
@@ -234,20 +425,44 @@ pub fn convert_separate_files(
         let output_file = output_dir.join(output_filename);
 
         // Convert the single file
-        match convert_single_markdown_file(file_path, &output_file, template_name, log_level) {
-            Ok(_) => {
+        match convert_file(
+            file_path,
+            &output_file,
+            template_name,
+            options,
+            log_level,
+            report,
+        ) {
+            Ok(warnings) => {
                 processed_count += 1;
+                diagnostics.extend(warnings);
                 if log_level.should_print_info() {
                     println!("  ✓ Created: {}", output_file.display());
                 }
             }
             Err(e) => {
-                eprintln!("  ✗ Failed to convert {}: {}", file_path.display(), e);
+                eprintln!(
+                    "  ✗ Failed to convert {}: error[{}]: {}",
+                    file_path.display(),
+                    e.code(),
+                    e.root()
+                );
                 // Continue processing other files instead of failing completely
+                if let Error::Warnings {
+                    diagnostics: warnings,
+                } = e
+                {
+                    diagnostics.extend(warnings);
+                }
             }
         }
     }
 
+    // With --strict, any warnings failed their files; the clean files are
+    // still written
+    if options.strict && !diagnostics.is_empty() {
+        return Err(Error::Warnings { diagnostics });
+    }
     if processed_count == 0 {
         return Err(Error::conversion("No files were successfully processed"));
     }
@@ -260,16 +475,35 @@ pub fn convert_separate_files(
         );
     }
 
-    Ok(processed_count)
+    Ok((processed_count, diagnostics))
 }
 
-/// Convert a single Markdown file to PowerPoint
+/// Convert a single Markdown file to PowerPoint, returning its warnings
 pub fn convert_single_markdown_file(
     input_file: &Path,
     output_file: &Path,
     template_name: &str,
+    options: &ConversionOptions,
     log_level: LogLevel,
-) -> Result<()> {
+) -> Result<Diagnostics> {
+    convert_file(
+        input_file,
+        output_file,
+        template_name,
+        options,
+        log_level,
+        &mut ConversionReport::default(),
+    )
+}
+
+fn convert_file(
+    input_file: &Path,
+    output_file: &Path,
+    template_name: &str,
+    options: &ConversionOptions,
+    log_level: LogLevel,
+    report: &mut ConversionReport,
+) -> Result<Diagnostics> {
     if log_level.should_print_info() {
         println!("Converting single file: {}", input_file.display());
     }
@@ -283,20 +517,28 @@ pub fn convert_single_markdown_file(
         .or_else(|_| file_io::validate_file_extension(input_file, "markdown"))?;
 
     // Read and parse the Markdown file
-    let content = file_io::read_file_to_string(input_file)?;
-    let document = MarkdownDocument::parse(&content)?;
+    let (_, mut document, mut diagnostics) = parse_file(input_file, report)?;
+    options.apply_metadata(&mut document.metadata);
 
     if log_level.should_print_info() {
         println!("Parsed {} slides from Markdown file", document.slides.len());
     }
 
-    // Get the template and build presentation
-    let template = SlideTemplate::from_name(template_name);
-    let presentation_builder = PresentationBuilder::from_markdown(&document, template)?;
-    let pptx_data = presentation_builder.build()?;
-
-    // Write the output file
-    file_io::write_file(output_file, &pptx_data)?;
+    // Get the template, then build and write the presentation
+    let mut output = OutputReport::new(output_file, &[input_file.to_path_buf()]);
+    let result = SlideTemplate::load(template_name).and_then(|template| {
+        build_and_write(
+            &document,
+            template,
+            options,
+            output_file,
+            &mut diagnostics,
+            &mut output,
+            LogLevel::Quiet,
+        )
+    });
+    report.add_output(output, &diagnostics, result.as_ref().err());
+    result?;
 
     if log_level.should_print_info() {
         println!(
@@ -305,7 +547,7 @@ pub fn convert_single_markdown_file(
         );
     }
 
-    Ok(())
+    Ok(diagnostics)
 }
 
 #[cfg(test)]
@@ -372,7 +614,12 @@ Content from file 2.
 
         let files = vec![temp_path.join("file1.md"), temp_path.join("file2.md")];
 
-        let combined = parse_and_combine_markdown_files(&files, LogLevel::Quiet).unwrap();
+        let (combined, _) = parse_and_combine_markdown_files(
+            &files,
+            LogLevel::Quiet,
+            &mut ConversionReport::default(),
+        )
+        .unwrap();
 
         // Should have slides from both files
         assert!(combined.slides.len() >= 2);
@@ -386,6 +633,18 @@ Content from file 2.
 
         assert!(all_titles.iter().any(|&title| title.contains("File 1")));
         assert!(all_titles.iter().any(|&title| title.contains("File 2")));
+
+        // file1 uses H2 slides, so its H1 opens a section; file2 is one section
+        let names: Vec<_> = combined.sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["File 1 Title", "file2"]);
+        assert_eq!(combined.sections[1].start_slide, 2);
+
+        // Slides remember the file they came from
+        let last = combined.slides.last().unwrap();
+        assert_eq!(last.source.as_deref(), Some(files[1].as_path()));
+        assert!(last
+            .location(&last.span)
+            .starts_with(&format!("{}:", files[1].display())));
     }
 
     #[test]
@@ -410,8 +669,13 @@ This is a test slide.
         )
         .unwrap();
 
-        let result =
-            convert_single_markdown_file(&input_file, &output_file, "default", LogLevel::Quiet);
+        let result = convert_single_markdown_file(
+            &input_file,
+            &output_file,
+            "default",
+            &ConversionOptions::default(),
+            LogLevel::Quiet,
+        );
 
         assert!(result.is_ok());
         assert!(output_file.exists());
@@ -420,4 +684,94 @@ This is a test slide.
         let file_size = fs::metadata(&output_file).unwrap().len();
         assert!(file_size > 1000); // PPTX files should be at least 1KB
     }
+
+    #[test]
+    fn test_warnings_and_strict_mode() {
+        let temp_dir = tempdir().unwrap();
+        let input_file = temp_dir.path().join("talk.md");
+        let output_file = temp_dir.path().join("talk.pptx");
+        fs::write(
+            &input_file,
+            "# Talk\n\n<div>Boxed</div>\n\n![Logo](missing.png)\n",
+        )
+        .unwrap();
+
+        let diagnostics = convert_single_markdown_file(
+            &input_file,
+            &output_file,
+            "default",
+            &ConversionOptions::default(),
+            LogLevel::Quiet,
+        )
+        .unwrap();
+        let codes: Vec<_> = diagnostics.iter().map(|d| d.code.code()).collect();
+        assert_eq!(codes, ["W001", "W006"]);
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.file.as_deref() == Some(input_file.as_path())));
+
+        fs::remove_file(&output_file).unwrap();
+        let options = ConversionOptions {
+            strict: true,
+            ..Default::default()
+        };
+        let result = convert_single_markdown_file(
+            &input_file,
+            &output_file,
+            "default",
+            &options,
+            LogLevel::Quiet,
+        );
+        assert!(matches!(result, Err(Error::Warnings { diagnostics }) if diagnostics.len() == 2));
+        assert!(!output_file.exists());
+
+        // In separate mode one file with warnings fails the run
+        let input_dir = temp_dir.path().join("slides");
+        let output_dir = temp_dir.path().join("decks");
+        fs::create_dir_all(&input_dir).unwrap();
+        fs::create_dir_all(&output_dir).unwrap();
+        fs::write(input_dir.join("clean.md"), "# Clean\n\nText\n").unwrap();
+        fs::rename(&input_file, input_dir.join("talk.md")).unwrap();
+        let result = convert_separate_files(
+            &input_dir,
+            &output_dir,
+            "default",
+            false,
+            &options,
+            LogLevel::Quiet,
+            &mut ConversionReport::default(),
+        );
+        assert!(matches!(result, Err(Error::Warnings { diagnostics }) if diagnostics.len() == 2));
+        assert!(output_dir.join("clean.pptx").exists());
+        assert!(!output_dir.join("talk.pptx").exists());
+    }
+
+    #[test]
+    fn test_metadata_precedence() {
+        let options = ConversionOptions {
+            metadata: DocumentMetadata {
+                author: Some("Flag author".to_string()),
+                ..Default::default()
+            },
+            metadata_defaults: DocumentMetadata {
+                title: Some("Config title".to_string()),
+                author: Some("Config author".to_string()),
+                description: Some("Config subtitle".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Front matter beats the config, and flags beat both
+        let mut metadata = DocumentMetadata {
+            title: Some("Front matter title".to_string()),
+            author: Some("Front matter author".to_string()),
+            ..Default::default()
+        };
+        options.apply_metadata(&mut metadata);
+        assert_eq!(metadata.title.as_deref(), Some("Front matter title"));
+        assert_eq!(metadata.author.as_deref(), Some("Flag author"));
+        assert_eq!(metadata.description.as_deref(), Some("Config subtitle"));
+        assert_eq!(metadata.date, None);
+    }
 }
@@ -1 +1,4 @@
+pub mod check;
 pub mod md_to_pptx;
+pub mod report;
+pub mod script;
@@ -0,0 +1,239 @@
+use crate::utils::diagnostics::Diagnostics;
+use crate::utils::error::Error;
+use crate::utils::file_io;
+use crate::Result;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Per-file results of a conversion run, written as JSON with `--report`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConversionReport {
+    pub files: Vec<FileReport>,
+    pub outputs: Vec<OutputReport>,
+    /// Failure of the run as a whole, e.g. an unknown template
+    pub error: Option<ErrorReport>,
+    pub elapsed_ms: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStatus {
+    Converted,
+    Failed,
+}
+
+/// How one Markdown file fared
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReport {
+    pub input: PathBuf,
+    pub status: FileStatus,
+    /// Deck the file's slides went into
+    pub output: Option<PathBuf>,
+    /// Slides parsed from the file
+    pub slides: usize,
+    pub warnings: Diagnostics,
+    pub error: Option<ErrorReport>,
+    pub parse_ms: f64,
+}
+
+/// One deck built from one or more files
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputReport {
+    pub path: PathBuf,
+    pub inputs: Vec<PathBuf>,
+    /// Slides in the deck, generated title and agenda slides included
+    pub slides: usize,
+    /// Size in bytes, unset when the deck was not written
+    pub size: Option<u64>,
+    pub error: Option<ErrorReport>,
+    pub build_ms: f64,
+    pub write_ms: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ErrorReport {
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl From<&Error> for ErrorReport {
+    fn from(error: &Error) -> Self {
+        Self {
+            code: error.code(),
+            message: error.message(),
+        }
+    }
+}
+
+/// Totals over the whole run
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReportSummary {
+    pub files: usize,
+    pub converted: usize,
+    pub failed: usize,
+    pub outputs: usize,
+    pub slides: usize,
+    pub warnings: usize,
+    pub bytes: u64,
+}
+
+impl FileReport {
+    pub(crate) fn new(input: &Path) -> Self {
+        Self {
+            input: input.to_path_buf(),
+            status: FileStatus::Failed,
+            output: None,
+            slides: 0,
+            warnings: Diagnostics::default(),
+            error: None,
+            parse_ms: 0.0,
+        }
+    }
+}
+
+impl OutputReport {
+    pub(crate) fn new(path: &Path, inputs: &[PathBuf]) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            inputs: inputs.to_vec(),
+            slides: 0,
+            size: None,
+            error: None,
+            build_ms: 0.0,
+            write_ms: 0.0,
+        }
+    }
+}
+
+impl ConversionReport {
+    /// Record a deck and the outcome of building it. Its input files take
+    /// their share of `diagnostics`, and its error when it failed.
+    pub(crate) fn add_output(
+        &mut self,
+        mut output: OutputReport,
+        diagnostics: &Diagnostics,
+        error: Option<&Error>,
+    ) {
+        output.error = error.map(ErrorReport::from);
+        for file in &mut self.files {
+            if !output.inputs.contains(&file.input) {
+                continue;
+            }
+            file.output = Some(output.path.clone());
+            file.warnings = diagnostics.for_file(&file.input);
+            match &output.error {
+                None => file.status = FileStatus::Converted,
+                Some(error) => {
+                    file.status = FileStatus::Failed;
+                    file.error.get_or_insert_with(|| error.clone());
+                }
+            }
+        }
+        self.outputs.push(output);
+    }
+
+    pub fn summary(&self) -> ReportSummary {
+        let written = || self.outputs.iter().filter(|output| output.size.is_some());
+        let converted = self
+            .files
+            .iter()
+            .filter(|file| file.status == FileStatus::Converted)
+            .count();
+        ReportSummary {
+            files: self.files.len(),
+            converted,
+            failed: self.files.len() - converted,
+            outputs: written().count(),
+            slides: written().map(|output| output.slides).sum(),
+            warnings: self.files.iter().map(|file| file.warnings.len()).sum(),
+            bytes: written().filter_map(|output| output.size).sum(),
+        }
+    }
+
+    /// Write the report as pretty-printed JSON, with the tool version and
+    /// the summary ahead of the per-file results
+    pub fn write(&self, path: &Path) -> Result<()> {
+        #[derive(Serialize)]
+        struct Document<'a> {
+            version: &'static str,
+            summary: ReportSummary,
+            #[serde(flatten)]
+            report: &'a ConversionReport,
+        }
+
+        let json = serde_json::to_string_pretty(&Document {
+            version: env!("CARGO_PKG_VERSION"),
+            summary: self.summary(),
+            report: self,
+        })?;
+        file_io::write_file(path, json.as_bytes())
+    }
+}
+
+/// Milliseconds since `start`
+pub fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::md_to_pptx::{convert_separate_files, ConversionOptions};
+    use crate::LogLevel;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_report_of_separate_conversion() {
+        let temp_dir = tempdir().unwrap();
+        let input_dir = temp_dir.path().join("slides");
+        let output_dir = temp_dir.path().join("out");
+        fs::create_dir_all(&input_dir).unwrap();
+        fs::create_dir_all(&output_dir).unwrap();
+        fs::write(input_dir.join("a.md"), "# One\n\n<div>x</div>\n\n# Two\n").unwrap();
+        fs::write(input_dir.join("b.md"), "").unwrap();
+
+        let mut report = ConversionReport::default();
+        let (count, _) = convert_separate_files(
+            &input_dir,
+            &output_dir,
+            "default",
+            false,
+            &ConversionOptions::default(),
+            LogLevel::Quiet,
+            &mut report,
+        )
+        .unwrap();
+        assert_eq!(count, 1);
+
+        let summary = report.summary();
+        assert_eq!(
+            (summary.files, summary.converted, summary.failed),
+            (2, 1, 1)
+        );
+        assert_eq!(
+            (summary.outputs, summary.slides, summary.warnings),
+            (1, 2, 1)
+        );
+        assert_eq!(
+            summary.bytes,
+            fs::metadata(output_dir.join("a.pptx")).unwrap().len()
+        );
+
+        let report_file = temp_dir.path().join("report.json");
+        report.write(&report_file).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&report_file).unwrap()).unwrap();
+        let files = &json["files"];
+        assert_eq!(files[0]["status"], "converted");
+        assert_eq!(files[0]["warnings"][0]["code"], "W001");
+        assert!(files[0]["output"].as_str().unwrap().ends_with("a.pptx"));
+        assert_eq!(files[1]["status"], "failed");
+        assert_eq!(files[1]["error"]["code"], "E002");
+        assert!(files[1]["output"].is_null());
+        assert_eq!(json["summary"]["converted"], 1);
+        assert_eq!(json["outputs"][0]["slides"], 2);
+        assert!(json["version"].is_string());
+    }
+}
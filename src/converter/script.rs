@@ -0,0 +1,266 @@
+use crate::converter::md_to_pptx::{
+    find_input_files, parse_and_combine_markdown_files, ConversionOptions,
+};
+use crate::converter::report::ConversionReport;
+use crate::presentation::builder::{PresentationBuilder, SlideOutline};
+use crate::presentation::templates::SlideTemplate;
+use crate::LogLevel;
+use crate::Result;
+use std::path::Path;
+
+/// Speaking rate assumed when timing a script
+pub const DEFAULT_WORDS_PER_MINUTE: u32 = 130;
+
+/// Output format of a presenter script
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScriptFormat {
+    #[default]
+    Markdown,
+    Text,
+}
+
+impl ScriptFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "markdown" | "md" => Some(ScriptFormat::Markdown),
+            "text" | "txt" => Some(ScriptFormat::Text),
+            _ => None,
+        }
+    }
+
+    /// Format implied by an output file's extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_name)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ScriptOptions {
+    pub format: ScriptFormat,
+    pub words_per_minute: u32,
+}
+
+impl Default for ScriptOptions {
+    fn default() -> Self {
+        Self {
+            format: ScriptFormat::default(),
+            words_per_minute: DEFAULT_WORDS_PER_MINUTE,
+        }
+    }
+}
+
+/// Build the presenter script for the deck the same input, template and
+/// options would produce
+pub fn export_script(
+    input_dir: &Path,
+    template_name: &str,
+    recursive: bool,
+    options: &ConversionOptions,
+    script: &ScriptOptions,
+    log_level: LogLevel,
+) -> Result<String> {
+    let template = SlideTemplate::load(template_name)?;
+    let markdown_files = find_input_files(input_dir, recursive)?;
+    let (mut document, _) = parse_and_combine_markdown_files(
+        &markdown_files,
+        log_level,
+        &mut ConversionReport::default(),
+    )?;
+    options.apply_metadata(&mut document.metadata);
+
+    let builder = PresentationBuilder::from_markdown_with_options(
+        &document,
+        template,
+        &options.presentation,
+    )?;
+    let title = document.metadata.title.as_deref().unwrap_or("Presentation");
+    Ok(presenter_script(title, &builder.outline(), script))
+}
+
+/// Per-slide titles, speaker notes and speaking times, with the total time.
+/// A slide is timed from its notes, or from its visible text when it has none.
+pub fn presenter_script(title: &str, slides: &[SlideOutline], options: &ScriptOptions) -> String {
+    let times: Vec<u64> = slides
+        .iter()
+        .map(|slide| speaking_seconds(slide_words(slide), options.words_per_minute))
+        .collect();
+    let total = format_duration(times.iter().sum());
+    let summary = format!(
+        "{} slides, about {} at {} words per minute",
+        slides.len(),
+        total,
+        options.words_per_minute
+    );
+
+    let mut script = match options.format {
+        ScriptFormat::Markdown => format!("# {}\n\n{}.\n", title, summary),
+        ScriptFormat::Text => format!(
+            "{}\n{}\n\n{}\n",
+            title,
+            "=".repeat(title.chars().count()),
+            summary
+        ),
+    };
+    for (slide, seconds) in slides.iter().zip(times) {
+        let heading = slide.title.as_deref().unwrap_or("Untitled slide");
+        let time = format_duration(seconds);
+        match options.format {
+            ScriptFormat::Markdown => {
+                let notes = if slide.notes.is_empty() {
+                    "*No speaker notes.*".to_string()
+                } else {
+                    slide.notes.join("\n\n")
+                };
+                script.push_str(&format!(
+                    "\n## {}. {} ({})\n\n{}\n",
+                    slide.number, heading, time, notes
+                ));
+            }
+            ScriptFormat::Text => {
+                let notes = if slide.notes.is_empty() {
+                    "(no speaker notes)".to_string()
+                } else {
+                    slide.notes.join("\n\n    ")
+                };
+                script.push_str(&format!(
+                    "\n{}. {} [{}]\n    {}\n",
+                    slide.number, heading, time, notes
+                ));
+            }
+        }
+    }
+    format!("{}\n", script.trim_end())
+}
+
+/// Words spoken on a slide: its notes, else its title and text
+fn slide_words(slide: &SlideOutline) -> usize {
+    let count = |lines: &[String]| -> usize {
+        lines
+            .iter()
+            .map(|line| line.split_whitespace().count())
+            .sum()
+    };
+    if slide.notes.is_empty() {
+        slide
+            .title
+            .as_deref()
+            .map_or(0, |title| title.split_whitespace().count())
+            + count(&slide.text)
+    } else {
+        count(&slide.notes)
+    }
+}
+
+/// Seconds needed to say `words` words, rounded up
+fn speaking_seconds(words: usize, words_per_minute: u32) -> u64 {
+    let words_per_minute = u64::from(words_per_minute.max(1));
+    (words as u64 * 60).div_ceil(words_per_minute)
+}
+
+/// `m:ss`, or `h:mm:ss` from an hour up
+fn format_duration(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::markdown::MarkdownDocument;
+    use crate::presentation::builder::PresentationOptions;
+
+    #[test]
+    fn test_presenter_script() {
+        let doc = MarkdownDocument::parse(
+            "# Welcome\n\n<!-- notes: Thank everyone for coming and introduce the team. -->\n\n# Results\n\n- Revenue up\n- Costs down\n",
+        )
+        .unwrap();
+        let options = PresentationOptions {
+            title_slide: true,
+            ..Default::default()
+        };
+        let builder =
+            PresentationBuilder::from_markdown_with_options(&doc, SlideTemplate::Default, &options)
+                .unwrap();
+        let outline = builder.outline();
+        // The title slide replaces the leading H1, as in the deck
+        assert_eq!(outline.len(), 2);
+        assert_eq!(outline[0].title.as_deref(), Some("Welcome"));
+        assert_eq!(outline[0].notes.len(), 1);
+
+        let script = presenter_script(
+            "Welcome",
+            &outline,
+            &ScriptOptions {
+                format: ScriptFormat::Markdown,
+                words_per_minute: 60,
+            },
+        );
+        assert!(script.starts_with("# Welcome\n\n2 slides, about "));
+        assert!(script.contains("## 2. Results (0:05)\n\n*No speaker notes.*"));
+
+        let text = presenter_script(
+            "Welcome",
+            &outline,
+            &ScriptOptions {
+                format: ScriptFormat::Text,
+                words_per_minute: 60,
+            },
+        );
+        assert!(text.starts_with("Welcome\n=======\n"));
+        assert!(text.contains("2. Results [0:05]\n    (no speaker notes)"));
+    }
+
+    #[test]
+    fn test_export_script_uses_conversion_options() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("talk.md"),
+            "---\ntitle: Draft\n---\n# One\n\nText\n",
+        )
+        .unwrap();
+        let mut options = ConversionOptions::default();
+        options.metadata.title = Some("Kickoff".to_string());
+        options.presentation.title_slide = true;
+
+        let script = export_script(
+            dir.path(),
+            "dark",
+            false,
+            &options,
+            &ScriptOptions::default(),
+            LogLevel::Quiet,
+        )
+        .unwrap();
+        assert!(script.starts_with("# Kickoff\n\n2 slides, about "));
+        assert!(script.contains("## 1. Kickoff"));
+
+        assert!(export_script(
+            dir.path(),
+            "unknown",
+            false,
+            &options,
+            &ScriptOptions::default(),
+            LogLevel::Quiet,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_speaking_time() {
+        assert_eq!(speaking_seconds(130, 130), 60);
+        assert_eq!(speaking_seconds(1, 130), 1);
+        assert_eq!(format_duration(75), "1:15");
+        assert_eq!(format_duration(3725), "1:02:05");
+        assert_eq!(
+            ScriptFormat::from_path(Path::new("talk.txt")),
+            Some(ScriptFormat::Text)
+        );
+    }
+}
@@ -5,9 +5,10 @@ pub mod utils;
 
 pub use converter::md_to_pptx::{
     convert_markdown_to_pptx, convert_separate_files, convert_single_markdown_file,
+    ConversionOptions,
 };
 pub use parser::markdown::MarkdownDocument;
-pub use presentation::builder::PresentationBuilder;
+pub use presentation::builder::{AgendaSource, PresentationBuilder, PresentationOptions};
 
 #[derive(Debug, Clone, Copy)]
 pub enum LogLevel {
@@ -1,72 +1,108 @@
-use clap::{Arg, Command};
-use md2pptx::{convert_markdown_to_pptx, LogLevel, Result};
-use std::path::PathBuf;
+use clap::{Arg, ArgMatches, Command};
+use md2pptx::converter::check::{check_accessibility, lint_files};
+use md2pptx::converter::report::{elapsed_ms, ConversionReport};
+use md2pptx::converter::script::{export_script, ScriptFormat, ScriptOptions};
+use md2pptx::parser::lint::Severity;
+use md2pptx::presentation::extract::extract_template;
+use md2pptx::presentation::palette::{check_template_contrast, generate_palette};
+use md2pptx::presentation::templates::{
+    user_template_dir, user_templates, CustomTemplate, FooterOverlay, SlideSize, SlideTemplate,
+    BUILT_IN_TEMPLATES,
+};
+use md2pptx::presentation::transitions::Transition;
+use md2pptx::utils::config::ProjectConfig;
+use md2pptx::utils::diagnostics::Diagnostics;
+use md2pptx::utils::error::{Error, ISSUES_FOUND_EXIT_CODE};
+use md2pptx::utils::file_io;
+use md2pptx::{convert_markdown_to_pptx, AgendaSource, ConversionOptions, LogLevel, Result};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 fn main() -> Result<()> {
-    let matches = Command::new("md2pptx")
-        .version("0.1.0")
-        .author("Your Name <your.email@example.com>")
-        .about("Convert Markdown files to PowerPoint presentations")
-        .arg(
-            Arg::new("input")
-                .help("Input directory containing Markdown files")
-                .required(true)
-                .index(1)
-                .value_parser(clap::value_parser!(PathBuf)),
-        )
-        .arg(
-            Arg::new("output")
-                .help("Output PowerPoint file (.pptx) or output directory for separate files")
-                .required(true)
-                .index(2)
-                .value_parser(clap::value_parser!(PathBuf)),
-        )
-        .arg(
-            Arg::new("template")
-                .short('t')
-                .long("template")
-                .help("PowerPoint template to use")
-                .value_name("TEMPLATE")
-                .default_value("default"),
-        )
-        .arg(
-            Arg::new("recursive")
-                .short('r')
-                .long("recursive")
-                .help("Process subdirectories recursively")
-                .action(clap::ArgAction::SetTrue),
-        )
-        .arg(
-            Arg::new("verbose")
-                .short('v')
-                .long("verbose")
-                .help("Enable verbose output")
-                .action(clap::ArgAction::SetTrue),
-        )
-        .arg(
-            Arg::new("quiet")
-                .short('q')
-                .long("quiet")
-                .help("Suppress all output except errors")
-                .action(clap::ArgAction::SetTrue)
-                .conflicts_with("verbose"),
-        )
-        .arg(
-            Arg::new("separate")
-                .short('s')
-                .long("separate")
-                .help("Generate separate PPTX file for each MD file (output must be directory)")
-                .action(clap::ArgAction::SetTrue),
-        )
-        .get_matches();
+    let matches = cli().get_matches();
+
+    match matches.subcommand() {
+        Some(("templates", template_matches)) => {
+            if let Err(e) = run_template_command(template_matches) {
+                fail(&e);
+            }
+            return Ok(());
+        }
+        Some(("check", check_matches)) => {
+            match run_check_command(check_matches) {
+                Ok(0) => {}
+                Ok(_) => std::process::exit(ISSUES_FOUND_EXIT_CODE),
+                Err(e) => fail(&e),
+            }
+            return Ok(());
+        }
+        Some(("lint", lint_matches)) => {
+            match run_lint_command(lint_matches) {
+                Ok(0) => {}
+                Ok(_) => std::process::exit(ISSUES_FOUND_EXIT_CODE),
+                Err(e) => fail(&e),
+            }
+            return Ok(());
+        }
+        Some(("script", script_matches)) => {
+            if let Err(e) = run_script_command(script_matches) {
+                fail(&e);
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
 
     let input_dir = matches.get_one::<PathBuf>("input").unwrap();
     let output_path = matches.get_one::<PathBuf>("output").unwrap();
     let template = matches.get_one::<String>("template").unwrap();
     let recursive = matches.get_flag("recursive");
     let verbose = matches.get_flag("verbose");
-    let quiet = matches.get_flag("quiet");
+    let json_messages = matches
+        .get_one::<String>("message-format")
+        .map(String::as_str)
+        == Some("json");
+    // Progress lines would mix with the JSON on stdout
+    let quiet = matches.get_flag("quiet") || json_messages;
     let separate = matches.get_flag("separate");
 
+    let mut options = ConversionOptions::default();
+    options.presentation.title_slide = matches.get_flag("title-slide");
+    options.presentation.agenda = matches
+        .get_one::<String>("agenda")
+        .and_then(|source| AgendaSource::from_name(source));
+    options.presentation.transition = matches
+        .get_one::<String>("transition")
+        .and_then(|name| Transition::from_name(name));
+    options.presentation.incremental_lists = matches.get_flag("incremental");
+    options.presentation.slide_size = matches.get_one::<SlideSize>("slide-size").copied();
+    options.presentation.reference_doc = matches.get_one::<PathBuf>("reference-doc").cloned();
+    options.presentation.language = matches.get_one::<String>("lang").cloned();
+    if matches.get_flag("draft") {
+        options.presentation.watermark = Some("DRAFT".to_string());
+    }
+    let footer_date = matches.get_one::<String>("footer-date");
+    options.presentation.footer = Some(FooterOverlay {
+        text: matches.get_one::<String>("footer").cloned(),
+        slide_number: switch(&matches, "slide-numbers", "no-slide-numbers"),
+        date: match footer_date {
+            Some(_) => Some(true),
+            None => matches.get_flag("no-footer-date").then_some(false),
+        },
+        fixed_date: footer_date.filter(|date| !date.is_empty()).cloned(),
+        show_on_title_slide: switch(&matches, "footer-on-title", "no-footer-on-title"),
+    });
+    options.metadata.title = matches.get_one::<String>("title").cloned();
+    options.metadata.description = matches.get_one::<String>("subtitle").cloned();
+    options.metadata.author = matches.get_one::<String>("author").cloned();
+    options.metadata.date = matches.get_one::<String>("date").cloned();
+    options.strict = matches.get_flag("strict");
+    let report_path = matches.get_one::<PathBuf>("report");
+    let mut run = Run {
+        report: ConversionReport::default(),
+        report_path: report_path.map(PathBuf::as_path),
+        started: Instant::now(),
+    };
+
     // Set up logging level based on flags
     let log_level = if quiet {
         LogLevel::Quiet
@@ -94,50 +130,40 @@ fn main() -> Result<()> {
 
     // Validate input directory exists
     if !input_dir.exists() {
-        eprintln!(
-            "Error: Input directory '{}' does not exist",
-            input_dir.display()
-        );
-        std::process::exit(1);
+        run.fail(&Error::file_not_found(input_dir.display().to_string()));
     }
 
     if !input_dir.is_dir() {
-        eprintln!(
-            "Error: Input path '{}' is not a directory",
+        run.fail(&Error::configuration(format!(
+            "Input path '{}' is not a directory",
             input_dir.display()
-        );
-        std::process::exit(1);
+        )));
+    }
+    match ProjectConfig::find(input_dir) {
+        Ok(config) => options.metadata_defaults = config.metadata.to_metadata(),
+        Err(e) => run.fail(&e),
     }
 
     // Validate output path based on mode
     if separate {
         // In separate mode, output must be a directory
         if output_path.exists() && !output_path.is_dir() {
-            eprintln!("Error: When using --separate, output path must be a directory");
-            std::process::exit(1);
+            run.fail(&Error::configuration(
+                "When using --separate, output path must be a directory",
+            ));
         }
         // Create output directory if it doesn't exist
         if !output_path.exists() {
             if let Err(e) = std::fs::create_dir_all(output_path) {
-                eprintln!(
-                    "Error: Failed to create output directory '{}': {}",
-                    output_path.display(),
-                    e
-                );
-                std::process::exit(1);
+                run.fail(&Error::from(e).in_file(output_path));
             }
         }
-    } else {
+    } else if output_path.extension().is_none_or(|ext| ext != "pptx") {
         // In combined mode, output must be a .pptx file
-        if let Some(ext) = output_path.extension() {
-            if ext != "pptx" {
-                eprintln!("Error: Output file must have .pptx extension");
-                std::process::exit(1);
-            }
-        } else {
-            eprintln!("Error: Output file must have .pptx extension");
-            std::process::exit(1);
-        }
+        run.fail(
+            &Error::configuration("Output file must have .pptx extension")
+                .with_hint("use --separate to write one deck per file into a directory"),
+        );
     }
 
     // Perform the conversion
@@ -147,9 +173,13 @@ fn main() -> Result<()> {
             output_path,
             template,
             recursive,
+            &options,
             log_level,
+            &mut run.report,
         ) {
-            Ok(count) => {
+            Ok((count, diagnostics)) => {
+                print_diagnostics(&mut run, &diagnostics, json_messages);
+                run.complete();
                 if !quiet {
                     println!(
                         "Conversion completed successfully! {} files processed.",
@@ -158,23 +188,841 @@ fn main() -> Result<()> {
                 }
             }
             Err(e) => {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
+                if let Error::Warnings { diagnostics } = e.root() {
+                    print_diagnostics(&mut run, diagnostics, json_messages);
+                }
+                run.fail(&e);
             }
         }
     } else {
-        match convert_markdown_to_pptx(input_dir, output_path, template, recursive, log_level) {
-            Ok(_) => {
+        match convert_markdown_to_pptx(
+            input_dir,
+            output_path,
+            template,
+            recursive,
+            &options,
+            log_level,
+            &mut run.report,
+        ) {
+            Ok(diagnostics) => {
+                print_diagnostics(&mut run, &diagnostics, json_messages);
+                run.complete();
                 if !quiet {
                     println!("Conversion completed successfully!");
                 }
             }
             Err(e) => {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
+                if let Error::Warnings { diagnostics } = e.root() {
+                    print_diagnostics(&mut run, diagnostics, json_messages);
+                }
+                run.fail(&e);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Command line definition. Subcommand names win over an input directory of
+/// the same name, so such a directory has to be given as a path, e.g.
+/// `./templates`.
+fn cli() -> Command {
+    Command::new("md2pptx")
+        .version("0.1.0")
+        .author("Your Name <your.email@example.com>")
+        .about("Convert Markdown files to PowerPoint presentations")
+        .subcommand_negates_reqs(true)
+        .args_conflicts_with_subcommands(true)
+        .subcommand(
+            Command::new("templates")
+                .visible_alias("template")
+                .about("List, inspect, export, extract and generate templates")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("list")
+                        .about("List built-in templates and templates in the user template directory"),
+                )
+                .subcommand(
+                    Command::new("show")
+                        .about("Print a template's colors, fonts and layout")
+                        .arg(
+                            Arg::new("name")
+                                .help("Template name or template file")
+                                .required(true),
+                        ),
+                )
+                .subcommand(
+                    Command::new("export")
+                        .about("Write a template as an editable TOML or JSON file")
+                        .arg(
+                            Arg::new("name")
+                                .help("Template name or template file")
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::new("output")
+                                .short('o')
+                                .long("output")
+                                .help("Template file to write (.toml or .json); prints TOML when omitted")
+                                .value_name("FILE")
+                                .value_parser(clap::value_parser!(PathBuf)),
+                        ),
+                )
+                .subcommand(
+                    Command::new("extract")
+                        .about("Extract colors, fonts and layout from a .pptx or .potx into a template file")
+                        .arg(
+                            Arg::new("presentation")
+                                .help("Presentation or template to read")
+                                .required(true)
+                                .value_parser(clap::value_parser!(PathBuf)),
+                        )
+                        .arg(
+                            Arg::new("output")
+                                .short('o')
+                                .long("output")
+                                .help("Template file to write (.toml or .json); prints TOML when omitted")
+                                .value_name("FILE")
+                                .value_parser(clap::value_parser!(PathBuf)),
+                        ),
+                )
+                .subcommand(
+                    Command::new("palette")
+                        .about("Generate a template from one or two brand colors and check its contrast")
+                        .arg(
+                            Arg::new("primary")
+                                .help("Primary brand color, e.g. 1F4E79")
+                                .required(true),
+                        )
+                        .arg(Arg::new("secondary").help("Secondary brand color"))
+                        .arg(
+                            Arg::new("name")
+                                .long("name")
+                                .help("Template name")
+                                .value_name("NAME")
+                                .default_value("brand"),
+                        )
+                        .arg(
+                            Arg::new("output")
+                                .short('o')
+                                .long("output")
+                                .help("Template file to write (.toml or .json); prints TOML when omitted")
+                                .value_name("FILE")
+                                .value_parser(clap::value_parser!(PathBuf)),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("script")
+                .about("Export slide titles and speaker notes as a timed presenter script")
+                .arg(
+                    Arg::new("input")
+                        .help("Input directory containing Markdown files")
+                        .required(true)
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .help("Script file to write (.md or .txt); prints the script when omitted")
+                        .value_name("FILE")
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .help("Script format [default: from the output extension, else markdown]")
+                        .value_name("FORMAT")
+                        .value_parser(["markdown", "text"]),
+                )
+                .arg(
+                    Arg::new("wpm")
+                        .long("wpm")
+                        .help("Speaking rate used for the time estimates, in words per minute")
+                        .value_name("WORDS")
+                        .value_parser(clap::value_parser!(u32).range(1..))
+                        .default_value("130"),
+                )
+                .arg(
+                    Arg::new("recursive")
+                        .short('r')
+                        .long("recursive")
+                        .help("Process subdirectories recursively")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("template")
+                        .short('t')
+                        .long("template")
+                        .help("Template the deck would use: a built-in name or a .toml/.json file")
+                        .value_name("TEMPLATE")
+                        .default_value("default"),
+                )
+                .arg(
+                    Arg::new("reference-doc")
+                        .long("reference-doc")
+                        .help("Reference .pptx/.potx the deck would use")
+                        .value_name("FILE")
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("lang")
+                        .long("lang")
+                        .help("Language the deck would use (overrides front matter)")
+                        .value_name("TAG"),
+                )
+                .arg(
+                    Arg::new("title")
+                        .long("title")
+                        .help("Script title (overrides front matter)")
+                        .value_name("TITLE"),
+                )
+                .arg(
+                    Arg::new("title-slide")
+                        .long("title-slide")
+                        .help("Count the title slide the deck would start with")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("agenda")
+                        .long("agenda")
+                        .help("Count the agenda slides the deck would have")
+                        .value_name("SOURCE")
+                        .value_parser(["slides", "sections"])
+                        .num_args(0..=1)
+                        .default_missing_value("slides"),
+                ),
+        )
+        .subcommand(
+            Command::new("check")
+                .about("Check the deck built from a directory for problems")
+                .arg(
+                    Arg::new("input")
+                        .help("Input directory containing Markdown files")
+                        .required(true)
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("a11y")
+                        .long("a11y")
+                        .help("Check accessibility: alt text, slide titles, contrast and font sizes (the default)")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("template")
+                        .short('t')
+                        .long("template")
+                        .help("Template the deck would use: a built-in name or a .toml/.json file")
+                        .value_name("TEMPLATE")
+                        .default_value("default"),
+                )
+                .arg(
+                    Arg::new("reference-doc")
+                        .long("reference-doc")
+                        .help("Reference .pptx/.potx the deck would use")
+                        .value_name("FILE")
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("recursive")
+                        .short('r')
+                        .long("recursive")
+                        .help("Process subdirectories recursively")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("title-slide")
+                        .long("title-slide")
+                        .help("Include the title slide the deck would start with")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("lint")
+                .about("Check Markdown slides for content problems such as crowded slides")
+                .arg(
+                    Arg::new("input")
+                        .help("Input directory containing Markdown files")
+                        .required(true)
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .help("Output format")
+                        .value_name("FORMAT")
+                        .value_parser(["text", "json"])
+                        .default_value("text"),
+                )
+                .arg(
+                    Arg::new("config")
+                        .long("config")
+                        .help("Project config file [default: md2pptx.toml in the input directory]")
+                        .value_name("FILE")
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("recursive")
+                        .short('r')
+                        .long("recursive")
+                        .help("Process subdirectories recursively")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .arg(
+            Arg::new("input")
+                .help("Input directory containing Markdown files (write ./templates for a directory named like a subcommand)")
+                .required(true)
+                .index(1)
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
+        .arg(
+            Arg::new("output")
+                .help("Output PowerPoint file (.pptx) or output directory for separate files")
+                .required(true)
+                .index(2)
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
+        .arg(
+            Arg::new("template")
+                .short('t')
+                .long("template")
+                .help("Built-in template name or path to a .toml/.json template file")
+                .value_name("TEMPLATE")
+                .default_value("default"),
+        )
+        .arg(
+            Arg::new("reference-doc")
+                .long("reference-doc")
+                .help("Use the slide masters, layouts and theme of an existing .pptx or .potx")
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
+        .arg(
+            Arg::new("recursive")
+                .short('r')
+                .long("recursive")
+                .help("Process subdirectories recursively")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .help("Enable verbose output")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .help("Suppress all output except errors")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("verbose"),
+        )
+        .arg(
+            Arg::new("separate")
+                .short('s')
+                .long("separate")
+                .help("Generate separate PPTX file for each MD file (output must be directory)")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("title-slide")
+                .long("title-slide")
+                .help("Prepend a title slide built from the document title, subtitle, author and date")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("agenda")
+                .long("agenda")
+                .help("Insert linked agenda slides listing slide titles or sections")
+                .value_name("SOURCE")
+                .value_parser(["slides", "sections"])
+                .num_args(0..=1)
+                .default_missing_value("slides"),
+        )
+        .arg(
+            Arg::new("footer")
+                .long("footer")
+                .help("Footer text shown on every slide")
+                .value_name("TEXT"),
+        )
+        .arg(
+            Arg::new("slide-numbers")
+                .long("slide-numbers")
+                .help("Show slide numbers in the footer")
+                .action(clap::ArgAction::SetTrue)
+                .overrides_with("no-slide-numbers"),
+        )
+        .arg(
+            Arg::new("no-slide-numbers")
+                .long("no-slide-numbers")
+                .help("Hide slide numbers, even if the template or front matter shows them")
+                .action(clap::ArgAction::SetTrue)
+                .overrides_with("slide-numbers"),
+        )
+        .arg(
+            Arg::new("footer-date")
+                .long("footer-date")
+                .help("Show the date in the footer; without a value the date updates automatically")
+                .value_name("DATE")
+                .num_args(0..=1)
+                .default_missing_value("")
+                .overrides_with("no-footer-date"),
+        )
+        .arg(
+            Arg::new("no-footer-date")
+                .long("no-footer-date")
+                .help("Hide the footer date, even if the template or front matter shows it")
+                .action(clap::ArgAction::SetTrue)
+                .overrides_with("footer-date"),
+        )
+        .arg(
+            Arg::new("footer-on-title")
+                .long("footer-on-title")
+                .help("Also show the footer, date and slide number on title slides")
+                .action(clap::ArgAction::SetTrue)
+                .overrides_with("no-footer-on-title"),
+        )
+        .arg(
+            Arg::new("no-footer-on-title")
+                .long("no-footer-on-title")
+                .help("Keep the footer off title slides, even if the template or front matter shows it")
+                .action(clap::ArgAction::SetTrue)
+                .overrides_with("footer-on-title"),
+        )
+        .arg(
+            Arg::new("transition")
+                .long("transition")
+                .help("Transition between slides")
+                .value_name("TRANSITION")
+                .value_parser(["fade", "push", "wipe", "morph", "none"]),
+        )
+        .arg(
+            Arg::new("incremental")
+                .long("incremental")
+                .help("Reveal list items one click at a time")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("draft")
+                .long("draft")
+                .help("Put a diagonal DRAFT watermark on every slide")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("lang")
+                .long("lang")
+                .help("Document language as a BCP 47 tag, e.g. en-US, ja-JP or ar-SA")
+                .value_name("TAG"),
+        )
+        .arg(
+            Arg::new("slide-size")
+                .long("slide-size")
+                .help("Slide size: 4:3, 16:9, 16:10, a4 or WIDTHxHEIGHT in EMUs")
+                .value_name("SIZE")
+                .value_parser(|value: &str| {
+                    SlideSize::from_name(value)
+                        .ok_or_else(|| format!("unknown slide size '{}'", value))
+                }),
+        )
+        .arg(
+            Arg::new("title")
+                .long("title")
+                .help("Presentation title (overrides front matter)")
+                .value_name("TITLE"),
+        )
+        .arg(
+            Arg::new("subtitle")
+                .long("subtitle")
+                .help("Title slide subtitle (overrides the front matter description)")
+                .value_name("SUBTITLE"),
+        )
+        .arg(
+            Arg::new("author")
+                .long("author")
+                .help("Presentation author (overrides front matter)")
+                .value_name("AUTHOR"),
+        )
+        .arg(
+            Arg::new("date")
+                .long("date")
+                .help("Date shown on the title slide [default: today]")
+                .value_name("DATE"),
+        )
+        .arg(
+            Arg::new("strict")
+                .long("strict")
+                .help("Treat warnings as errors and write no output when there are any")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("message-format")
+                .long("message-format")
+                .help("How warnings are printed: text on stderr, or json on stdout with progress output turned off")
+                .value_name("FORMAT")
+                .value_parser(["text", "json"])
+                .default_value("text"),
+        )
+        .arg(
+            Arg::new("report")
+                .long("report")
+                .help("Write a JSON report of per-file results, outputs and timings")
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
+}
+
+/// `Some(true)` or `Some(false)` when the `on` or `off` flag is given, the
+/// last one winning, and `None` to keep the template and front matter setting
+fn switch(matches: &ArgMatches, on: &str, off: &str) -> Option<bool> {
+    if matches.get_flag(on) {
+        Some(true)
+    } else {
+        matches.get_flag(off).then_some(false)
+    }
+}
+
+/// Report an error with its location and hint, and exit with the code for
+/// its category
+fn fail(error: &Error) -> ! {
+    eprint!("{}", error.render());
+    std::process::exit(error.exit_code())
+}
+
+/// A conversion run, with the report to write when `--report` is given
+struct Run<'a> {
+    report: ConversionReport,
+    report_path: Option<&'a Path>,
+    started: Instant,
+}
+
+impl Run<'_> {
+    fn finish(&mut self) -> Result<()> {
+        self.report.elapsed_ms = elapsed_ms(self.started);
+        match self.report_path {
+            Some(path) => self.report.write(path),
+            None => Ok(()),
+        }
+    }
+
+    /// Write the report of a successful run, failing like any other error
+    /// when it cannot be written
+    fn complete(&mut self) {
+        if let Err(e) = self.finish() {
+            fail(&e)
+        }
+    }
+
+    /// Record the error in the report, write it, and fail
+    fn fail(&mut self, error: &Error) -> ! {
+        self.report.error = Some(error.into());
+        if let Err(e) = self.finish() {
+            eprint!("{}", e.render());
+        }
+        fail(error)
+    }
+}
+
+/// Print conversion warnings as `file:line:col: warning[CODE]: message` lines,
+/// or as a JSON array for tools. A failure to serialize fails the run.
+fn print_diagnostics(run: &mut Run, diagnostics: &Diagnostics, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(diagnostics) {
+            Ok(json) => println!("{}", json),
+            Err(e) => run.fail(&e.into()),
+        }
+    } else if !diagnostics.is_empty() {
+        for diagnostic in diagnostics.iter() {
+            eprintln!("{}", diagnostic);
+        }
+        eprintln!("{}", diagnostics);
+    }
+}
+
+/// Print the problems found and return how many there were
+fn run_check_command(matches: &ArgMatches) -> Result<usize> {
+    let input_dir = matches.get_one::<PathBuf>("input").unwrap();
+    let mut options = ConversionOptions::default();
+    options.presentation.title_slide = matches.get_flag("title-slide");
+    options.presentation.reference_doc = matches.get_one::<PathBuf>("reference-doc").cloned();
+    options.metadata_defaults = ProjectConfig::find(input_dir)?.metadata.to_metadata();
+
+    // Accessibility is the only check so far, so it also runs without --a11y
+    let issues = check_accessibility(
+        input_dir,
+        matches.get_one::<String>("template").unwrap(),
+        matches.get_flag("recursive"),
+        &options,
+        LogLevel::Quiet,
+    )?;
+    for issue in &issues {
+        println!("{}", issue);
+    }
+    match issues.len() {
+        0 => println!("No accessibility issues found"),
+        1 => println!("\n1 accessibility issue found"),
+        count => println!("\n{} accessibility issues found", count),
+    }
+    Ok(issues.len())
+}
+
+/// Print the lint findings and return the number of errors
+fn run_lint_command(matches: &ArgMatches) -> Result<usize> {
+    let input_dir = matches.get_one::<PathBuf>("input").unwrap();
+    let config = match matches.get_one::<PathBuf>("config") {
+        Some(path) => ProjectConfig::from_file(path)?,
+        None => ProjectConfig::find(input_dir)?,
+    };
+    let reports = lint_files(input_dir, matches.get_flag("recursive"), &config.lint)?;
+    let errors: usize = reports.iter().map(|r| r.count(Severity::Error)).sum();
+    let warnings: usize = reports.iter().map(|r| r.count(Severity::Warning)).sum();
+
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json") {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+        return Ok(errors);
+    }
+    for report in &reports {
+        for issue in &report.issues {
+            println!("{}:{}", report.file.display(), issue);
+        }
+    }
+    let plural = |count: usize, word: &str| {
+        format!("{} {}{}", count, word, if count == 1 { "" } else { "s" })
+    };
+    println!(
+        "{}{}, {} in {}",
+        if errors + warnings > 0 { "\n" } else { "" },
+        plural(errors, "error"),
+        plural(warnings, "warning"),
+        plural(reports.len(), "file")
+    );
+    Ok(errors)
+}
+
+fn run_script_command(matches: &ArgMatches) -> Result<()> {
+    let input_dir = matches.get_one::<PathBuf>("input").unwrap();
+    let output = matches.get_one::<PathBuf>("output");
+
+    let mut options = ConversionOptions::default();
+    options.presentation.title_slide = matches.get_flag("title-slide");
+    options.presentation.agenda = matches
+        .get_one::<String>("agenda")
+        .and_then(|source| AgendaSource::from_name(source));
+    options.presentation.reference_doc = matches.get_one::<PathBuf>("reference-doc").cloned();
+    options.presentation.language = matches.get_one::<String>("lang").cloned();
+    options.metadata.title = matches.get_one::<String>("title").cloned();
+    options.metadata_defaults = ProjectConfig::find(input_dir)?.metadata.to_metadata();
+    let script_options = ScriptOptions {
+        format: matches
+            .get_one::<String>("format")
+            .and_then(|name| ScriptFormat::from_name(name))
+            .or_else(|| output.and_then(|path| ScriptFormat::from_path(path)))
+            .unwrap_or_default(),
+        words_per_minute: *matches.get_one::<u32>("wpm").unwrap(),
+    };
+
+    let script = export_script(
+        input_dir,
+        matches.get_one::<String>("template").unwrap(),
+        matches.get_flag("recursive"),
+        &options,
+        &script_options,
+        LogLevel::Quiet,
+    )?;
+    match output {
+        Some(path) => {
+            file_io::write_file(path, script.as_bytes())?;
+            println!("Wrote presenter script to {}", path.display());
+        }
+        None => print!("{}", script),
+    }
+    Ok(())
+}
+
+fn run_template_command(matches: &ArgMatches) -> Result<()> {
+    match matches.subcommand() {
+        Some(("list", _)) => {
+            println!("Built-in templates:");
+            for name in BUILT_IN_TEMPLATES {
+                println!("  {}", name);
+            }
+
+            let Some(dir) = user_template_dir() else {
+                return Ok(());
+            };
+            println!("\nUser templates ({}):", dir.display());
+            let templates = user_templates();
+            if templates.is_empty() {
+                println!("  (none)");
+            }
+            for path in templates {
+                let name = path.file_stem().unwrap_or_default().to_string_lossy();
+                match CustomTemplate::from_file(&path) {
+                    Ok(_) => println!("  {}", name),
+                    Err(e) => println!("  {} (invalid: {})", name, e.message()),
+                }
+            }
+        }
+        Some(("show", show_matches)) => {
+            let template = SlideTemplate::load(show_matches.get_one::<String>("name").unwrap())?;
+            print_template(&template);
+        }
+        Some(("export", export_matches)) => {
+            let template =
+                SlideTemplate::load(export_matches.get_one::<String>("name").unwrap())?.to_custom();
+            write_template(&template, export_matches.get_one::<PathBuf>("output"))?;
+        }
+        Some(("extract", extract_matches)) => {
+            let presentation = extract_matches.get_one::<PathBuf>("presentation").unwrap();
+            let template = extract_template(presentation)?;
+            write_template(&template, extract_matches.get_one::<PathBuf>("output"))?;
+        }
+        Some(("palette", palette_matches)) => {
+            let mut custom = SlideTemplate::Default.to_custom();
+            custom.name = palette_matches.get_one::<String>("name").unwrap().clone();
+            custom.theme_colors = generate_palette(
+                palette_matches.get_one::<String>("primary").unwrap(),
+                palette_matches
+                    .get_one::<String>("secondary")
+                    .map(String::as_str),
+            )?;
+            let template = SlideTemplate::Custom(custom.clone());
+
+            // Keep stdout clean for the TOML when no file is given
+            match palette_matches.get_one::<PathBuf>("output") {
+                Some(output) => {
+                    write_template(&custom, Some(output))?;
+                    print_contrast(&template);
+                }
+                None => {
+                    print!("{}", custom.to_toml()?);
+                    for check in check_template_contrast(&template) {
+                        if !check.passes() {
+                            eprintln!(
+                                "Warning: {} on {} has contrast {:.2}:1, below {}:1",
+                                check.foreground, check.background, check.ratio, check.required
+                            );
+                        }
+                    }
+                }
             }
         }
+        _ => {}
     }
+    Ok(())
+}
 
+/// Save a template to `output`, or print it as TOML
+fn write_template(template: &CustomTemplate, output: Option<&PathBuf>) -> Result<()> {
+    match output {
+        Some(output) => {
+            template.save(output)?;
+            println!("Wrote template '{}' to {}", template.name, output.display());
+        }
+        None => print!("{}", template.to_toml()?),
+    }
     Ok(())
 }
+
+fn print_template(template: &SlideTemplate) {
+    let inches = |emu: i32| f64::from(emu) / 914400.0;
+    let custom = template.to_custom();
+    let colors = &custom.theme_colors;
+    let fonts = &custom.fonts;
+    let layout = &custom.layout_settings;
+
+    println!("Template: {}", custom.name);
+    println!("\nColors:");
+    for (field, value) in colors.fields() {
+        println!("  {:<22}#{}", field.replace('_', " "), value);
+    }
+    if let Some(background) = &custom.background {
+        println!("  {:<22}{}", "deck background", background);
+    }
+    if let Some(logo) = &custom.logo {
+        println!("\nLogo:");
+        println!("  {:<16}{}", "path", logo.path.display());
+        println!("  {:<16}{}", "position", logo.position.name());
+        println!("  {:<16}{:.2} in", "width", inches(logo.width));
+        println!("  {:<16}{}%", "opacity", logo.opacity);
+    }
+    if let Some(watermark) = &custom.watermark {
+        println!("\nWatermark: {}", watermark);
+    }
+    println!("\nFonts:");
+    println!("  {:<16}{}", "title", fonts.title_font);
+    println!("  {:<16}{}", "body", fonts.body_font);
+    println!("  {:<16}{}", "code", fonts.code_font);
+    if !fonts.east_asian_font.is_empty() {
+        println!("  {:<16}{}", "east asian", fonts.east_asian_font);
+    }
+    if !fonts.complex_script_font.is_empty() {
+        println!("  {:<16}{}", "complex script", fonts.complex_script_font);
+    }
+    println!("\nLayout:");
+    println!(
+        "  {:<16}{:.2} x {:.2} in ({})",
+        "slide size",
+        inches(layout.slide_width),
+        inches(layout.slide_height),
+        layout.slide_size().label()
+    );
+    for (label, value) in [
+        ("margin top", layout.margin_top),
+        ("margin bottom", layout.margin_bottom),
+        ("margin left", layout.margin_left),
+        ("margin right", layout.margin_right),
+        ("title height", layout.title_height),
+        ("content spacing", layout.content_spacing),
+    ] {
+        println!("  {:<16}{:.2} in", label, inches(value));
+    }
+    print_contrast(template);
+}
+
+/// WCAG contrast of the template's text and accent colors
+fn print_contrast(template: &SlideTemplate) {
+    println!("\nContrast:");
+    for check in check_template_contrast(template) {
+        println!(
+            "  {:<42}{:>6.2}:1  {}",
+            format!("{} on {}", check.foreground, check.background),
+            check.ratio,
+            if check.passes() {
+                "ok".to_string()
+            } else {
+                format!("FAIL (needs {}:1)", check.required)
+            }
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_named_like_a_subcommand() {
+        // A bare `templates` is the subcommand, which needs one of its own
+        let err = cli()
+            .try_get_matches_from(["md2pptx", "templates", "out.pptx"])
+            .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::InvalidSubcommand);
+        assert!(cli()
+            .try_get_matches_from(["md2pptx", "template", "list"])
+            .is_ok());
+
+        // Written as a path it is the input directory
+        let matches = cli()
+            .try_get_matches_from(["md2pptx", "./templates", "out.pptx"])
+            .unwrap();
+        assert!(matches.subcommand().is_none());
+        assert_eq!(
+            matches.get_one::<PathBuf>("input"),
+            Some(&PathBuf::from("./templates"))
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use crate::parser::markdown::{MarkdownDocument, Slide, SlideElement, SourceSpan, Spanned};
+use crate::utils::error::Error;
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// How a rule's findings are reported. Errors make `md2pptx lint` fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Off,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Off => "off",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintRule {
+    TooManyBullets,
+    TooManyWords,
+    ListTooDeep,
+    MissingTitle,
+    DuplicateTitle,
+    LongCodeBlock,
+    EmptySlide,
+}
+
+impl LintRule {
+    pub const ALL: [LintRule; 7] = [
+        LintRule::TooManyBullets,
+        LintRule::TooManyWords,
+        LintRule::ListTooDeep,
+        LintRule::MissingTitle,
+        LintRule::DuplicateTitle,
+        LintRule::LongCodeBlock,
+        LintRule::EmptySlide,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintRule::TooManyBullets => "too-many-bullets",
+            LintRule::TooManyWords => "too-many-words",
+            LintRule::ListTooDeep => "list-too-deep",
+            LintRule::MissingTitle => "missing-title",
+            LintRule::DuplicateTitle => "duplicate-title",
+            LintRule::LongCodeBlock => "long-code-block",
+            LintRule::EmptySlide => "empty-slide",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|rule| rule.name() == name)
+    }
+
+    pub fn default_severity(&self) -> Severity {
+        match self {
+            LintRule::MissingTitle => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+/// `[lint]` table of the project config: rule limits and per-rule severities
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintConfig {
+    /// List items per slide
+    pub max_bullets: usize,
+    /// Words of visible text per slide, code excluded
+    pub max_words: usize,
+    /// List nesting levels
+    pub max_list_depth: usize,
+    /// Lines per code block
+    pub max_code_lines: usize,
+    /// Severity by rule name, e.g. `duplicate-title = "error"`
+    pub rules: BTreeMap<String, Severity>,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            max_bullets: 6,
+            max_words: 80,
+            max_list_depth: 2,
+            max_code_lines: 15,
+            rules: BTreeMap::new(),
+        }
+    }
+}
+
+impl LintConfig {
+    pub fn severity(&self, rule: LintRule) -> Severity {
+        self.rules
+            .get(rule.name())
+            .copied()
+            .unwrap_or_else(|| rule.default_severity())
+    }
+
+    /// Reject rule names that do not exist, so typos do not go unnoticed
+    pub fn validate(&self) -> Result<()> {
+        match self
+            .rules
+            .keys()
+            .find(|name| LintRule::from_name(name).is_none())
+        {
+            Some(name) => Err(Error::configuration(format!(
+                "unknown lint rule '{}'; expected one of {}",
+                name,
+                LintRule::ALL.map(|rule| rule.name()).join(", ")
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// One finding, located by 1-based slide number and source line and column
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LintIssue {
+    pub rule: LintRule,
+    pub severity: Severity,
+    pub slide: usize,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {} [{}]",
+            self.line,
+            self.column,
+            self.severity.name(),
+            self.message,
+            self.rule.name()
+        )
+    }
+}
+
+/// Check a parsed document against the configured rules. Slide-wide
+/// findings point at the slide's start, the others at the element.
+pub fn lint_document(document: &MarkdownDocument, config: &LintConfig) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    let mut first_use: HashMap<String, usize> = HashMap::new();
+
+    for (index, slide) in document.slides.iter().enumerate() {
+        let number = index + 1;
+        let mut report = |rule: LintRule, span: &SourceSpan, message: String| {
+            let severity = config.severity(rule);
+            if severity != Severity::Off {
+                issues.push(LintIssue {
+                    rule,
+                    severity,
+                    slide: number,
+                    line: span.line,
+                    column: span.column,
+                    message,
+                });
+            }
+        };
+
+        match slide.title.as_deref().map(str::trim) {
+            None | Some("") => report(
+                LintRule::MissingTitle,
+                &slide.span,
+                "slide has no title".to_string(),
+            ),
+            Some(title) => match first_use.get(&title.to_lowercase()) {
+                Some(first) => report(
+                    LintRule::DuplicateTitle,
+                    &slide.span,
+                    format!("title \"{}\" is also used by slide {}", title, first),
+                ),
+                None => {
+                    first_use.insert(title.to_lowercase(), number);
+                }
+            },
+        }
+        if slide.content.is_empty() {
+            report(
+                LintRule::EmptySlide,
+                &slide.span,
+                "slide has no content".to_string(),
+            );
+        }
+
+        let bullets: usize = slide
+            .content
+            .iter()
+            .map(|element| match &element.node {
+                SlideElement::List { items, .. } => items.len(),
+                _ => 0,
+            })
+            .sum();
+        if bullets > config.max_bullets {
+            report(
+                LintRule::TooManyBullets,
+                &slide.span,
+                format!("{} bullets, more than {}", bullets, config.max_bullets),
+            );
+        }
+
+        let words = slide_words(slide);
+        if words > config.max_words {
+            report(
+                LintRule::TooManyWords,
+                &slide.span,
+                format!("{} words, more than {}", words, config.max_words),
+            );
+        }
+
+        for Spanned { node, span } in &slide.content {
+            match node {
+                SlideElement::List { depth, .. } if *depth > config.max_list_depth => report(
+                    LintRule::ListTooDeep,
+                    span,
+                    format!(
+                        "list nested {} levels deep, more than {}",
+                        depth, config.max_list_depth
+                    ),
+                ),
+                SlideElement::CodeBlock { code, .. } => {
+                    let code_lines = code.trim_end().lines().count();
+                    if code_lines > config.max_code_lines {
+                        report(
+                            LintRule::LongCodeBlock,
+                            span,
+                            format!(
+                                "code block of {} lines, more than {}",
+                                code_lines, config.max_code_lines
+                            ),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    issues
+}
+
+/// Words of visible text on a slide: its title and everything but code
+fn slide_words(slide: &Slide) -> usize {
+    let count = |text: &str| {
+        text.split_whitespace()
+            .filter(|word| word.chars().any(char::is_alphanumeric))
+            .count()
+    };
+    let content: usize = slide
+        .content
+        .iter()
+        .map(|element| match &element.node {
+            SlideElement::Heading { text, .. } => count(text),
+            SlideElement::Paragraph { text }
+            | SlideElement::Quote { text }
+            | SlideElement::Callout { text, .. } => count(&text.to_string()),
+            SlideElement::List { items, .. } => {
+                items.iter().map(|item| count(&item.to_string())).sum()
+            }
+            SlideElement::Table { headers, rows } => headers
+                .iter()
+                .chain(rows.iter().flatten())
+                .map(|cell| count(&cell.to_string()))
+                .sum(),
+            SlideElement::Image { alt_text, .. } => count(alt_text),
+            SlideElement::CodeBlock { .. } => 0,
+        })
+        .sum();
+    slide.title.as_deref().map_or(0, count) + content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_rules_and_locations() {
+        let source = "---\ntitle: Demo\n---\nIntro text\n\n# Plan\n\n- a\n- b\n  - c\n    - d\n\n# Plan\n\n```\n1\n2\n3\n```\n\n# Empty\n";
+        let document = MarkdownDocument::parse(source).unwrap();
+        let config = LintConfig {
+            max_bullets: 3,
+            max_code_lines: 2,
+            ..Default::default()
+        };
+        let found: Vec<(LintRule, usize, usize)> = lint_document(&document, &config)
+            .iter()
+            .map(|issue| (issue.rule, issue.slide, issue.line))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (LintRule::MissingTitle, 1, 4),
+                (LintRule::TooManyBullets, 2, 6),
+                (LintRule::ListTooDeep, 2, 8),
+                (LintRule::DuplicateTitle, 3, 13),
+                (LintRule::LongCodeBlock, 3, 15),
+                (LintRule::EmptySlide, 4, 21),
+            ]
+        );
+
+        let mut config = LintConfig::default();
+        config
+            .rules
+            .insert("missing-title".to_string(), Severity::Off);
+        config
+            .rules
+            .insert("empty-slide".to_string(), Severity::Error);
+        let issues = lint_document(&document, &config);
+        assert!(issues
+            .iter()
+            .all(|issue| issue.rule != LintRule::MissingTitle));
+        assert_eq!(issues.last().unwrap().severity, Severity::Error);
+        assert_eq!(
+            issues.last().unwrap().to_string(),
+            "21:1: error: slide has no content [empty-slide]"
+        );
+
+        config.rules.insert("too-long".to_string(), Severity::Error);
+        assert!(config.validate().is_err());
+    }
+}
@@ -1,18 +1,123 @@
+use crate::utils::diagnostics::{Diagnostics, WarningCode};
 use crate::utils::error::Error;
 use crate::Result;
-use pulldown_cmark::{Event, HeadingLevel, Parser, Tag};
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag};
+use serde::Serialize;
 use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+use std::path::PathBuf;
 
 #[derive(Debug, Clone)]
 pub struct MarkdownDocument {
     pub slides: Vec<Slide>,
     pub metadata: DocumentMetadata,
+    pub sections: Vec<Section>,
 }
 
-#[derive(Debug, Clone)]
+/// A named run of slides, written as a PowerPoint section.
+/// A section covers slides from `start_slide` up to the next section's start.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub name: String,
+    pub start_slide: usize,
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct Slide {
     pub title: Option<String>,
-    pub content: Vec<SlideElement>,
+    pub content: Vec<Spanned<SlideElement>>,
+    /// Per-slide settings from `<!-- key: value -->` comments, e.g.
+    /// `transition: fade` or a bare `incremental`
+    pub directives: HashMap<String, String>,
+    /// Speaker notes from `<!-- notes: ... -->` comments, one entry per
+    /// paragraph
+    pub notes: Vec<String>,
+    /// Source of the whole slide, from its heading to its last element
+    pub span: SourceSpan,
+    /// Markdown file the slide was read from, set when files are combined
+    pub source: Option<PathBuf>,
+}
+
+impl Slide {
+    /// Add an element and the source it was parsed from
+    pub fn push(&mut self, element: SlideElement, span: SourceSpan) {
+        if self.title.is_none() && self.content.is_empty() {
+            self.span = span;
+        } else {
+            self.span.end = self.span.end.max(span.end);
+        }
+        self.content.push(Spanned {
+            node: element,
+            span,
+        });
+    }
+
+    /// `file.md:42:3` for a span of this slide, or `42:3` when the file is
+    /// not known
+    pub fn location(&self, span: &SourceSpan) -> String {
+        match &self.source {
+            Some(file) => format!("{}:{}:{}", file.display(), span.line, span.column),
+            None => format!("{}:{}", span.line, span.column),
+        }
+    }
+}
+
+/// Where parsed Markdown came from: byte offsets into the source text and
+/// the 1-based line and column of the start
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SourceSpan {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SourceSpan {
+    /// Span of a byte range of `source`
+    pub fn locate(source: &str, range: Range<usize>) -> Self {
+        LineIndex::new(source).span(0, &range)
+    }
+}
+
+/// A parsed value and the source it was parsed from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<T> {
+    pub node: T,
+    pub span: SourceSpan,
+}
+
+/// Line start offsets of a source text, for turning byte ranges into spans
+struct LineIndex<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    fn new(source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(offset, _)| offset + 1))
+            .collect();
+        Self {
+            source,
+            line_starts,
+        }
+    }
+
+    /// Span of `range`, given relative to the text starting at `base`
+    fn span(&self, base: usize, range: &Range<usize>) -> SourceSpan {
+        let start = base + range.start;
+        let line = self
+            .line_starts
+            .partition_point(|&line_start| line_start <= start);
+        let line_start = self.line_starts[line - 1];
+        SourceSpan {
+            start,
+            end: base + range.end,
+            line,
+            column: self.source[line_start..start].chars().count() + 1,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -22,11 +127,16 @@ pub enum SlideElement {
         text: String,
     },
     Paragraph {
-        text: String,
+        text: InlineText,
     },
     List {
-        items: Vec<String>,
+        items: Vec<InlineText>,
         ordered: bool,
+        /// Reveal items one at a time (Pandoc-style `> - item` lists)
+        incremental: bool,
+        /// Deepest nesting level, 1 for a flat list. Nested items are
+        /// flattened into `items`.
+        depth: usize,
     },
     CodeBlock {
         language: Option<String>,
@@ -35,14 +145,156 @@ pub enum SlideElement {
     Image {
         alt_text: String,
         url: String,
+        /// Marked with the title `"decorative"`: ignored by screen readers
+        decorative: bool,
     },
     Table {
-        headers: Vec<String>,
-        rows: Vec<Vec<String>>,
+        headers: Vec<InlineText>,
+        rows: Vec<Vec<InlineText>>,
     },
     Quote {
-        text: String,
+        text: InlineText,
     },
+    Callout {
+        kind: CalloutKind,
+        text: InlineText,
+    },
+}
+
+/// Text of a paragraph, list item, quote or table cell as a sequence of runs.
+/// Displays as its plain text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InlineText {
+    pub runs: Vec<Inline>,
+}
+
+/// A run of text and the link and `<span lang>` it sits in
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Inline {
+    pub text: String,
+    /// Link target as written, e.g. `https://example.com` or `#setup`
+    pub link: Option<String>,
+    /// `lang` attribute of the innermost `<span>` that sets one
+    pub lang: Option<String>,
+}
+
+impl InlineText {
+    pub fn is_empty(&self) -> bool {
+        self.runs.iter().all(|run| run.text.is_empty())
+    }
+
+    /// Runs with the whitespace around the whole text removed
+    fn trimmed(mut runs: Vec<Inline>) -> Self {
+        while let Some(first) = runs.first_mut() {
+            first.text = first.text.trim_start().to_string();
+            if !first.text.is_empty() {
+                break;
+            }
+            runs.remove(0);
+        }
+        while let Some(last) = runs.last_mut() {
+            last.text.truncate(last.text.trim_end().len());
+            if !last.text.is_empty() {
+                break;
+            }
+            runs.pop();
+        }
+        Self { runs }
+    }
+}
+
+impl From<&str> for InlineText {
+    /// A single run without a link or language
+    fn from(text: &str) -> Self {
+        Self::trimmed(vec![Inline {
+            text: text.to_string(),
+            ..Default::default()
+        }])
+    }
+}
+
+impl fmt::Display for InlineText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.runs.iter().try_for_each(|run| f.write_str(&run.text))
+    }
+}
+
+/// Collects inline events into runs, tracking the open link and `<span>` tags
+#[derive(Default)]
+struct InlineCollector {
+    runs: Vec<Inline>,
+    link: Option<String>,
+    /// Language of each open `<span>`, `None` for spans without one
+    spans: Vec<Option<String>>,
+}
+
+impl InlineCollector {
+    fn push_str(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let lang = self.spans.iter().rev().find_map(Clone::clone);
+        match self.runs.last_mut() {
+            Some(run) if run.link == self.link && run.lang == lang => run.text.push_str(text),
+            _ => self.runs.push(Inline {
+                text: text.to_string(),
+                link: self.link.clone(),
+                lang,
+            }),
+        }
+    }
+
+    /// Inline code keeps its backticks
+    fn push_code(&mut self, code: &str) {
+        self.push_str(&format!("`{}`", code));
+    }
+
+    /// Open or close a `<span>` tag
+    fn span_tag(&mut self, html: &str) {
+        if html.trim() == "</span>" {
+            self.spans.pop();
+        } else {
+            self.spans.push(span_language(html));
+        }
+    }
+
+    /// The runs collected so far, trimmed, leaving the collector empty
+    fn take(&mut self) -> InlineText {
+        InlineText::trimmed(std::mem::take(&mut self.runs))
+    }
+}
+
+/// GitHub-style alert kinds recognised in `> [!KIND]` blockquotes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalloutKind {
+    Note,
+    Tip,
+    Important,
+    Warning,
+    Caution,
+}
+
+impl CalloutKind {
+    pub fn from_marker(marker: &str) -> Option<Self> {
+        match marker.to_uppercase().as_str() {
+            "NOTE" => Some(CalloutKind::Note),
+            "TIP" => Some(CalloutKind::Tip),
+            "IMPORTANT" => Some(CalloutKind::Important),
+            "WARNING" => Some(CalloutKind::Warning),
+            "CAUTION" => Some(CalloutKind::Caution),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CalloutKind::Note => "Note",
+            CalloutKind::Tip => "Tip",
+            CalloutKind::Important => "Important",
+            CalloutKind::Warning => "Warning",
+            CalloutKind::Caution => "Caution",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -50,31 +302,50 @@ pub struct DocumentMetadata {
     pub title: Option<String>,
     pub author: Option<String>,
     pub description: Option<String>,
+    pub date: Option<String>,
     pub custom_properties: HashMap<String, String>,
 }
 
 impl MarkdownDocument {
     pub fn parse(markdown_content: &str) -> Result<Self> {
-        let parser = Parser::new(markdown_content);
+        Self::parse_with_diagnostics(markdown_content).map(|(document, _)| document)
+    }
+
+    /// Parse, also returning warnings about content the slides cannot show
+    pub fn parse_with_diagnostics(markdown_content: &str) -> Result<(Self, Diagnostics)> {
+        let line_index = LineIndex::new(markdown_content);
+        let (front_matter, body) = split_front_matter(markdown_content);
+        // Offsets from the parser are relative to the text after front matter
+        let body_start = markdown_content.len() - body.len();
+        let parser = Parser::new_ext(body, Options::ENABLE_TABLES | Options::ENABLE_FOOTNOTES);
         let mut document = MarkdownDocument {
             slides: Vec::new(),
             metadata: DocumentMetadata::default(),
+            sections: Vec::new(),
         };
 
-        let mut current_slide = Slide {
-            title: None,
-            content: Vec::new(),
-        };
+        let mut current_slide = Slide::default();
 
-        let events: Vec<Event> = parser.collect();
+        let (events, ranges): (Vec<Event>, Vec<Range<usize>>) = parser.into_offset_iter().unzip();
+        let diagnostics =
+            dropped_content(&events, |index| line_index.span(body_start, &ranges[index]));
         let mut i = 0;
 
-        // Extract metadata from front matter if present
-        if let Some(title) = extract_title_from_events(&events) {
-            document.metadata.title = Some(title);
+        // Front matter wins; otherwise fall back to the first H1 as the title
+        if let Some(front_matter) = front_matter {
+            document.metadata = front_matter;
         }
+        if document.metadata.title.is_none() {
+            document.metadata.title = extract_title_from_events(&events);
+        }
+
+        // When H2 headings are used for slides, each H1 opens a new section
+        let h1_sections = events
+            .iter()
+            .any(|e| matches!(e, Event::Start(Tag::Heading(HeadingLevel::H2, _, _))));
 
         while i < events.len() {
+            let span = line_index.span(body_start, &ranges[i]);
             match &events[i] {
                 Event::Start(Tag::Heading(level, _, _)) => {
                     let heading_text = extract_text_from_heading(&events, &mut i)?;
@@ -84,23 +355,25 @@ impl MarkdownDocument {
                             // H1 creates a new slide with title
                             if !current_slide.content.is_empty() || current_slide.title.is_some() {
                                 document.slides.push(current_slide);
-                                current_slide = Slide {
-                                    title: None,
-                                    content: Vec::new(),
-                                };
+                                current_slide = Slide::default();
+                            }
+                            if h1_sections {
+                                document.sections.push(Section {
+                                    name: heading_text.clone(),
+                                    start_slide: document.slides.len(),
+                                });
                             }
                             current_slide.title = Some(heading_text);
+                            current_slide.span = span;
                         }
                         HeadingLevel::H2 => {
                             // H2 also creates a new slide with title
                             if !current_slide.content.is_empty() || current_slide.title.is_some() {
                                 document.slides.push(current_slide);
-                                current_slide = Slide {
-                                    title: None,
-                                    content: Vec::new(),
-                                };
+                                current_slide = Slide::default();
                             }
                             current_slide.title = Some(heading_text);
+                            current_slide.span = span;
                         }
                         level => {
                             // H3+ becomes content within the slide
@@ -112,52 +385,108 @@ impl MarkdownDocument {
                                 HeadingLevel::H5 => 5,
                                 HeadingLevel::H6 => 6,
                             };
-                            current_slide.content.push(SlideElement::Heading {
-                                level: level_num,
-                                text: heading_text,
-                            });
+                            current_slide.push(
+                                SlideElement::Heading {
+                                    level: level_num,
+                                    text: heading_text,
+                                },
+                                span,
+                            );
                         }
                     }
                 }
+                Event::Start(Tag::Paragraph) if is_image_paragraph(&events, i) => {
+                    i += 1;
+                    current_slide.push(extract_image(&events, &mut i), span);
+                    i += 1; // Skip the End(Paragraph) event
+                }
                 Event::Start(Tag::Paragraph) => {
                     let paragraph_text = extract_paragraph_text(&events, &mut i)?;
-                    if !paragraph_text.trim().is_empty() {
-                        current_slide.content.push(SlideElement::Paragraph {
-                            text: paragraph_text,
-                        });
+                    if !paragraph_text.is_empty() {
+                        current_slide.push(
+                            SlideElement::Paragraph {
+                                text: paragraph_text,
+                            },
+                            span,
+                        );
                     }
                 }
                 Event::Start(Tag::List(start_num)) => {
-                    let (items, _ordered) = extract_list_items(&events, &mut i)?;
-                    current_slide.content.push(SlideElement::List {
-                        items,
-                        ordered: start_num.is_some(),
-                    });
+                    let (items, depth) = extract_list_items(&events, &mut i)?;
+                    current_slide.push(
+                        SlideElement::List {
+                            items,
+                            ordered: start_num.is_some(),
+                            incremental: false,
+                            depth,
+                        },
+                        span,
+                    );
+                }
+                Event::Html(html) => {
+                    // HTML blocks arrive a line at a time; gather the whole comment
+                    let mut comment = html.to_string();
+                    if comment.trim_start().starts_with("<!--") {
+                        while !comment.contains("-->") {
+                            let Some(Event::Html(next)) = events.get(i + 1) else {
+                                break;
+                            };
+                            comment.push_str(next);
+                            i += 1;
+                        }
+                    }
+                    if let Some(notes) = parse_notes(&comment) {
+                        current_slide.notes.extend(notes);
+                    } else if let Some((key, value)) = parse_directive(&comment) {
+                        current_slide.directives.insert(key, value);
+                    }
+                    i += 1;
                 }
                 Event::Start(Tag::CodeBlock(kind)) => {
                     let (code, language) = extract_code_block(&events, &mut i, kind.clone())?;
-                    current_slide
-                        .content
-                        .push(SlideElement::CodeBlock { language, code });
+                    current_slide.push(SlideElement::CodeBlock { language, code }, span);
                 }
-                Event::Start(Tag::Image(_, url, alt)) => {
-                    current_slide.content.push(SlideElement::Image {
-                        alt_text: alt.to_string(),
-                        url: url.to_string(),
-                    });
-                    i += 1; // Skip the image event
+                Event::Start(Tag::Image(..)) => {
+                    current_slide.push(extract_image(&events, &mut i), span);
                 }
                 Event::Start(Tag::BlockQuote) => {
-                    let quote_text = extract_quote_text(&events, &mut i)?;
-                    current_slide
-                        .content
-                        .push(SlideElement::Quote { text: quote_text });
+                    if let Some(end) = quoted_list_end(&events, i) {
+                        // Pandoc treats a list inside a blockquote as an incremental list
+                        let ordered = matches!(events[i + 1], Event::Start(Tag::List(Some(_))));
+                        let mut list_index = i + 1;
+                        let (items, depth) = extract_list_items(&events, &mut list_index)?;
+                        current_slide.push(
+                            SlideElement::List {
+                                items,
+                                ordered,
+                                incremental: true,
+                                depth,
+                            },
+                            span,
+                        );
+                        i = end + 1;
+                    } else {
+                        let quote_text = extract_quote_text(&events, &mut i)?;
+                        let element = match split_callout_marker(&quote_text) {
+                            Some((kind, text)) => SlideElement::Callout { kind, text },
+                            None => SlideElement::Quote { text: quote_text },
+                        };
+                        current_slide.push(element, span);
+                    }
                 }
                 Event::Start(Tag::Table(_)) => {
                     let (headers, rows) = extract_table_data(&events, &mut i)?;
-                    current_slide
-                        .content
-                        .push(SlideElement::Table { headers, rows });
+                    current_slide.push(SlideElement::Table { headers, rows }, span);
+                }
+                Event::Start(Tag::FootnoteDefinition(_)) => {
+                    // Footnotes have no place on a slide; skip the whole definition
+                    while !matches!(
+                        events.get(i),
+                        Some(Event::End(Tag::FootnoteDefinition(_))) | None
+                    ) {
+                        i += 1;
+                    }
+                    i += 1;
                 }
                 _ => {
                     i += 1;
@@ -171,15 +500,174 @@ impl MarkdownDocument {
         }
 
         if document.slides.is_empty() {
-            return Err(Error::markdown_parsing(
-                "No slides found in markdown document",
-            ));
+            return Err(
+                Error::markdown_parsing("No slides found in markdown document")
+                    .with_hint("start each slide with a `#` or `##` heading"),
+            );
         }
 
-        Ok(document)
+        Ok((document, diagnostics))
     }
 }
 
+/// Warn about Markdown the slides cannot show: content the extraction below
+/// drops, or flattens to plain text
+fn dropped_content(events: &[Event], span: impl Fn(usize) -> SourceSpan) -> Diagnostics {
+    let mut diagnostics = Diagnostics::default();
+    let mut warn = |code, index, message: String| {
+        diagnostics.warn(code, None, Some(span(index)), message);
+    };
+    let mut open: Vec<&Tag> = Vec::new();
+    let mut in_comment = false;
+    let mut html_block_end = None;
+
+    for (index, event) in events.iter().enumerate() {
+        match event {
+            Event::Start(tag) => {
+                match tag {
+                    Tag::Image(_, url, _) => {
+                        let own_element = open.is_empty()
+                            || (open.len() == 1
+                                && matches!(events[index - 1], Event::Start(Tag::Paragraph))
+                                && is_image_paragraph(events, index - 1));
+                        if !own_element {
+                            warn(
+                                WarningCode::InlineImage,
+                                index,
+                                format!(
+                                    "image {} is inside other content, so only its alt text is kept",
+                                    url
+                                ),
+                            );
+                        }
+                    }
+                    Tag::Link(_, url, _)
+                        if open
+                            .iter()
+                            .any(|tag| matches!(tag, Tag::Heading(..) | Tag::TableCell)) =>
+                    {
+                        warn(
+                            WarningCode::LinkDropped,
+                            index,
+                            format!(
+                                "link to {} in a heading or table cell keeps only its text",
+                                url
+                            ),
+                        );
+                    }
+                    Tag::FootnoteDefinition(label) => warn(
+                        WarningCode::FootnoteDropped,
+                        index,
+                        format!("footnote [^{}] is not shown on slides", label),
+                    ),
+                    _ => {}
+                }
+                open.push(tag);
+            }
+            Event::End(_) => {
+                open.pop();
+            }
+            Event::FootnoteReference(label) => warn(
+                WarningCode::FootnoteDropped,
+                index,
+                format!("footnote reference [^{}] was dropped", label),
+            ),
+            Event::Rule => warn(
+                WarningCode::UnsupportedElement,
+                index,
+                "horizontal rule was ignored; slides start at # and ## headings".to_string(),
+            ),
+            Event::Html(html) => {
+                let html = html.trim();
+                if in_comment || html.starts_with("<!--") {
+                    // Comments hold notes and directives, or are meant to be hidden
+                    in_comment = !html.ends_with("-->");
+                } else if !html.is_empty() && !is_span_tag(html) {
+                    // Block HTML arrives a line at a time; report it once
+                    if html_block_end != Some(index) {
+                        let tag = html.lines().next().unwrap_or_default();
+                        warn(
+                            WarningCode::HtmlDropped,
+                            index,
+                            format!("HTML {} is not supported and was dropped", tag),
+                        );
+                    }
+                    html_block_end = Some(index + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    diagnostics
+}
+
+/// Split a leading `---` delimited front matter block off the document.
+///
+/// Only flat `key: value` pairs are understood; `title`, `author`, `date` and
+/// `description` (or `subtitle`) map to their metadata fields and every other
+/// key lands in `custom_properties`. A block with any other line, such as a
+/// leading thematic break followed by slide text, is not front matter, and
+/// content without front matter is returned unchanged.
+pub fn split_front_matter(content: &str) -> (Option<DocumentMetadata>, &str) {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let Some(rest) = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    else {
+        return (None, content);
+    };
+
+    let mut metadata = DocumentMetadata::default();
+    let mut has_keys = false;
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        offset += line.len();
+        let line = line.trim_end();
+        if line == "---" || line == "..." {
+            if !has_keys {
+                break;
+            }
+            return (Some(metadata), &rest[offset..]);
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some((key, value)) = line
+            .split_once(':')
+            .filter(|(key, _)| is_front_matter_key(key.trim_end()))
+        else {
+            break;
+        };
+        has_keys = true;
+        let key = key.trim_end().to_lowercase();
+        let value = value
+            .trim()
+            .trim_matches(|c| c == '"' || c == '\'')
+            .to_string();
+        match key.as_str() {
+            "title" => metadata.title = Some(value),
+            "author" => metadata.author = Some(value),
+            "description" | "subtitle" => metadata.description = Some(value),
+            "date" => metadata.date = Some(value),
+            _ => {
+                metadata.custom_properties.insert(key, value);
+            }
+        }
+    }
+
+    // An unterminated block, or one with anything but keys, is not front matter
+    (None, content)
+}
+
+/// A flat front matter key such as `title` or `review_date`
+fn is_front_matter_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
 fn extract_title_from_events(events: &[Event]) -> Option<String> {
     for (i, event) in events.iter().enumerate() {
         if let Event::Start(Tag::Heading(level, _, _)) = event {
@@ -213,22 +701,66 @@ fn extract_text_from_heading(events: &[Event], index: &mut usize) -> Result<Stri
     Ok(text)
 }
 
-fn extract_paragraph_text(events: &[Event], index: &mut usize) -> Result<String> {
+/// Whether the paragraph starting at `index` holds a single image and nothing else
+fn is_image_paragraph(events: &[Event], index: usize) -> bool {
+    if !matches!(events.get(index + 1), Some(Event::Start(Tag::Image(..)))) {
+        return false;
+    }
+    let Some(end) = events[index + 1..]
+        .iter()
+        .position(|event| matches!(event, Event::End(Tag::Image(..))))
+    else {
+        return false;
+    };
+    matches!(
+        events.get(index + 1 + end + 1),
+        Some(Event::End(Tag::Paragraph))
+    )
+}
+
+/// Read an image from its Start(Image) event to just past its End(Image).
+/// The alt text is the image's inline text.
+fn extract_image(events: &[Event], index: &mut usize) -> SlideElement {
+    let (url, title) = match &events[*index] {
+        Event::Start(Tag::Image(_, url, title)) => (url.to_string(), title.to_string()),
+        _ => (String::new(), String::new()),
+    };
+    *index += 1;
+    let mut alt_text = String::new();
+    while *index < events.len() {
+        match &events[*index] {
+            Event::Text(t) | Event::Code(t) => alt_text.push_str(t),
+            Event::End(Tag::Image(..)) => {
+                *index += 1;
+                break;
+            }
+            _ => {}
+        }
+        *index += 1;
+    }
+
+    SlideElement::Image {
+        alt_text: alt_text.trim().to_string(),
+        url,
+        decorative: title.eq_ignore_ascii_case("decorative"),
+    }
+}
+
+fn extract_paragraph_text(events: &[Event], index: &mut usize) -> Result<InlineText> {
     *index += 1; // Skip the Start(Paragraph) event
-    let mut text = String::new();
+    let mut text = InlineCollector::default();
 
     while *index < events.len() {
         match &events[*index] {
             Event::Text(t) => text.push_str(t),
-            Event::Code(t) => {
-                text.push('`');
-                text.push_str(t);
-                text.push('`');
-            }
+            Event::Code(t) => text.push_code(t),
+            Event::Start(Tag::Link(_, url, _)) => text.link = Some(url.to_string()),
+            Event::End(Tag::Link(..)) => text.link = None,
             Event::Start(Tag::Strong) => text.push_str("**"),
             Event::End(Tag::Strong) => text.push_str("**"),
-            Event::Start(Tag::Emphasis) => text.push('*'),
-            Event::End(Tag::Emphasis) => text.push('*'),
+            Event::Start(Tag::Emphasis) => text.push_str("*"),
+            Event::End(Tag::Emphasis) => text.push_str("*"),
+            Event::Html(html) if is_span_tag(html) => text.span_tag(html),
             Event::End(Tag::Paragraph) => {
                 *index += 1;
                 break;
@@ -238,33 +770,39 @@ fn extract_paragraph_text(events: &[Event], index: &mut usize) -> Result<String>
         *index += 1;
     }
 
-    Ok(text)
+    Ok(text.take())
 }
 
-fn extract_list_items(events: &[Event], index: &mut usize) -> Result<(Vec<String>, bool)> {
+/// Items of a list, nested items included, and its deepest nesting level
+fn extract_list_items(events: &[Event], index: &mut usize) -> Result<(Vec<InlineText>, usize)> {
     *index += 1; // Skip the Start(List) event
     let mut items = Vec::new();
-    let mut current_item = String::new();
-    let ordered = false;
+    let mut current_item = InlineCollector::default();
+    let mut nesting = 0;
+    let mut depth = 1;
 
     while *index < events.len() {
         match &events[*index] {
+            Event::Start(Tag::List(_)) => {
+                nesting += 1;
+                depth = depth.max(nesting + 1);
+            }
+            Event::End(Tag::List(_)) if nesting > 0 => nesting -= 1,
             Event::Start(Tag::Item) => {
-                if !current_item.is_empty() {
-                    items.push(current_item.trim().to_string());
-                    current_item.clear();
+                let item = current_item.take();
+                if !item.is_empty() {
+                    items.push(item);
                 }
             }
             Event::Text(t) => current_item.push_str(t),
-            Event::Code(t) => {
-                current_item.push('`');
-                current_item.push_str(t);
-                current_item.push('`');
-            }
+            Event::Code(t) => current_item.push_code(t),
+            Event::Start(Tag::Link(_, url, _)) => current_item.link = Some(url.to_string()),
+            Event::End(Tag::Link(..)) => current_item.link = None,
+            Event::Html(html) if is_span_tag(html) => current_item.span_tag(html),
             Event::End(Tag::Item) => {
-                if !current_item.is_empty() {
-                    items.push(current_item.trim().to_string());
-                    current_item.clear();
+                let item = current_item.take();
+                if !item.is_empty() {
+                    items.push(item);
                 }
             }
             Event::End(Tag::List(_)) => {
@@ -276,7 +814,7 @@ fn extract_list_items(events: &[Event], index: &mut usize) -> Result<(Vec<String
         *index += 1;
     }
 
-    Ok((items, ordered))
+    Ok((items, depth))
 }
 
 fn extract_code_block(
@@ -313,18 +851,18 @@ fn extract_code_block(
     Ok((code, language))
 }
 
-fn extract_quote_text(events: &[Event], index: &mut usize) -> Result<String> {
+fn extract_quote_text(events: &[Event], index: &mut usize) -> Result<InlineText> {
     *index += 1; // Skip the Start(BlockQuote) event
-    let mut text = String::new();
+    let mut text = InlineCollector::default();
 
     while *index < events.len() {
         match &events[*index] {
             Event::Text(t) => text.push_str(t),
-            Event::Code(t) => {
-                text.push('`');
-                text.push_str(t);
-                text.push('`');
-            }
+            Event::Code(t) => text.push_code(t),
+            Event::Start(Tag::Link(_, url, _)) => text.link = Some(url.to_string()),
+            Event::End(Tag::Link(..)) => text.link = None,
+            Event::SoftBreak | Event::HardBreak => text.push_str(" "),
+            Event::Html(html) if is_span_tag(html) => text.span_tag(html),
             Event::End(Tag::BlockQuote) => {
                 *index += 1;
                 break;
@@ -334,18 +872,136 @@ fn extract_quote_text(events: &[Event], index: &mut usize) -> Result<String> {
         *index += 1;
     }
 
-    Ok(text)
+    Ok(text.take())
+}
+
+/// Inline `<span ...>` and `</span>` tags, which set the language of the
+/// runs inside, as in `<span lang="ar">...</span>`
+fn is_span_tag(html: &str) -> bool {
+    let html = html.trim();
+    html == "</span>" || (html.starts_with("<span") && html.ends_with('>'))
+}
+
+/// The `lang` attribute of a `<span ...>` tag, as written
+fn span_language(tag: &str) -> Option<String> {
+    let start = tag.find("lang=")? + "lang=".len();
+    let quote = tag[start..]
+        .chars()
+        .next()
+        .filter(|c| *c == '"' || *c == '\'')?;
+    let value = &tag[start + 1..];
+    Some(value[..value.find(quote)?].to_string())
+}
+
+/// GitHub-style anchor for a heading: lowercase, punctuation dropped, spaces as hyphens.
+/// `[Jump](#getting-started)` links resolve against slide titles with this slug.
+pub fn heading_anchor(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Parse an HTML comment such as `<!-- transition: fade -->` into a directive.
+/// A bare word like `<!-- incremental -->` is treated as `incremental: true`.
+fn parse_directive(html: &str) -> Option<(String, String)> {
+    let body = html
+        .trim()
+        .strip_prefix("<!--")?
+        .strip_suffix("-->")?
+        .trim();
+    if body.is_empty() || body.contains('\n') {
+        return None;
+    }
+
+    let (key, value) = body.split_once(':').unwrap_or((body, "true"));
+    let key = key.trim().to_lowercase();
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((key, value.trim().to_string()))
+}
+
+/// Parse a `<!-- notes: ... -->` comment, which may span several lines,
+/// into paragraphs. Lines within a paragraph are joined with spaces.
+fn parse_notes(html: &str) -> Option<Vec<String>> {
+    let body = html
+        .trim()
+        .strip_prefix("<!--")?
+        .strip_suffix("-->")?
+        .trim_start();
+    let (key, text) = body.split_once(':')?;
+    if !key.trim().eq_ignore_ascii_case("notes") {
+        return None;
+    }
+
+    let mut paragraphs = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim).chain([""]) {
+        if line.is_empty() {
+            if !paragraph.is_empty() {
+                paragraphs.push(paragraph.join(" "));
+                paragraph.clear();
+            }
+        } else {
+            paragraph.push(line);
+        }
+    }
+    Some(paragraphs)
+}
+
+/// If the blockquote starting at `index` holds nothing but a single list,
+/// return the index of its `End(BlockQuote)` event.
+fn quoted_list_end(events: &[Event], index: usize) -> Option<usize> {
+    if !matches!(events.get(index + 1), Some(Event::Start(Tag::List(_)))) {
+        return None;
+    }
+
+    let mut depth = 0;
+    for (offset, event) in events[index + 1..].iter().enumerate() {
+        match event {
+            Event::Start(Tag::List(_)) => depth += 1,
+            Event::End(Tag::List(_)) => {
+                depth -= 1;
+                if depth == 0 {
+                    let end = index + offset + 2;
+                    return matches!(events.get(end), Some(Event::End(Tag::BlockQuote)))
+                        .then_some(end);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split a leading `[!KIND]` marker off quote text, returning the callout kind
+/// and the remaining body. Unknown markers leave the quote as a plain quote.
+fn split_callout_marker(text: &InlineText) -> Option<(CalloutKind, InlineText)> {
+    let first = text.runs.first()?;
+    let rest = first.text.strip_prefix("[!")?;
+    let (marker, body) = rest.split_once(']')?;
+    let kind = CalloutKind::from_marker(marker)?;
+    let mut runs = text.runs.clone();
+    runs[0].text = body.to_string();
+    Some((kind, InlineText::trimmed(runs)))
 }
 
 fn extract_table_data(
     events: &[Event],
     index: &mut usize,
-) -> Result<(Vec<String>, Vec<Vec<String>>)> {
+) -> Result<(Vec<InlineText>, Vec<Vec<InlineText>>)> {
     *index += 1; // Skip the Start(Table) event
     let mut headers = Vec::new();
     let mut rows = Vec::new();
     let mut current_row = Vec::new();
-    let mut current_cell = String::new();
+    // Links in cells keep only their text
+    let mut current_cell = InlineCollector::default();
     let mut in_header = true;
 
     while *index < events.len() {
@@ -354,9 +1010,8 @@ fn extract_table_data(
                 in_header = true;
             }
             Event::End(Tag::TableHead) => {
-                if !current_cell.is_empty() {
-                    headers.push(current_cell.trim().to_string());
-                    current_cell.clear();
+                if !current_cell.runs.is_empty() {
+                    headers.push(current_cell.take());
                 }
                 if !current_row.is_empty() {
                     headers.append(&mut current_row);
@@ -367,26 +1022,25 @@ fn extract_table_data(
                 current_row.clear();
             }
             Event::End(Tag::TableRow) => {
-                if !current_cell.is_empty() {
-                    current_row.push(current_cell.trim().to_string());
-                    current_cell.clear();
+                if !current_cell.runs.is_empty() {
+                    current_row.push(current_cell.take());
                 }
                 if !in_header && !current_row.is_empty() {
                     rows.push(current_row.clone());
                 }
             }
             Event::Start(Tag::TableCell) => {
-                current_cell.clear();
+                current_cell.take();
             }
             Event::End(Tag::TableCell) => {
                 if in_header {
-                    headers.push(current_cell.trim().to_string());
+                    headers.push(current_cell.take());
                 } else {
-                    current_row.push(current_cell.trim().to_string());
+                    current_row.push(current_cell.take());
                 }
-                current_cell.clear();
             }
-            Event::Text(t) => current_cell.push_str(t),
+            Event::Text(t) | Event::Code(t) => current_cell.push_str(t),
+            Event::Html(html) if is_span_tag(html) => current_cell.span_tag(html),
             Event::End(Tag::Table(_)) => {
                 *index += 1;
                 break;
@@ -440,11 +1094,411 @@ fn main() {
         let doc = MarkdownDocument::parse(markdown).unwrap();
         assert_eq!(doc.slides.len(), 1);
 
-        if let SlideElement::CodeBlock { language, code } = &doc.slides[0].content[0] {
+        if let SlideElement::CodeBlock { language, code } = &doc.slides[0].content[0].node {
             assert_eq!(language, &Some("rust".to_string()));
             assert!(code.contains("fn main()"));
         } else {
             panic!("Expected code block");
         }
     }
+
+    #[test]
+    fn test_front_matter() {
+        let markdown = r#"---
+title: "Quarterly Review"
+author: Jane Doe
+team: Platform
+---
+
+# First Heading
+
+Body.
+"#;
+
+        let doc = MarkdownDocument::parse(markdown).unwrap();
+        assert_eq!(doc.metadata.title.as_deref(), Some("Quarterly Review"));
+        assert_eq!(doc.metadata.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(
+            doc.metadata
+                .custom_properties
+                .get("team")
+                .map(String::as_str),
+            Some("Platform")
+        );
+        assert_eq!(doc.slides.len(), 1);
+        assert_eq!(doc.slides[0].title.as_deref(), Some("First Heading"));
+    }
+
+    #[test]
+    fn test_leading_rule_is_not_front_matter() {
+        // A Marp-style deck opening with a slide separator
+        let markdown = "---\n# Intro\n\nWelcome.\nNote: this is a slide\n---\n\n# Next\n";
+        let (front_matter, body) = split_front_matter(markdown);
+        assert!(front_matter.is_none());
+        assert_eq!(body, markdown);
+
+        let doc = MarkdownDocument::parse(markdown).unwrap();
+        assert_eq!(doc.slides[0].title.as_deref(), Some("Intro"));
+        assert!(doc.metadata.custom_properties.is_empty());
+
+        // Keys with spaces, or only comments, do not make front matter either
+        for markdown in [
+            "---\nKey point: keep it short\n---\n",
+            "---\n# Just a heading\n---\n",
+        ] {
+            let (front_matter, body) = split_front_matter(markdown);
+            assert!(front_matter.is_none());
+            assert_eq!(body, markdown);
+        }
+
+        let (front_matter, body) = split_front_matter(
+            "---\n# Settings\ntitle: Deck\n\nreview-date: 2024-10-01\n---\nBody\n",
+        );
+        let front_matter = front_matter.unwrap();
+        assert_eq!(front_matter.title.as_deref(), Some("Deck"));
+        assert!(front_matter.custom_properties.contains_key("review-date"));
+        assert_eq!(body, "Body\n");
+    }
+
+    #[test]
+    fn test_h1_sections_when_h2_slides() {
+        let markdown = r#"# Part One
+
+## Intro
+
+## Details
+
+# Part Two
+
+## Wrap-up
+"#;
+
+        let doc = MarkdownDocument::parse(markdown).unwrap();
+        assert_eq!(doc.slides.len(), 5);
+        assert_eq!(
+            doc.sections,
+            vec![
+                Section {
+                    name: "Part One".to_string(),
+                    start_slide: 0
+                },
+                Section {
+                    name: "Part Two".to_string(),
+                    start_slide: 3
+                },
+            ]
+        );
+
+        let flat = MarkdownDocument::parse("# One\n\nText\n\n# Two\n\nText\n").unwrap();
+        assert!(flat.sections.is_empty());
+    }
+
+    /// A run of `text`, linked to `link` when given
+    fn run(text: &str, link: Option<&str>) -> Inline {
+        Inline {
+            text: text.to_string(),
+            link: link.map(str::to_string),
+            lang: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_links_as_runs() {
+        let markdown = r#"# Links
+
+See [the docs](https://example.com/docs) or [Rust](https://en.wikipedia.org/wiki/Rust_(programming_language)).
+
+- Jump to [setup](#getting-started)
+- Index with `a[0](x)` or \[draft\](notes)
+"#;
+
+        let doc = MarkdownDocument::parse(markdown).unwrap();
+        let content = &doc.slides[0].content;
+        let SlideElement::Paragraph { text } = &content[0].node else {
+            panic!("Expected paragraph, got {:?}", content[0].node);
+        };
+        assert_eq!(
+            text.runs,
+            [
+                run("See ", None),
+                run("the docs", Some("https://example.com/docs")),
+                run(" or ", None),
+                run(
+                    "Rust",
+                    Some("https://en.wikipedia.org/wiki/Rust_(programming_language)")
+                ),
+                run(".", None),
+            ]
+        );
+
+        let SlideElement::List { items, .. } = &content[1].node else {
+            panic!("Expected list, got {:?}", content[1].node);
+        };
+        assert_eq!(
+            items[0].runs,
+            [
+                run("Jump to ", None),
+                run("setup", Some("#getting-started"))
+            ]
+        );
+        // Bracket text that is not a link stays plain text
+        assert_eq!(
+            items[1].runs,
+            [run("Index with `a[0](x)` or [draft](notes)", None)]
+        );
+    }
+
+    #[test]
+    fn test_directives_and_incremental_lists() {
+        let markdown = r#"# Reveal
+
+<!-- transition: push -->
+<!-- incremental -->
+
+> - First
+> - Second
+
+> 1. One
+> 2. Two
+
+- Static
+"#;
+
+        let doc = MarkdownDocument::parse(markdown).unwrap();
+        let slide = &doc.slides[0];
+        assert_eq!(
+            slide.directives.get("transition").map(String::as_str),
+            Some("push")
+        );
+        assert_eq!(
+            slide.directives.get("incremental").map(String::as_str),
+            Some("true")
+        );
+        assert!(matches!(
+            &slide.content[0].node,
+            SlideElement::List { items, ordered: false, incremental: true, .. } if items.len() == 2
+        ));
+        assert!(matches!(
+            &slide.content[1].node,
+            SlideElement::List {
+                ordered: true,
+                incremental: true,
+                ..
+            }
+        ));
+        assert!(matches!(
+            &slide.content[2].node,
+            SlideElement::List {
+                incremental: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_nested_lists() {
+        let doc =
+            MarkdownDocument::parse("# Plan\n\n- One\n  - One A\n    - Deep\n- Two\n").unwrap();
+        assert!(matches!(
+            &doc.slides[0].content[0].node,
+            SlideElement::List { items, depth: 3, .. }
+                if items.iter().map(InlineText::to_string).eq(["One", "One A", "Deep", "Two"])
+        ));
+    }
+
+    #[test]
+    fn test_speaker_notes() {
+        let markdown = r#"# Welcome
+
+<!-- notes: Thank everyone for coming. -->
+
+Agenda for today
+
+<!--
+notes:
+Mention the survey
+results first.
+
+Then take questions.
+-->
+
+# Next
+<!-- transition: fade -->
+"#;
+
+        let doc = MarkdownDocument::parse(markdown).unwrap();
+        assert_eq!(
+            doc.slides[0].notes,
+            vec![
+                "Thank everyone for coming.",
+                "Mention the survey results first.",
+                "Then take questions.",
+            ]
+        );
+        assert!(doc.slides[0].directives.is_empty());
+        assert!(doc.slides[1].notes.is_empty());
+        assert_eq!(doc.slides[1].directives.len(), 1);
+    }
+
+    #[test]
+    fn test_images() {
+        let doc = MarkdownDocument::parse(
+            "# Results\n\n![Revenue by *quarter*](chart.png)\n\n![](rule.png \"decorative\")\n\nSee ![icon](i.png) here\n",
+        )
+        .unwrap();
+        let content = &doc.slides[0].content;
+        assert_eq!(content.len(), 3);
+        assert!(matches!(
+            &content[0].node,
+            SlideElement::Image { alt_text, url, decorative: false }
+                if alt_text == "Revenue by quarter" && url == "chart.png"
+        ));
+        assert!(matches!(
+            &content[1].node,
+            SlideElement::Image { alt_text, decorative: true, .. } if alt_text.is_empty()
+        ));
+        // Inline images stay part of their paragraph
+        assert!(matches!(&content[2].node, SlideElement::Paragraph { .. }));
+    }
+
+    #[test]
+    fn test_language_spans_as_runs() {
+        let doc = MarkdownDocument::parse(
+            "# Greetings\n\nHello, <span lang=\"fr-FR\">bonjour [toi](#greetings)</span>!\n\n- <span lang='ja-JP'>こんにちは</span>\n",
+        )
+        .unwrap();
+        let content = &doc.slides[0].content;
+        let french = |text: &str, link: Option<&str>| Inline {
+            lang: Some("fr-FR".to_string()),
+            ..run(text, link)
+        };
+        assert!(matches!(
+            &content[0].node,
+            SlideElement::Paragraph { text } if text.runs == [
+                run("Hello, ", None),
+                french("bonjour ", None),
+                french("toi", Some("#greetings")),
+                run("!", None),
+            ]
+        ));
+        assert!(matches!(
+            &content[1].node,
+            SlideElement::List { items, .. } if items[0].runs[0].lang.as_deref() == Some("ja-JP")
+        ));
+        assert!(matches!(
+            &content[0].node,
+            SlideElement::Paragraph { text } if text.to_string() == "Hello, bonjour toi!"
+        ));
+    }
+
+    #[test]
+    fn test_heading_anchor() {
+        assert_eq!(heading_anchor("Getting Started"), "getting-started");
+        assert_eq!(heading_anchor("What's new in 2.0?"), "whats-new-in-20");
+    }
+
+    #[test]
+    fn test_parse_callouts() {
+        let markdown = r#"# Callouts
+
+> [!WARNING]
+> Mind the gap.
+
+> [!note] Lowercase markers work too.
+
+> Just a quote.
+
+> [!UNKNOWN] Stays a quote.
+"#;
+
+        let doc = MarkdownDocument::parse(markdown).unwrap();
+        let content = &doc.slides[0].content;
+        assert_eq!(content.len(), 4);
+
+        match &content[0].node {
+            SlideElement::Callout { kind, text } => {
+                assert_eq!(*kind, CalloutKind::Warning);
+                assert_eq!(text.to_string(), "Mind the gap.");
+            }
+            other => panic!("Expected callout, got {:?}", other),
+        }
+        assert!(matches!(
+            &content[1].node,
+            SlideElement::Callout { kind: CalloutKind::Note, text }
+                if text.to_string() == "Lowercase markers work too."
+        ));
+        assert!(
+            matches!(&content[2].node, SlideElement::Quote { text } if text.to_string() == "Just a quote.")
+        );
+        assert!(matches!(&content[3].node, SlideElement::Quote { .. }));
+    }
+
+    #[test]
+    fn test_source_spans() {
+        let source = "---\ntitle: Spans\n---\nIntro\n\n# Plan\n\n- a\n- b\n\n> ```\n> code\n> ```\n\n## Café\n\nText é **bold**\n";
+        let document = MarkdownDocument::parse(source).unwrap();
+        let lines: Vec<Vec<usize>> = document
+            .slides
+            .iter()
+            .map(|slide| {
+                slide
+                    .content
+                    .iter()
+                    .map(|element| element.span.line)
+                    .collect()
+            })
+            .collect();
+        assert_eq!(lines, vec![vec![4], vec![8, 11], vec![17]]);
+
+        // An untitled slide starts at its first element
+        let intro = &document.slides[0];
+        assert_eq!((intro.span.line, intro.span.column), (4, 1));
+        assert_eq!(&source[intro.span.start..intro.span.end], "Intro\n");
+
+        // A titled slide runs from its heading to the end of its last element
+        let plan = &document.slides[1];
+        assert_eq!(plan.span.line, 6);
+        assert!(source[plan.span.start..plan.span.end].starts_with("# Plan"));
+        assert!(source[plan.span.start..plan.span.end].ends_with("> ```\n"));
+        assert_eq!(plan.location(&plan.content[0].span), "8:1");
+
+        let cafe = &document.slides[2];
+        let mut slide = cafe.clone();
+        slide.source = Some(PathBuf::from("talk.md"));
+        assert_eq!(slide.location(&cafe.span), "talk.md:15:1");
+        assert_eq!(
+            &source[cafe.content[0].span.start..cafe.content[0].span.end],
+            "Text é **bold**\n"
+        );
+    }
+
+    #[test]
+    fn test_dropped_content_diagnostics() {
+        let source = "# [Home](index.md)\n\n<!-- notes:\nSay hi\n-->\n\n<div class=\"box\">\n<p>Hi</p>\n</div>\n\n![Chart](chart.png)\n\nSee ![icon](i.png) and <span lang=\"fr\">oui</span><br> too[^1].\n\n---\n\n[^1]: A footnote.\n";
+        let (doc, diagnostics) = MarkdownDocument::parse_with_diagnostics(source).unwrap();
+        let found: Vec<(&str, usize)> = diagnostics
+            .iter()
+            .map(|d| (d.code.code(), d.span.unwrap().line))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("W004", 1),
+                ("W001", 7),
+                ("W003", 13),
+                ("W001", 13),
+                ("W002", 13),
+                ("W005", 15),
+                ("W002", 17),
+            ]
+        );
+
+        // The footnote definition does not turn into a paragraph
+        let content = &doc.slides[0].content;
+        assert_eq!(content.len(), 2);
+        assert!(
+            matches!(&content[1].node, SlideElement::Paragraph { text } if text.to_string().contains("oui"))
+        );
+        assert_eq!(doc.slides[0].notes, ["Say hi"]);
+    }
 }
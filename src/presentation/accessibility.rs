@@ -0,0 +1,221 @@
+use crate::presentation::background::Background;
+use crate::presentation::package::Package;
+use crate::presentation::palette::{check_template_contrast, contrast_ratio, TEXT_CONTRAST};
+use crate::presentation::templates::SlideTemplate;
+use crate::Result;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Smallest readable font size, in hundredths of a point
+pub const MIN_FONT_SIZE: u32 = 1200;
+
+/// Kind of accessibility problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessibilityRule {
+    MissingAltText,
+    MissingTitle,
+    DuplicateTitle,
+    LowContrast,
+    SmallFont,
+}
+
+impl AccessibilityRule {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AccessibilityRule::MissingAltText => "missing-alt-text",
+            AccessibilityRule::MissingTitle => "missing-title",
+            AccessibilityRule::DuplicateTitle => "duplicate-title",
+            AccessibilityRule::LowContrast => "low-contrast",
+            AccessibilityRule::SmallFont => "small-font",
+        }
+    }
+}
+
+/// One problem found by the accessibility check. `slide` is the 1-based
+/// slide number, or `None` for problems with the whole deck.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessibilityIssue {
+    pub rule: AccessibilityRule,
+    pub slide: Option<usize>,
+    pub message: String,
+}
+
+impl AccessibilityIssue {
+    pub fn new(rule: AccessibilityRule, slide: Option<usize>, message: impl Into<String>) -> Self {
+        Self {
+            rule,
+            slide,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for AccessibilityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.slide {
+            Some(slide) => write!(f, "slide {}: ", slide)?,
+            None => write!(f, "deck: ")?,
+        }
+        write!(f, "{} [{}]", self.message, self.rule.name())
+    }
+}
+
+/// Slides without a title, and titles shared by more than one slide, which
+/// leave screen reader users unable to tell slides apart
+pub fn title_issues(titles: &[Option<&str>]) -> Vec<AccessibilityIssue> {
+    let mut issues = Vec::new();
+    let mut first_use: HashMap<String, usize> = HashMap::new();
+    for (index, title) in titles.iter().enumerate() {
+        let slide = index + 1;
+        match title.map(str::trim).filter(|title| !title.is_empty()) {
+            None => issues.push(AccessibilityIssue::new(
+                AccessibilityRule::MissingTitle,
+                Some(slide),
+                "slide has no title",
+            )),
+            Some(title) => match first_use.get(&title.to_lowercase()) {
+                Some(first) => issues.push(AccessibilityIssue::new(
+                    AccessibilityRule::DuplicateTitle,
+                    Some(slide),
+                    format!("title \"{}\" is also used by slide {}", title, first),
+                )),
+                None => {
+                    first_use.insert(title.to_lowercase(), slide);
+                }
+            },
+        }
+    }
+    issues
+}
+
+/// Template text colors below WCAG AA contrast, and solid slide backgrounds
+/// that body text does not stand out from
+pub fn contrast_issues(
+    template: &SlideTemplate,
+    backgrounds: &[(Option<usize>, &Background)],
+) -> Vec<AccessibilityIssue> {
+    let mut issues: Vec<AccessibilityIssue> = check_template_contrast(template)
+        .into_iter()
+        .filter(|check| check.required >= TEXT_CONTRAST && !check.passes())
+        .map(|check| {
+            AccessibilityIssue::new(
+                AccessibilityRule::LowContrast,
+                None,
+                format!(
+                    "{} on {} has contrast {:.1}:1, below {}:1",
+                    check.foreground, check.background, check.ratio, check.required
+                ),
+            )
+        })
+        .collect();
+
+    let text = template.get_theme_colors().text_primary;
+    for (slide, background) in backgrounds {
+        let Background::Solid(color) = background else {
+            continue;
+        };
+        if let Some(ratio) = contrast_ratio(&text, color) {
+            if ratio < TEXT_CONTRAST {
+                issues.push(AccessibilityIssue::new(
+                    AccessibilityRule::LowContrast,
+                    *slide,
+                    format!(
+                        "text color {} on background {} has contrast {:.1}:1, below {}:1",
+                        text,
+                        color.trim_start_matches('#').to_uppercase(),
+                        ratio,
+                        TEXT_CONTRAST
+                    ),
+                ));
+            }
+        }
+    }
+    issues
+}
+
+/// Text smaller than `MIN_FONT_SIZE` on slides, layouts and masters of a
+/// built deck, reported once per part at its smallest size
+pub(crate) fn font_size_issues(package: &Package) -> Result<Vec<AccessibilityIssue>> {
+    let mut parts: Vec<&str> = package
+        .part_names()
+        .filter(|name| {
+            name.ends_with(".xml")
+                && ["ppt/slides/", "ppt/slideLayouts/", "ppt/slideMasters/"]
+                    .iter()
+                    .any(|dir| name.starts_with(dir) && !name[dir.len()..].contains('/'))
+        })
+        .collect();
+    parts.sort_by_key(|name| (slide_number(name).unwrap_or(0), name.to_string()));
+
+    let mut issues = Vec::new();
+    for part in parts {
+        let smallest = package
+            .elements(part)?
+            .into_iter()
+            .filter(|element| matches!(element.name.as_str(), "rPr" | "defRPr" | "endParaRPr"))
+            .filter_map(|element| element.attr("sz")?.parse::<u32>().ok())
+            .min();
+        if let Some(size) = smallest.filter(|size| *size < MIN_FONT_SIZE) {
+            let slide = slide_number(part);
+            let location = match slide {
+                Some(_) => String::new(),
+                None => format!(" in {}", part),
+            };
+            issues.push(AccessibilityIssue::new(
+                AccessibilityRule::SmallFont,
+                slide,
+                format!(
+                    "text at {}pt{} is smaller than {}pt",
+                    f64::from(size) / 100.0,
+                    location,
+                    MIN_FONT_SIZE / 100
+                ),
+            ));
+        }
+    }
+    Ok(issues)
+}
+
+/// Slide number of a `ppt/slides/slideN.xml` part
+fn slide_number(part: &str) -> Option<usize> {
+    part.strip_prefix("ppt/slides/slide")?
+        .strip_suffix(".xml")?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_issues() {
+        let issues = title_issues(&[Some("Intro"), None, Some("  "), Some("intro")]);
+        let found: Vec<_> = issues
+            .iter()
+            .map(|issue| (issue.rule, issue.slide))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (AccessibilityRule::MissingTitle, Some(2)),
+                (AccessibilityRule::MissingTitle, Some(3)),
+                (AccessibilityRule::DuplicateTitle, Some(4)),
+            ]
+        );
+        assert_eq!(
+            issues[2].to_string(),
+            "slide 4: title \"intro\" is also used by slide 1 [duplicate-title]"
+        );
+    }
+
+    #[test]
+    fn test_contrast_issues() {
+        let background = Background::Solid("#333333".to_string());
+        let issues = contrast_issues(&SlideTemplate::Default, &[(Some(2), &background)]);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].slide, Some(2));
+        assert!(issues[0].message.contains("background 333333"));
+        assert!(contrast_issues(&SlideTemplate::HighContrast, &[]).is_empty());
+    }
+}
@@ -0,0 +1,236 @@
+use crate::presentation::palette::Rgb;
+use crate::utils::error::Error;
+use crate::Result;
+use std::path::{Path, PathBuf};
+
+/// A slide or slide master background, written as `p:bg`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Background {
+    Solid(String),
+    /// CSS-style angle in degrees (0 points up, 90 right, 180 down) and two
+    /// or more colors spread evenly
+    LinearGradient {
+        angle: u32,
+        colors: Vec<String>,
+    },
+    /// Local image stretched over the slide, darkened by `overlay` percent
+    Image {
+        path: PathBuf,
+        overlay: u8,
+    },
+}
+
+impl Background {
+    /// Parse a color (`#1F4E79`), a gradient
+    /// (`linear-gradient(135deg, #1F4E79, #2E75B6)`) or an image
+    /// (`url(images/hero.jpg)`)
+    pub fn parse(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        let invalid = |reason: &str| {
+            Error::configuration(format!("invalid background '{}': {}", spec, reason))
+        };
+        let color = |value: &str| {
+            Rgb::from_hex(value)
+                .map(Rgb::to_hex)
+                .ok_or_else(|| invalid(&format!("'{}' is not a hex color", value.trim())))
+        };
+
+        if let Some(args) = function_args(spec, "linear-gradient") {
+            let mut args: Vec<&str> = args.split(',').map(str::trim).collect();
+            let angle = match args.first().and_then(|arg| arg.strip_suffix("deg")) {
+                Some(degrees) => {
+                    let degrees = degrees
+                        .trim()
+                        .parse::<i64>()
+                        .map_err(|_| invalid("the angle must be whole degrees, e.g. 135deg"))?;
+                    args.remove(0);
+                    degrees.rem_euclid(360) as u32
+                }
+                None => 180,
+            };
+            if args.len() < 2 {
+                return Err(invalid("a gradient needs at least two colors"));
+            }
+            let colors = args.into_iter().map(color).collect::<Result<_>>()?;
+            return Ok(Background::LinearGradient { angle, colors });
+        }
+
+        if let Some(path) = function_args(spec, "url") {
+            let path = path.trim().trim_matches(|c| c == '"' || c == '\'');
+            if path.is_empty() {
+                return Err(invalid("the image path is empty"));
+            }
+            return Ok(Background::Image {
+                path: PathBuf::from(path),
+                overlay: 0,
+            });
+        }
+
+        color(spec).map(Background::Solid)
+    }
+
+    /// Darken an image background by `overlay` percent; other backgrounds
+    /// are unchanged
+    pub fn with_overlay(self, overlay: u8) -> Self {
+        match self {
+            Background::Image { path, .. } => Background::Image {
+                path,
+                overlay: overlay.min(100),
+            },
+            background => background,
+        }
+    }
+
+    /// Take a relative image path as relative to `dir`
+    pub fn resolve(self, dir: &Path) -> Self {
+        match self {
+            Background::Image { path, overlay } if path.is_relative() => Background::Image {
+                path: dir.join(path),
+                overlay,
+            },
+            background => background,
+        }
+    }
+
+    pub fn image_path(&self) -> Option<&Path> {
+        match self {
+            Background::Image { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// `p:bg` element. `image_rel` is the relationship id of the image part.
+    pub(crate) fn to_xml(&self, image_rel: Option<&str>) -> String {
+        let fill = match self {
+            Background::Solid(color) => {
+                format!(r#"<a:solidFill><a:srgbClr val="{}"/></a:solidFill>"#, color)
+            }
+            Background::LinearGradient { angle, colors } => {
+                let last = colors.len().saturating_sub(1).max(1);
+                let stops: String = colors
+                    .iter()
+                    .enumerate()
+                    .map(|(i, color)| {
+                        format!(
+                            r#"<a:gs pos="{}"><a:srgbClr val="{}"/></a:gs>"#,
+                            i * 100000 / last,
+                            color
+                        )
+                    })
+                    .collect();
+                // DrawingML angles run clockwise from pointing right
+                let ang = (i64::from(*angle) - 90).rem_euclid(360) * 60000;
+                format!(
+                    r#"<a:gradFill rotWithShape="1"><a:gsLst>{}</a:gsLst><a:lin ang="{}" scaled="0"/></a:gradFill>"#,
+                    stops, ang
+                )
+            }
+            Background::Image { overlay, .. } => {
+                let darken = match overlay {
+                    0 => String::new(),
+                    overlay => format!(r#"<a:lum bright="-{}"/>"#, u32::from(*overlay) * 1000),
+                };
+                format!(
+                    r#"<a:blipFill dpi="0" rotWithShape="1"><a:blip r:embed="{}">{}</a:blip><a:srcRect/><a:stretch><a:fillRect/></a:stretch></a:blipFill>"#,
+                    image_rel.unwrap_or_default(),
+                    darken
+                )
+            }
+        };
+        format!(
+            r#"
+        <p:bg>
+            <p:bgPr>
+                {}
+                <a:effectLst/>
+            </p:bgPr>
+        </p:bg>"#,
+            fill
+        )
+    }
+}
+
+/// Rewrite an image background spec with a relative path as relative to
+/// `dir`. Other specs, including invalid ones, are returned unchanged.
+pub fn resolve_spec(spec: &str, dir: &Path) -> String {
+    match Background::parse(spec) {
+        Ok(Background::Image { path, .. }) if path.is_relative() => {
+            format!("url({})", dir.join(path).display())
+        }
+        _ => spec.to_string(),
+    }
+}
+
+/// Parse a `background_overlay` value such as `40%` or `40`
+pub fn parse_overlay(value: &str) -> Result<u8> {
+    value
+        .trim()
+        .trim_end_matches('%')
+        .trim()
+        .parse::<u8>()
+        .ok()
+        .filter(|percent| *percent <= 100)
+        .ok_or_else(|| {
+            Error::configuration(format!(
+                "invalid background overlay '{}': expected a percentage from 0 to 100",
+                value
+            ))
+        })
+}
+
+/// The text between `name(` and a closing `)`
+fn function_args<'a>(spec: &'a str, name: &str) -> Option<&'a str> {
+    spec.strip_prefix(name)?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_backgrounds() {
+        assert_eq!(
+            Background::parse("#1f4e79").unwrap(),
+            Background::Solid("1F4E79".to_string())
+        );
+        assert_eq!(
+            Background::parse("linear-gradient(90deg, #000000, 333333)").unwrap(),
+            Background::LinearGradient {
+                angle: 90,
+                colors: vec!["000000".to_string(), "333333".to_string()],
+            }
+        );
+        assert_eq!(
+            Background::parse("url('images/hero.jpg')")
+                .unwrap()
+                .with_overlay(40)
+                .resolve(Path::new("deck")),
+            Background::Image {
+                path: Path::new("deck").join("images/hero.jpg"),
+                overlay: 40,
+            }
+        );
+
+        assert!(Background::parse("blue").is_err());
+        assert!(Background::parse("linear-gradient(#000000)").is_err());
+        assert!(Background::parse("url()").is_err());
+        assert_eq!(parse_overlay("35%").unwrap(), 35);
+        assert!(parse_overlay("150%").is_err());
+    }
+
+    #[test]
+    fn test_background_xml() {
+        let gradient = Background::parse("linear-gradient(#000000, #808080, #FFFFFF)").unwrap();
+        let xml = gradient.to_xml(None);
+        assert!(xml.contains(r#"<a:gs pos="50000"><a:srgbClr val="808080"/></a:gs>"#));
+        // The CSS default, top to bottom, is 90 degrees in DrawingML
+        assert!(xml.contains(r#"<a:lin ang="5400000" scaled="0"/>"#));
+
+        let image = Background::parse("url(hero.png)").unwrap().with_overlay(40);
+        let xml = image.to_xml(Some("rId3"));
+        assert!(xml.contains(r#"<a:blip r:embed="rId3"><a:lum bright="-40000"/></a:blip>"#));
+    }
+}
@@ -1,16 +1,120 @@
-use crate::parser::markdown::{MarkdownDocument, Slide, SlideElement};
-use crate::presentation::templates::SlideTemplate;
+use crate::parser::markdown::{
+    heading_anchor, CalloutKind, InlineText, MarkdownDocument, Section, Slide, SlideElement,
+    Spanned,
+};
+use crate::presentation::accessibility::{self, AccessibilityIssue, AccessibilityRule};
+use crate::presentation::background::{parse_overlay, Background};
+use crate::presentation::media::{self, MediaParts};
+use crate::presentation::notes::{self, NOTES_MASTER_PART, NOTES_REL, NOTES_THEME_PART};
+use crate::presentation::package::Package;
+use crate::presentation::palette::Rgb;
+use crate::presentation::properties::{custom_properties_xml, PropertyValue};
+use crate::presentation::reference::{Placeholder, ReferenceDoc};
+use crate::presentation::templates::{
+    FooterOverlay, FooterSettings, LayoutSettings, LogoPosition, LogoSettings, SlideSize,
+    SlideTemplate,
+};
+use crate::presentation::transitions::{timing_xml, ParagraphBuild, Transition};
+use crate::utils::diagnostics::{Diagnostics, WarningCode};
+use crate::utils::error::Error;
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 use std::io::{Cursor, Write};
+use std::path::PathBuf;
 use uuid::Uuid;
 use zip::ZipWriter;
 
 use crate::Result;
 
+/// Language of runs when neither the command line nor the front matter sets one
+const DEFAULT_LANGUAGE: &str = "en-US";
+
+/// Agenda entries per generated agenda slide before it is split across pages
+const AGENDA_ENTRIES_PER_SLIDE: usize = 8;
+
+/// Deck-level generation options that are not part of the Markdown content
+#[derive(Debug, Clone, Default)]
+pub struct PresentationOptions {
+    /// Prepend a title slide built from the document title, description,
+    /// author and date
+    pub title_slide: bool,
+    /// Insert agenda slides, after the title slide if there is one
+    pub agenda: Option<AgendaSource>,
+    /// Footer settings from the command line, layered over the template's
+    /// footer and the document front matter
+    pub footer: Option<FooterOverlay>,
+    /// Transition for every slide; a `transition` slide directive overrides it
+    pub transition: Option<Transition>,
+    /// Reveal every list one item per click, not just quoted lists and
+    /// slides with an `incremental` directive
+    pub incremental_lists: bool,
+    /// Slide size, replacing the template's dimensions
+    pub slide_size: Option<SlideSize>,
+    /// `.pptx` or `.potx` whose masters, layouts and theme replace the
+    /// built-in ones. Its slide size wins over `slide_size`.
+    pub reference_doc: Option<PathBuf>,
+    /// Watermark text such as `DRAFT` for every slide, winning over the
+    /// front matter and the template
+    pub watermark: Option<String>,
+    /// Document language as a BCP 47 tag such as `ja-JP`, winning over the
+    /// front matter `lang`
+    pub language: Option<String>,
+}
+
+/// What the generated agenda lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgendaSource {
+    /// Every titled slide
+    Slides,
+    /// Section names, one per source file or H1 section. Falls back to slide
+    /// titles when the document has no sections.
+    Sections,
+}
+
+impl AgendaSource {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "slides" => Some(AgendaSource::Slides),
+            "sections" | "files" => Some(AgendaSource::Sections),
+            _ => None,
+        }
+    }
+}
+
+/// A generated slide as the presenter sees it: its number in the deck,
+/// title, speaker notes and the text shown on it
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlideOutline {
+    pub number: usize,
+    pub title: Option<String>,
+    pub notes: Vec<String>,
+    /// Text of the slide's paragraphs, list items, quotes and tables; code
+    /// is left out
+    pub text: Vec<String>,
+}
+
 pub struct PresentationBuilder {
-    _template: SlideTemplate,
+    template: SlideTemplate,
     slides: Vec<PptxSlide>,
+    sections: Vec<Section>,
+    footer: FooterSettings,
+    layout: LayoutSettings,
+    reference: Option<ReferenceDoc>,
+    transition: Option<Transition>,
+    incremental_lists: bool,
+    /// Deck background from the template or front matter; the master uses
+    /// the theme background color when unset
+    background: Option<Background>,
+    /// BCP 47 tag of the document language, e.g. `en-US`
+    language: String,
+    /// Logo and watermark placed on the slide master
+    logo: Option<LogoSettings>,
+    watermark: Option<String>,
+    /// Images embedded for backgrounds and the logo
+    media: MediaParts,
     metadata: PresentationMetadata,
+    /// Content shown as a placeholder instead of as written
+    diagnostics: Diagnostics,
 }
 
 #[derive(Debug, Clone)]
@@ -18,33 +122,71 @@ struct PptxSlide {
     _id: String,
     title: Option<String>,
     content: Vec<PptxElement>,
+    kind: PptxSlideKind,
+    /// Overrides the deck transition
+    transition: Option<Transition>,
+    /// Overrides the master background
+    background: Option<Background>,
+    /// Speaker notes paragraphs
+    notes: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+enum PptxSlideKind {
+    Content,
+    /// Uses the title layout's centered title and subtitle placeholders
+    Title {
+        subtitle_lines: Vec<String>,
+    },
 }
 
 #[derive(Debug, Clone)]
 enum PptxElement {
     _Title(String),
-    Text(String),
-    BulletList(Vec<String>),
-    NumberedList(Vec<String>),
+    Text(InlineText),
+    BulletList {
+        items: Vec<InlineText>,
+        incremental: bool,
+    },
+    NumberedList {
+        items: Vec<InlineText>,
+        incremental: bool,
+    },
     Code {
         _language: Option<String>,
         content: String,
     },
     Image {
         alt: String,
-        _url: String,
+        url: String,
+        decorative: bool,
+        /// Local image embedded as a picture; other images get a placeholder
+        path: Option<PathBuf>,
     },
     Table {
-        headers: Vec<String>,
-        _rows: Vec<Vec<String>>,
+        headers: Vec<InlineText>,
+        rows: Vec<Vec<InlineText>>,
     },
-    Quote(String),
+    Quote(InlineText),
+    Callout {
+        kind: CalloutKind,
+        text: InlineText,
+    },
+    /// Bullet list whose entries jump to a 1-based slide number
+    LinkList(Vec<(String, usize)>),
 }
 
 #[derive(Debug, Clone)]
 struct PresentationMetadata {
     title: String,
     author: String,
+    subject: Option<String>,
+    keywords: Option<String>,
+    category: Option<String>,
+    description: Option<String>,
+    last_modified_by: Option<String>,
+    /// Written to `docProps/custom.xml`
+    custom_properties: Vec<(String, PropertyValue)>,
     created: DateTime<Utc>,
     modified: DateTime<Utc>,
     slide_count: usize,
@@ -53,19 +195,45 @@ struct PresentationMetadata {
 impl PresentationBuilder {
     pub fn new(template: SlideTemplate) -> Self {
         Self {
-            _template: template,
+            footer: template.get_footer(),
+            layout: template.get_layout_settings(),
+            reference: None,
+            template,
             slides: Vec::new(),
+            sections: Vec::new(),
+            transition: None,
+            incremental_lists: false,
+            background: None,
+            language: DEFAULT_LANGUAGE.to_string(),
+            logo: None,
+            watermark: None,
+            media: MediaParts::default(),
             metadata: PresentationMetadata {
                 title: "Converted Presentation".to_string(),
                 author: "md2pptx".to_string(),
+                subject: None,
+                keywords: None,
+                category: None,
+                description: None,
+                last_modified_by: None,
+                custom_properties: Vec::new(),
                 created: Utc::now(),
                 modified: Utc::now(),
                 slide_count: 0,
             },
+            diagnostics: Diagnostics::default(),
         }
     }
 
     pub fn from_markdown(markdown_doc: &MarkdownDocument, template: SlideTemplate) -> Result<Self> {
+        Self::from_markdown_with_options(markdown_doc, template, &PresentationOptions::default())
+    }
+
+    pub fn from_markdown_with_options(
+        markdown_doc: &MarkdownDocument,
+        template: SlideTemplate,
+        options: &PresentationOptions,
+    ) -> Result<Self> {
         let mut builder = Self::new(template);
 
         // Set metadata from markdown document
@@ -75,41 +243,246 @@ impl PresentationBuilder {
         if let Some(author) = &markdown_doc.metadata.author {
             builder.metadata.author = author.clone();
         }
+        let properties = &markdown_doc.metadata.custom_properties;
+        builder.metadata.description = markdown_doc.metadata.description.clone();
+        builder.metadata.subject = properties.get("subject").cloned();
+        builder.metadata.keywords = properties.get("keywords").cloned();
+        builder.metadata.category = properties.get("category").cloned();
+        builder.metadata.last_modified_by = properties.get("last_modified_by").cloned();
+        builder.metadata.custom_properties = custom_document_properties(properties);
+        if let Some(language) = options
+            .language
+            .as_ref()
+            .or_else(|| properties.get("lang"))
+            .or_else(|| properties.get("language"))
+        {
+            if !is_language_tag(language) {
+                return Err(Error::configuration(format!(
+                    "invalid language '{}': expected a BCP 47 tag such as en-US, ja-JP or ar-SA",
+                    language
+                )));
+            }
+            builder.language = language.clone();
+        }
+
+        // Footer precedence: template, then front matter, then command line
+        builder.footer.overlay(&footer_from_front_matter(
+            &markdown_doc.metadata.custom_properties,
+        ));
+        if let Some(footer) = &options.footer {
+            builder.footer.overlay(footer);
+        }
+
+        // Command line transition, build and slide size settings win over the
+        // front matter
+        builder.transition = options.transition.or_else(|| {
+            properties
+                .get("transition")
+                .and_then(|name| Transition::from_name(name))
+        });
+        if let Some(size) = options.slide_size.or_else(|| {
+            properties
+                .get("slide_size")
+                .and_then(|name| SlideSize::from_name(name))
+        }) {
+            builder.layout.set_slide_size(size);
+        }
+        if let Some(path) = &options.reference_doc {
+            let reference = ReferenceDoc::open(path)?;
+            let (width, height) = reference.slide_size();
+            builder
+                .layout
+                .set_slide_size(SlideSize::Custom { width, height });
+            builder.reference = Some(reference);
+        }
+        builder.incremental_lists = options.incremental_lists
+            || properties
+                .get("incremental")
+                .is_some_and(|value| is_truthy(value));
+        builder.background = match properties.get("background") {
+            Some(spec) => builder.background_setting(
+                spec,
+                properties.get("background_overlay").map(String::as_str),
+            )?,
+            None => builder.template.get_background()?,
+        };
+        if let Some(path) = builder.background.as_ref().and_then(Background::image_path) {
+            builder.media.add(path)?;
+        }
+        builder.logo = builder.template.get_logo();
+        if let Some(logo) = &builder.logo {
+            builder.media.add(&logo.path)?;
+        }
+        // Watermark precedence: command line, then front matter, then template.
+        // `watermark: none` in the front matter removes the template's.
+        builder.watermark = match (&options.watermark, properties.get("watermark")) {
+            (Some(text), _) => Some(text.clone()),
+            (None, Some(text)) if matches!(text.to_lowercase().as_str(), "none" | "false") => None,
+            (None, Some(text)) => Some(text.clone()),
+            (None, None)
+                if properties
+                    .get("draft")
+                    .is_some_and(|value| is_truthy(value)) =>
+            {
+                Some("DRAFT".to_string())
+            }
+            (None, None) => builder.template.get_watermark(),
+        }
+        .filter(|text| !text.trim().is_empty());
+
+        let mut markdown_slides = markdown_doc.slides.as_slice();
+        let mut skipped = 0;
+        if options.title_slide {
+            builder.add_title_slide(markdown_doc);
+
+            // A leading H1 that only repeats the deck title would duplicate the title slide
+            if let Some(first) = markdown_slides.first() {
+                if first.content.is_empty() && first.title == markdown_doc.metadata.title {
+                    // Its speaker notes move to the title slide
+                    if let Some(title_slide) = builder.slides.last_mut() {
+                        title_slide.notes = first.notes.clone();
+                    }
+                    markdown_slides = &markdown_slides[1..];
+                    skipped = 1;
+                }
+            }
+        }
+        if let Some(source) = options.agenda {
+            let entries = agenda_entries(markdown_doc, source, skipped);
+            builder.add_agenda_slides(&entries);
+        }
+        let prepended = builder.slides.len();
 
         // Convert markdown slides to PPTX slides
-        for slide in &markdown_doc.slides {
+        for slide in markdown_slides {
             builder.add_slide_from_markdown(slide)?;
         }
 
+        // Shift sections past generated slides; a section starting the document
+        // keeps starting it so the generated slides belong to it
+        builder.sections = markdown_doc
+            .sections
+            .iter()
+            .map(|section| Section {
+                name: section.name.clone(),
+                start_slide: match section.start_slide {
+                    0 => 0,
+                    start => start - skipped + prepended,
+                },
+            })
+            .collect();
         builder.metadata.slide_count = builder.slides.len();
         Ok(builder)
     }
 
+    fn add_title_slide(&mut self, markdown_doc: &MarkdownDocument) {
+        let metadata = &markdown_doc.metadata;
+        let mut subtitle_lines = Vec::new();
+        if let Some(description) = &metadata.description {
+            subtitle_lines.push(description.clone());
+        }
+        if let Some(author) = &metadata.author {
+            subtitle_lines.push(author.clone());
+        }
+        subtitle_lines.push(
+            metadata
+                .date
+                .clone()
+                .unwrap_or_else(|| self.metadata.created.format("%B %-d, %Y").to_string()),
+        );
+
+        self.slides.push(PptxSlide {
+            _id: Uuid::new_v4().to_string(),
+            title: Some(self.metadata.title.clone()),
+            content: Vec::new(),
+            kind: PptxSlideKind::Title { subtitle_lines },
+            transition: None,
+            background: None,
+            notes: Vec::new(),
+        });
+    }
+
+    /// Add agenda slides for `(label, markdown slide index)` entries. Targets are
+    /// resolved once the number of agenda pages is known, since the content
+    /// slides follow them.
+    fn add_agenda_slides(&mut self, entries: &[(String, usize)]) {
+        let pages: Vec<_> = entries.chunks(AGENDA_ENTRIES_PER_SLIDE).collect();
+        let first_content_slide = self.slides.len() + pages.len() + 1;
+
+        for (page, chunk) in pages.iter().enumerate() {
+            let title = if pages.len() > 1 {
+                format!("Agenda ({}/{})", page + 1, pages.len())
+            } else {
+                "Agenda".to_string()
+            };
+            let links = chunk
+                .iter()
+                .map(|(label, index)| (label.clone(), first_content_slide + index))
+                .collect();
+
+            self.slides.push(PptxSlide {
+                _id: Uuid::new_v4().to_string(),
+                title: Some(title),
+                content: vec![PptxElement::LinkList(links)],
+                kind: PptxSlideKind::Content,
+                transition: None,
+                background: None,
+                notes: Vec::new(),
+            });
+        }
+    }
+
     fn add_slide_from_markdown(&mut self, slide: &Slide) -> Result<()> {
         let mut pptx_slide = PptxSlide {
             _id: Uuid::new_v4().to_string(),
             title: slide.title.clone(),
             content: Vec::new(),
+            kind: PptxSlideKind::Content,
+            transition: slide
+                .directives
+                .get("transition")
+                .and_then(|name| Transition::from_name(name)),
+            background: None,
+            notes: slide.notes.clone(),
         };
+        if let Some(spec) = slide.directives.get("background") {
+            pptx_slide.background = self.background_setting(
+                spec,
+                slide
+                    .directives
+                    .get("background_overlay")
+                    .map(String::as_str),
+            )?;
+        }
+        let incremental_slide = self.incremental_lists
+            || slide
+                .directives
+                .get("incremental")
+                .is_some_and(|value| is_truthy(value));
 
-        for element in &slide.content {
-            match element {
+        for Spanned { node, span } in &slide.content {
+            match node {
                 SlideElement::Heading { level: _, text } => {
-                    pptx_slide.content.push(PptxElement::Text(text.clone()));
+                    pptx_slide
+                        .content
+                        .push(PptxElement::Text(InlineText::from(text.as_str())));
                 }
                 SlideElement::Paragraph { text } => {
                     pptx_slide.content.push(PptxElement::Text(text.clone()));
                 }
-                SlideElement::List { items, ordered } => {
-                    if *ordered {
-                        pptx_slide
-                            .content
-                            .push(PptxElement::NumberedList(items.clone()));
+                SlideElement::List {
+                    items,
+                    ordered,
+                    incremental,
+                    ..
+                } => {
+                    let items = items.clone();
+                    let incremental = *incremental || incremental_slide;
+                    pptx_slide.content.push(if *ordered {
+                        PptxElement::NumberedList { items, incremental }
                     } else {
-                        pptx_slide
-                            .content
-                            .push(PptxElement::BulletList(items.clone()));
-                    }
+                        PptxElement::BulletList { items, incremental }
+                    });
                 }
                 SlideElement::CodeBlock { language, code } => {
                     pptx_slide.content.push(PptxElement::Code {
@@ -117,21 +490,50 @@ impl PresentationBuilder {
                         content: code.clone(),
                     });
                 }
-                SlideElement::Image { alt_text, url } => {
+                SlideElement::Image {
+                    alt_text,
+                    url,
+                    decorative,
+                } => {
+                    let path = PathBuf::from(url);
+                    let added = if url.contains("://") {
+                        Err(format!("remote image {} is shown as a placeholder", url))
+                    } else {
+                        self.media.add(&path).map_err(|err| {
+                            format!("image {} is shown as a placeholder: {}", url, err)
+                        })
+                    };
+                    let embedded = added.is_ok();
+                    if let Err(message) = added {
+                        self.diagnostics.warn(
+                            WarningCode::ImagePlaceholder,
+                            slide.source.as_deref(),
+                            Some(*span),
+                            message,
+                        );
+                    }
                     pptx_slide.content.push(PptxElement::Image {
                         alt: alt_text.clone(),
-                        _url: url.clone(),
+                        url: url.clone(),
+                        decorative: *decorative,
+                        path: embedded.then_some(path),
                     });
                 }
                 SlideElement::Table { headers, rows } => {
                     pptx_slide.content.push(PptxElement::Table {
                         headers: headers.clone(),
-                        _rows: rows.clone(),
+                        rows: rows.clone(),
                     });
                 }
                 SlideElement::Quote { text } => {
                     pptx_slide.content.push(PptxElement::Quote(text.clone()));
                 }
+                SlideElement::Callout { kind, text } => {
+                    pptx_slide.content.push(PptxElement::Callout {
+                        kind: *kind,
+                        text: text.clone(),
+                    });
+                }
             }
         }
 
@@ -139,6 +541,109 @@ impl PresentationBuilder {
         Ok(())
     }
 
+    /// Warnings about content shown as a placeholder instead of as written
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
+    }
+
+    /// Every slide of the deck in order, including generated title and
+    /// agenda slides
+    pub fn outline(&self) -> Vec<SlideOutline> {
+        self.slides
+            .iter()
+            .enumerate()
+            .map(|(index, slide)| {
+                let mut text = match &slide.kind {
+                    PptxSlideKind::Title { subtitle_lines } => subtitle_lines.clone(),
+                    PptxSlideKind::Content => Vec::new(),
+                };
+                for element in &slide.content {
+                    match element {
+                        PptxElement::_Title(line) => text.push(line.clone()),
+                        PptxElement::Text(line)
+                        | PptxElement::Quote(line)
+                        | PptxElement::Callout { text: line, .. } => text.push(line.to_string()),
+                        PptxElement::Image {
+                            alt, decorative, ..
+                        } => {
+                            if !decorative {
+                                text.push(alt.clone())
+                            }
+                        }
+                        PptxElement::BulletList { items, .. }
+                        | PptxElement::NumberedList { items, .. } => {
+                            text.extend(items.iter().map(InlineText::to_string))
+                        }
+                        PptxElement::Table { headers, rows } => text.extend(
+                            headers
+                                .iter()
+                                .chain(rows.iter().flatten())
+                                .map(InlineText::to_string),
+                        ),
+                        PptxElement::LinkList(links) => {
+                            text.extend(links.iter().map(|(label, _)| label.clone()))
+                        }
+                        PptxElement::Code { .. } => {}
+                    }
+                }
+                text.retain(|line| !line.trim().is_empty());
+                SlideOutline {
+                    number: index + 1,
+                    title: slide.title.clone(),
+                    notes: slide.notes.clone(),
+                    text,
+                }
+            })
+            .collect()
+    }
+
+    /// Accessibility problems of the deck: images without alt text, missing
+    /// or duplicate slide titles, low-contrast colors and small fonts
+    pub fn accessibility_issues(&self) -> Result<Vec<AccessibilityIssue>> {
+        let titles: Vec<Option<&str>> = self
+            .slides
+            .iter()
+            .map(|slide| slide.title.as_deref())
+            .collect();
+        let mut issues = accessibility::title_issues(&titles);
+
+        for (index, slide) in self.slides.iter().enumerate() {
+            for element in &slide.content {
+                if let PptxElement::Image {
+                    alt,
+                    url,
+                    decorative: false,
+                    ..
+                } = element
+                {
+                    if alt.trim().is_empty() {
+                        issues.push(AccessibilityIssue::new(
+                            AccessibilityRule::MissingAltText,
+                            Some(index + 1),
+                            format!("image {} has no alt text", url),
+                        ));
+                    }
+                }
+            }
+        }
+
+        let mut backgrounds: Vec<(Option<usize>, &Background)> = Vec::new();
+        if let Some(background) = &self.background {
+            backgrounds.push((None, background));
+        }
+        for (index, slide) in self.slides.iter().enumerate() {
+            if let Some(background) = &slide.background {
+                backgrounds.push((Some(index + 1), background));
+            }
+        }
+        issues.extend(accessibility::contrast_issues(&self.template, &backgrounds));
+
+        let package = Package::from_bytes(&self.build()?, "generated deck")?;
+        issues.extend(accessibility::font_size_issues(&package)?);
+        issues.sort_by_key(|issue| issue.slide);
+        Ok(issues)
+    }
+
     pub fn build(&self) -> Result<Vec<u8>> {
         let mut buffer = Vec::new();
         {
@@ -152,38 +657,126 @@ impl PresentationBuilder {
             self.add_core_properties(&mut zip)?;
             self.add_presentation(&mut zip)?;
             self.add_presentation_relationships(&mut zip)?;
-            self.add_slide_master(&mut zip)?;
-            self.add_slide_master_relationships(&mut zip)?;
-            self.add_slide_layout(&mut zip)?;
+            match &self.reference {
+                Some(reference) => {
+                    // The decorations go on the first master, as with the
+                    // built-in master
+                    let master = reference.masters()[0].1.as_str();
+                    let master_rels = rels_part_name(master);
+                    for (name, data) in reference.parts() {
+                        zip.start_file(name, Default::default())?;
+                        if name == master {
+                            let xml = insert_before_last(
+                                data,
+                                "</p:spTree>",
+                                &self.master_decorations_xml(),
+                            );
+                            zip.write_all(xml.as_bytes())?;
+                        } else if name == master_rels {
+                            let xml = insert_before_last(
+                                data,
+                                "</Relationships>",
+                                &self.master_logo_relationship(),
+                            );
+                            zip.write_all(xml.as_bytes())?;
+                        } else {
+                            zip.write_all(data)?;
+                        }
+                    }
+                }
+                None => {
+                    self.add_slide_master(&mut zip)?;
+                    self.add_slide_master_relationships(&mut zip)?;
+                    self.add_slide_layout(&mut zip)?;
+                    self.add_slide_layout_relationships(&mut zip)?;
+                    self.add_theme(&mut zip)?;
+                }
+            }
+            self.media.write(&mut zip)?;
 
             // Add slides
+            let anchors = self.slide_anchors();
             for (index, slide) in self.slides.iter().enumerate() {
-                self.add_slide(&mut zip, slide, index + 1)?;
-                self.add_slide_relationships(&mut zip, slide, index + 1)?;
+                let mut context = SlideContext::new(&anchors);
+                self.add_slide(&mut zip, slide, index + 1, &mut context)?;
+                self.add_slide_relationships(&mut zip, slide, &context, index + 1)?;
             }
-
-            self.add_theme(&mut zip)?;
+            self.add_notes(&mut zip)?;
 
             zip.finish()?;
         }
         Ok(buffer)
     }
 
+    /// Whether any slide has speaker notes, and so the deck needs a notes master
+    fn has_notes(&self) -> bool {
+        self.slides.iter().any(|slide| !slide.notes.is_empty())
+    }
+
+    /// Notes master, its theme and a notes page for each slide with notes
+    fn add_notes(&self, zip: &mut ZipWriter<Cursor<&mut Vec<u8>>>) -> Result<()> {
+        if !self.has_notes() {
+            return Ok(());
+        }
+        zip.start_file(NOTES_MASTER_PART, Default::default())?;
+        zip.write_all(notes::notes_master_xml(&self.language).as_bytes())?;
+        zip.start_file(rels_part_name(NOTES_MASTER_PART), Default::default())?;
+        zip.write_all(notes::notes_master_relationships_xml().as_bytes())?;
+        zip.start_file(NOTES_THEME_PART, Default::default())?;
+        zip.write_all(self.template.get_theme_xml().as_bytes())?;
+
+        for (index, slide) in self.slides.iter().enumerate() {
+            if slide.notes.is_empty() {
+                continue;
+            }
+            zip.start_file(
+                format!("ppt/notesSlides/notesSlide{}.xml", index + 1),
+                Default::default(),
+            )?;
+            zip.write_all(notes::notes_slide_xml(&slide.notes, &self.language).as_bytes())?;
+            zip.start_file(
+                format!("ppt/notesSlides/_rels/notesSlide{}.xml.rels", index + 1),
+                Default::default(),
+            )?;
+            zip.write_all(notes::notes_slide_relationships_xml(index + 1).as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Map heading anchors to 1-based slide numbers so `#anchor` links can jump
+    /// to the slide with that title. The first slide with a given title wins.
+    fn slide_anchors(&self) -> HashMap<String, usize> {
+        let mut anchors = HashMap::new();
+        for (index, slide) in self.slides.iter().enumerate() {
+            if let Some(title) = &slide.title {
+                anchors.entry(heading_anchor(title)).or_insert(index + 1);
+            }
+        }
+        anchors
+    }
+
     fn add_content_types(&self, zip: &mut ZipWriter<Cursor<&mut Vec<u8>>>) -> Result<()> {
         let content_types = format!(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
     <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
-    <Default Extension="xml" ContentType="application/xml"/>
+    <Default Extension="xml" ContentType="application/xml"/>{}
     <Override PartName="/ppt/presentation.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.presentation.main+xml"/>
-    <Override PartName="/ppt/slideMasters/slideMaster1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slideMaster+xml"/>
-    <Override PartName="/ppt/slideLayouts/slideLayout1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slideLayout+xml"/>
-    <Override PartName="/ppt/theme/theme1.xml" ContentType="application/vnd.openxmlformats-officedocument.theme+xml"/>
+{}
     <Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/>
-    <Override PartName="/docProps/app.xml" ContentType="application/vnd.openxmlformats-officedocument.extended-properties+xml"/>{}</Types>"#,
+    <Override PartName="/docProps/app.xml" ContentType="application/vnd.openxmlformats-officedocument.extended-properties+xml"/>{}{}{}</Types>"#,
+            self.media.content_types(),
+            self.master_content_types(),
+            if self.metadata.custom_properties.is_empty() {
+                ""
+            } else {
+                r#"
+    <Override PartName="/docProps/custom.xml" ContentType="application/vnd.openxmlformats-officedocument.custom-properties+xml"/>"#
+            },
             self.slides.iter().enumerate().map(|(i, _)| {
                 format!(r#"
     <Override PartName="/ppt/slides/slide{}.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slide+xml"/>"#, i + 1)
-            }).collect::<String>()
+            }).collect::<String>(),
+            self.notes_content_types()
         );
 
         zip.start_file("[Content_Types].xml", Default::default())?;
@@ -191,13 +784,42 @@ impl PresentationBuilder {
         Ok(())
     }
 
+    /// Content type overrides for the notes master, its theme and the notes pages
+    fn notes_content_types(&self) -> String {
+        if !self.has_notes() {
+            return String::new();
+        }
+        let mut types = format!(
+            r#"
+    <Override PartName="/{}" ContentType="application/vnd.openxmlformats-officedocument.presentationml.notesMaster+xml"/>
+    <Override PartName="/{}" ContentType="application/vnd.openxmlformats-officedocument.theme+xml"/>"#,
+            NOTES_MASTER_PART, NOTES_THEME_PART
+        );
+        for (index, slide) in self.slides.iter().enumerate() {
+            if !slide.notes.is_empty() {
+                types.push_str(&format!(r#"
+    <Override PartName="/ppt/notesSlides/notesSlide{}.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.notesSlide+xml"/>"#, index + 1));
+            }
+        }
+        types
+    }
+
     fn add_relationships(&self, zip: &mut ZipWriter<Cursor<&mut Vec<u8>>>) -> Result<()> {
-        let relationships = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+        let custom = if self.metadata.custom_properties.is_empty() {
+            ""
+        } else {
+            r#"
+    <Relationship Id="rId4" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/custom-properties" Target="docProps/custom.xml"/>"#
+        };
+        let relationships = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
     <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="ppt/presentation.xml"/>
     <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/>
-    <Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/extended-properties" Target="docProps/app.xml"/>
-</Relationships>"#;
+    <Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/extended-properties" Target="docProps/app.xml"/>{}
+</Relationships>"#,
+            custom
+        );
 
         zip.start_file("_rels/.rels", Default::default())?;
         zip.write_all(relationships.as_bytes())?;
@@ -209,16 +831,21 @@ impl PresentationBuilder {
             r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/extended-properties" xmlns:vt="http://schemas.openxmlformats.org/officeDocument/2006/docPropsVTypes">
     <Application>md2pptx</Application>
-    <PresentationFormat>On-screen Show (4:3)</PresentationFormat>
+    <PresentationFormat>{}</PresentationFormat>
     <Slides>{}</Slides>
-    <Notes>0</Notes>
+    <Notes>{}</Notes>
     <HiddenSlides>0</HiddenSlides>
     <MMClips>0</MMClips>
     <ScaleCrop>false</ScaleCrop>
     <Company>md2pptx</Company>
     <AppVersion>16.0000</AppVersion>
 </Properties>"#,
-            self.metadata.slide_count
+            self.layout.slide_size().label(),
+            self.metadata.slide_count,
+            self.slides
+                .iter()
+                .filter(|slide| !slide.notes.is_empty())
+                .count()
         );
 
         zip.start_file("docProps/app.xml", Default::default())?;
@@ -231,47 +858,105 @@ impl PresentationBuilder {
             r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/" xmlns:dcmitype="http://purl.org/dc/dcmitype/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
     <dc:title>{}</dc:title>
-    <dc:creator>{}</dc:creator>
+    <dc:creator>{}</dc:creator>{}
+    <cp:lastModifiedBy>{}</cp:lastModifiedBy>
     <dcterms:created xsi:type="dcterms:W3CDTF">{}</dcterms:created>
     <dcterms:modified xsi:type="dcterms:W3CDTF">{}</dcterms:modified>
 </cp:coreProperties>"#,
             escape_xml(&self.metadata.title),
             escape_xml(&self.metadata.author),
+            self.optional_core_properties(),
+            escape_xml(
+                self.metadata
+                    .last_modified_by
+                    .as_ref()
+                    .unwrap_or(&self.metadata.author)
+            ),
             self.metadata.created.format("%Y-%m-%dT%H:%M:%SZ"),
             self.metadata.modified.format("%Y-%m-%dT%H:%M:%SZ")
         );
 
         zip.start_file("docProps/core.xml", Default::default())?;
         zip.write_all(core_props.as_bytes())?;
+
+        if !self.metadata.custom_properties.is_empty() {
+            zip.start_file("docProps/custom.xml", Default::default())?;
+            zip.write_all(custom_properties_xml(&self.metadata.custom_properties).as_bytes())?;
+        }
         Ok(())
     }
 
+    /// Subject, keywords, category and description elements that are set
+    fn optional_core_properties(&self) -> String {
+        let metadata = &self.metadata;
+        [
+            ("dc:subject", &metadata.subject),
+            ("cp:keywords", &metadata.keywords),
+            ("cp:category", &metadata.category),
+            ("dc:description", &metadata.description),
+        ]
+        .into_iter()
+        .filter_map(|(element, value)| {
+            value
+                .as_ref()
+                .map(|value| format!("\n    <{}>{}</{}>", element, escape_xml(value), element))
+        })
+        .collect()
+    }
+
     fn add_presentation(&self, zip: &mut ZipWriter<Cursor<&mut Vec<u8>>>) -> Result<()> {
+        let lang = &self.language;
+        let masters = self.master_parts();
+        let master_id_list = masters
+            .iter()
+            .enumerate()
+            .map(|(i, (id, _))| format!(r#"<p:sldMasterId id="{}" r:id="rId{}"/>"#, id, i + 1))
+            .collect::<String>();
         let slide_id_list = self
             .slides
             .iter()
             .enumerate()
-            .map(|(i, _slide)| format!(r#"<p:sldId id="{}" r:id="rId{}"/>"#, 256 + i, i + 2))
+            .map(|(i, _slide)| {
+                format!(
+                    r#"<p:sldId id="{}" r:id="rId{}"/>"#,
+                    256 + i,
+                    i + masters.len() + 1
+                )
+            })
             .collect::<String>();
 
         let presentation = format!(
             r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <p:presentation xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
     <p:sldMasterIdLst>
-        <p:sldMasterId id="2147483648" r:id="rId1"/>
-    </p:sldMasterIdLst>
+        {}
+    </p:sldMasterIdLst>{}
     <p:sldIdLst>
         {}
     </p:sldIdLst>
-    <p:sldSz cx="9144000" cy="6858000" type="screen4x3"/>
+    {}
     <p:notesSz cx="6858000" cy="9144000"/>
     <p:defaultTextStyle>
         <a:defPPr>
-            <a:defRPr lang="en-US"/>
+            <a:defRPr lang="{lang}"/>
         </a:defPPr>
-    </p:defaultTextStyle>
+    </p:defaultTextStyle>{}
 </p:presentation>"#,
-            slide_id_list
+            master_id_list,
+            if self.has_notes() {
+                format!(
+                    r#"
+    <p:notesMasterIdLst>
+        <p:notesMasterId r:id="{}"/>
+    </p:notesMasterIdLst>"#,
+                    NOTES_REL
+                )
+            } else {
+                String::new()
+            },
+            slide_id_list,
+            self.slide_size_xml(),
+            self.section_list_xml()
         );
 
         zip.start_file("ppt/presentation.xml", Default::default())?;
@@ -279,22 +964,97 @@ impl PresentationBuilder {
         Ok(())
     }
 
+    /// Build the `p14:sectionLst` extension listing each section's slide ids.
+    /// Slides before the first section are gathered into a default section,
+    /// since PowerPoint expects every slide to belong to one.
+    fn section_list_xml(&self) -> String {
+        if self.sections.is_empty() || self.slides.is_empty() {
+            return String::new();
+        }
+
+        let mut sections = Vec::new();
+        if self.sections[0].start_slide > 0 {
+            sections.push(("Default Section", 0));
+        }
+        sections.extend(
+            self.sections
+                .iter()
+                .filter(|section| section.start_slide < self.slides.len())
+                .map(|section| (section.name.as_str(), section.start_slide)),
+        );
+
+        let section_xml = sections
+            .iter()
+            .enumerate()
+            .map(|(i, (name, start))| {
+                let end = sections
+                    .get(i + 1)
+                    .map(|(_, next)| *next)
+                    .unwrap_or(self.slides.len());
+                let slide_ids = (*start..end)
+                    .map(|slide| format!(r#"<p14:sldId id="{}"/>"#, 256 + slide))
+                    .collect::<String>();
+                format!(
+                    r#"
+                <p14:section name="{}" id="{{{}}}">
+                    <p14:sldIdLst>{}</p14:sldIdLst>
+                </p14:section>"#,
+                    escape_xml(name),
+                    Uuid::new_v4().to_string().to_uppercase(),
+                    slide_ids
+                )
+            })
+            .collect::<String>();
+
+        format!(
+            r#"
+    <p:extLst>
+        <p:ext uri="{{521415D9-36F7-43E2-AB2F-B90AF26B5E84}}">
+            <p14:sectionLst xmlns:p14="http://schemas.microsoft.com/office/powerpoint/2010/main">{}
+            </p14:sectionLst>
+        </p:ext>
+    </p:extLst>"#,
+            section_xml
+        )
+    }
+
     fn add_presentation_relationships(
         &self,
         zip: &mut ZipWriter<Cursor<&mut Vec<u8>>>,
     ) -> Result<()> {
+        let masters = self.master_parts();
+        let master_relationships = masters.iter().enumerate().map(|(i, (_, part))| {
+            format!(r#"    <Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideMaster" Target="{}"/>
+"#, i + 1, part.trim_start_matches("ppt/"))
+        }).collect::<String>();
         let slide_relationships = self.slides.iter().enumerate().map(|(i, _)| {
-            format!(r#"    <Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide{}.xml"/>"#, i + 2, i + 1)
+            format!(r#"    <Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide{}.xml"/>
+"#, i + masters.len() + 1, i + 1)
         }).collect::<String>();
 
-        let next_id = self.slides.len() + 2;
+        let theme = match &self.reference {
+            Some(reference) => reference.theme(),
+            None => Some("ppt/theme/theme1.xml"),
+        };
+        let theme_relationship = theme.map(|theme| {
+            format!(r#"    <Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme" Target="{}"/>
+"#, self.slides.len() + masters.len() + 1, theme.trim_start_matches("ppt/"))
+        }).unwrap_or_default();
+        let notes_relationship = if self.has_notes() {
+            format!(
+                r#"    <Relationship Id="{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesMaster" Target="{}"/>
+"#,
+                NOTES_REL,
+                NOTES_MASTER_PART.trim_start_matches("ppt/")
+            )
+        } else {
+            String::new()
+        };
         let relationships = format!(
             r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
-    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideMaster" Target="slideMasters/slideMaster1.xml"/>
-{}    <Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme" Target="theme/theme1.xml"/>
-</Relationships>"#,
-            slide_relationships, next_id
+{}{}{}{}</Relationships>"#,
+            master_relationships, slide_relationships, theme_relationship, notes_relationship
         );
 
         zip.start_file("ppt/_rels/presentation.xml.rels", Default::default())?;
@@ -303,9 +1063,10 @@ impl PresentationBuilder {
     }
 
     fn add_slide_master(&self, zip: &mut ZipWriter<Cursor<&mut Vec<u8>>>) -> Result<()> {
-        let slide_master = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+        let slide_master = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <p:sldMaster xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
-    <p:cSld>
+    <p:cSld>{}
         <p:spTree>
             <p:nvGrpSpPr>
                 <p:cNvPr id="1" name=""/>
@@ -319,13 +1080,14 @@ impl PresentationBuilder {
                     <a:chOff x="0" y="0"/>
                     <a:chExt cx="0" cy="0"/>
                 </a:xfrm>
-            </p:grpSpPr>
+            </p:grpSpPr>{}{}
         </p:spTree>
     </p:cSld>
     <p:clrMap bg1="lt1" tx1="dk1" bg2="lt2" tx2="dk2" accent1="accent1" accent2="accent2" accent3="accent3" accent4="accent4" accent5="accent5" accent6="accent6" hlink="hlink" folHlink="folHlink"/>
     <p:sldLayoutIdLst>
         <p:sldLayoutId id="2147483649" r:id="rId1"/>
     </p:sldLayoutIdLst>
+    {}
     <p:txStyles>
         <p:titleStyle>
             <a:lvl1pPr>
@@ -364,7 +1126,12 @@ impl PresentationBuilder {
             </a:lvl1pPr>
         </p:otherStyle>
     </p:txStyles>
-</p:sldMaster>"#;
+</p:sldMaster>"#,
+            self.master_background_xml(),
+            self.master_decorations_xml(),
+            self.master_footer_placeholders(),
+            self.header_footer_xml()
+        );
 
         zip.start_file("ppt/slideMasters/slideMaster1.xml", Default::default())?;
         zip.write_all(slide_master.as_bytes())?;
@@ -375,11 +1142,29 @@ impl PresentationBuilder {
         &self,
         zip: &mut ZipWriter<Cursor<&mut Vec<u8>>>,
     ) -> Result<()> {
-        let relationships = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+        let image = self
+            .background
+            .as_ref()
+            .and_then(Background::image_path)
+            .and_then(|path| self.media.part_name(path))
+            .map(|part| {
+                format!(
+                    r#"
+    <Relationship Id="{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../{}"/>"#,
+                    MASTER_IMAGE_REL,
+                    part.trim_start_matches("ppt/")
+                )
+            })
+            .unwrap_or_default();
+        let relationships = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
     <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideLayout" Target="../slideLayouts/slideLayout1.xml"/>
-    <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme" Target="../theme/theme1.xml"/>
-</Relationships>"#;
+    <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme" Target="../theme/theme1.xml"/>{}{}
+</Relationships>"#,
+            image,
+            self.master_logo_relationship()
+        );
 
         zip.start_file(
             "ppt/slideMasters/_rels/slideMaster1.xml.rels",
@@ -390,7 +1175,9 @@ impl PresentationBuilder {
     }
 
     fn add_slide_layout(&self, zip: &mut ZipWriter<Cursor<&mut Vec<u8>>>) -> Result<()> {
-        let slide_layout = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+        let lang = &self.language;
+        let slide_layout = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <p:sldLayout xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" type="title" preserve="1">
     <p:cSld name="Title Slide">
         <p:spTree>
@@ -423,10 +1210,10 @@ impl PresentationBuilder {
                     <a:lstStyle/>
                     <a:p>
                         <a:r>
-                            <a:rPr lang="en-US"/>
+                            <a:rPr lang="{lang}"/>
                             <a:t>Click to edit Master title style</a:t>
                         </a:r>
-                        <a:endParaRPr lang="en-US"/>
+                        <a:endParaRPr lang="{lang}"/>
                     </a:p>
                 </p:txBody>
             </p:sp>
@@ -446,38 +1233,80 @@ impl PresentationBuilder {
                     <a:lstStyle/>
                     <a:p>
                         <a:r>
-                            <a:rPr lang="en-US"/>
+                            <a:rPr lang="{lang}"/>
                             <a:t>Click to edit Master subtitle style</a:t>
                         </a:r>
-                        <a:endParaRPr lang="en-US"/>
+                        <a:endParaRPr lang="{lang}"/>
                     </a:p>
                 </p:txBody>
-            </p:sp>
+            </p:sp>{}
         </p:spTree>
     </p:cSld>
     <p:clrMapOvr>
         <a:masterClrMapping/>
     </p:clrMapOvr>
-</p:sldLayout>"#;
+    {}
+</p:sldLayout>"#,
+            layout_footer_placeholders(&self.language),
+            self.header_footer_xml()
+        );
 
         zip.start_file("ppt/slideLayouts/slideLayout1.xml", Default::default())?;
         zip.write_all(slide_layout.as_bytes())?;
         Ok(())
     }
 
+    fn add_slide_layout_relationships(
+        &self,
+        zip: &mut ZipWriter<Cursor<&mut Vec<u8>>>,
+    ) -> Result<()> {
+        let relationships = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideMaster" Target="../slideMasters/slideMaster1.xml"/>
+</Relationships>"#;
+
+        zip.start_file(
+            "ppt/slideLayouts/_rels/slideLayout1.xml.rels",
+            Default::default(),
+        )?;
+        zip.write_all(relationships.as_bytes())?;
+        Ok(())
+    }
+
     fn add_slide(
         &self,
         zip: &mut ZipWriter<Cursor<&mut Vec<u8>>>,
         slide: &PptxSlide,
         slide_num: usize,
+        context: &mut SlideContext,
     ) -> Result<()> {
-        let title_text = slide.title.as_deref().unwrap_or("Slide Title");
-        let content_shapes = self.generate_content_shapes(&slide.content);
+        let lang = &self.language;
+        let background = self.slide_background_xml(slide, context);
+        if let PptxSlideKind::Title { subtitle_lines } = &slide.kind {
+            return self.add_title_slide_xml(zip, slide, subtitle_lines, &background, slide_num);
+        }
+
+        // An untitled slide keeps an empty title placeholder, which
+        // `check --a11y` reports as a missing title
+        let title_text = slide.title.as_deref().unwrap_or_default();
+        let direction = direction_ppr(title_text);
+        let title_run = match &slide.title {
+            Some(title) => format!(
+                r#"
+                        <a:r>
+                            <a:rPr lang="{lang}"/>
+                            <a:t>{}</a:t>
+                        </a:r>"#,
+                escape_xml(title)
+            ),
+            None => String::new(),
+        };
+        let content_shapes = self.generate_content_shapes(&slide.content, context);
 
         let slide_xml = format!(
             r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
-    <p:cSld>
+    <p:cSld>{}
         <p:spTree>
             <p:nvGrpSpPr>
                 <p:cNvPr id="1" name=""/>
@@ -502,33 +1331,28 @@ impl PresentationBuilder {
                         <p:ph type="title"/>
                     </p:nvPr>
                 </p:nvSpPr>
-                <p:spPr>
-                    <a:xfrm>
-                        <a:off x="685800" y="457200"/>
-                        <a:ext cx="7772400" cy="1143000"/>
-                    </a:xfrm>
-                </p:spPr>
+                <p:spPr>{}</p:spPr>
                 <p:txBody>
                     <a:bodyPr/>
                     <a:lstStyle/>
-                    <a:p>
-                        <a:r>
-                            <a:rPr lang="en-US"/>
-                            <a:t>{}</a:t>
-                        </a:r>
-                        <a:endParaRPr lang="en-US"/>
+                    <a:p>{direction}{title_run}
+                        <a:endParaRPr lang="{lang}"/>
                     </a:p>
                 </p:txBody>
             </p:sp>
-            {}
+            {}{}
         </p:spTree>
     </p:cSld>
     <p:clrMapOvr>
         <a:masterClrMapping/>
-    </p:clrMapOvr>
+    </p:clrMapOvr>{}{}
 </p:sld>"#,
-            escape_xml(title_text),
-            content_shapes
+            background,
+            self.placeholder_xfrm(685800, 457200, 7772400, 1143000),
+            content_shapes,
+            self.footer_shapes(slide_num, false),
+            self.transition_xml(slide),
+            timing_xml(&context.builds)
         );
 
         zip.start_file(
@@ -539,76 +1363,679 @@ impl PresentationBuilder {
         Ok(())
     }
 
-    fn generate_content_shapes(&self, content: &[PptxElement]) -> String {
-        if content.is_empty() {
-            return String::new();
-        }
-
-        let mut shapes = String::new();
-        let mut shape_id = 3;
-        let mut y_pos = 1828800; // Starting Y position below title
+    fn add_title_slide_xml(
+        &self,
+        zip: &mut ZipWriter<Cursor<&mut Vec<u8>>>,
+        slide: &PptxSlide,
+        subtitle_lines: &[String],
+        background: &str,
+        slide_num: usize,
+    ) -> Result<()> {
+        let lang = &self.language;
+        let subtitle_paragraphs = subtitle_lines
+            .iter()
+            .map(|line| {
+                let direction = if is_rtl_text(line) {
+                    r#"<a:pPr rtl="1"/>"#
+                } else {
+                    ""
+                };
+                format!(
+                    r#"
+                    <a:p>{direction}
+                        <a:r>
+                            <a:rPr lang="{lang}"/>
+                            <a:t>{}</a:t>
+                        </a:r>
+                    </a:p>"#,
+                    escape_xml(line)
+                )
+            })
+            .collect::<String>();
+        let title_text = slide.title.as_deref().unwrap_or_default();
+        let title_rtl = if is_rtl_text(title_text) {
+            r#" rtl="1""#
+        } else {
+            ""
+        };
 
-        for element in content {
-            match element {
-                PptxElement::Text(text) | PptxElement::Quote(text) => {
-                    shapes.push_str(&format!(
-                        r#"
+        let slide_xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+    <p:cSld>{}
+        <p:spTree>
+            <p:nvGrpSpPr>
+                <p:cNvPr id="1" name=""/>
+                <p:cNvGrpSpPr/>
+                <p:nvPr/>
+            </p:nvGrpSpPr>
+            <p:grpSpPr>
+                <a:xfrm>
+                    <a:off x="0" y="0"/>
+                    <a:ext cx="0" cy="0"/>
+                    <a:chOff x="0" y="0"/>
+                    <a:chExt cx="0" cy="0"/>
+                </a:xfrm>
+            </p:grpSpPr>
             <p:sp>
                 <p:nvSpPr>
-                    <p:cNvPr id="{}" name="Content {}"/>
+                    <p:cNvPr id="2" name="Title 1"/>
                     <p:cNvSpPr>
                         <a:spLocks noGrp="1"/>
                     </p:cNvSpPr>
                     <p:nvPr>
-                        <p:ph type="body" idx="1"/>
+                        <p:ph type="ctrTitle"/>
                     </p:nvPr>
                 </p:nvSpPr>
-                <p:spPr>
-                    <a:xfrm>
-                        <a:off x="685800" y="{}"/>
-                        <a:ext cx="7772400" cy="1200000"/>
-                    </a:xfrm>
-                </p:spPr>
+                <p:spPr>{}</p:spPr>
                 <p:txBody>
-                    <a:bodyPr/>
+                    <a:bodyPr anchor="b"/>
                     <a:lstStyle/>
                     <a:p>
+                        <a:pPr algn="ctr"{title_rtl}/>
                         <a:r>
-                            <a:rPr lang="en-US"/>
+                            <a:rPr lang="{lang}"/>
                             <a:t>{}</a:t>
                         </a:r>
-                        <a:endParaRPr lang="en-US"/>
                     </a:p>
                 </p:txBody>
-            </p:sp>"#,
-                        shape_id,
-                        shape_id,
-                        y_pos,
-                        escape_xml(text)
-                    ));
-
-                    shape_id += 1;
-                    y_pos += 400000;
-                }
-                PptxElement::BulletList(items) | PptxElement::NumberedList(items) => {
-                    let list_items = items
-                        .iter()
-                        .map(|item| {
-                            format!(
-                                r#"
-                    <a:p>
-                        <a:pPr lvl="0"/>
-                        <a:r>
-                            <a:rPr lang="en-US"/>
-                            <a:t>{}</a:t>
-                        </a:r>
-                        <a:endParaRPr lang="en-US"/>
-                    </a:p>"#,
-                                escape_xml(item)
-                            )
-                        })
-                        .collect::<String>();
+            </p:sp>
+            <p:sp>
+                <p:nvSpPr>
+                    <p:cNvPr id="3" name="Subtitle 2"/>
+                    <p:cNvSpPr>
+                        <a:spLocks noGrp="1"/>
+                    </p:cNvSpPr>
+                    <p:nvPr>
+                        <p:ph type="subTitle" idx="1"/>
+                    </p:nvPr>
+                </p:nvSpPr>
+                <p:spPr>{}</p:spPr>
+                <p:txBody>
+                    <a:bodyPr/>
+                    <a:lstStyle>
+                        <a:lvl1pPr algn="ctr">
+                            <a:defRPr sz="2000"/>
+                        </a:lvl1pPr>
+                    </a:lstStyle>{}
+                </p:txBody>
+            </p:sp>{}
+        </p:spTree>
+    </p:cSld>
+    <p:clrMapOvr>
+        <a:masterClrMapping/>
+    </p:clrMapOvr>{}
+</p:sld>"#,
+            background,
+            self.placeholder_xfrm(685800, 2130425, 7772400, 1470025),
+            escape_xml(title_text),
+            self.placeholder_xfrm(1371600, 3886200, 6400800, 1752600),
+            subtitle_paragraphs,
+            if self.footer.show_on_title_slide {
+                self.footer_shapes(slide_num, true)
+            } else {
+                String::new()
+            },
+            self.transition_xml(slide)
+        );
+
+        zip.start_file(
+            format!("ppt/slides/slide{}.xml", slide_num),
+            Default::default(),
+        )?;
+        zip.write_all(slide_xml.as_bytes())?;
+        Ok(())
+    }
+
+    /// Slide masters as `(sldMasterId id, part name)`
+    fn master_parts(&self) -> Vec<(u32, String)> {
+        match &self.reference {
+            Some(reference) => reference.masters().to_vec(),
+            None => vec![(2147483648, "ppt/slideMasters/slideMaster1.xml".to_string())],
+        }
+    }
+
+    /// Content type overrides for the masters, layouts, themes and media
+    fn master_content_types(&self) -> String {
+        let types = match &self.reference {
+            Some(reference) => reference
+                .content_types()
+                .map(|(name, content_type)| (name.to_string(), content_type.to_string()))
+                .collect(),
+            None => vec![
+                (
+                    "ppt/slideMasters/slideMaster1.xml".to_string(),
+                    "application/vnd.openxmlformats-officedocument.presentationml.slideMaster+xml"
+                        .to_string(),
+                ),
+                (
+                    "ppt/slideLayouts/slideLayout1.xml".to_string(),
+                    "application/vnd.openxmlformats-officedocument.presentationml.slideLayout+xml"
+                        .to_string(),
+                ),
+                (
+                    "ppt/theme/theme1.xml".to_string(),
+                    "application/vnd.openxmlformats-officedocument.theme+xml".to_string(),
+                ),
+            ],
+        };
+        types
+            .iter()
+            .map(|(name, content_type)| {
+                format!(
+                    r#"
+    <Override PartName="/{}" ContentType="{}"/>"#,
+                    escape_xml(name),
+                    escape_xml(content_type)
+                )
+            })
+            .collect()
+    }
+
+    /// Layout part a slide is based on
+    fn layout_part(&self, slide: &PptxSlide) -> &str {
+        match &self.reference {
+            Some(reference) => reference.layout(matches!(slide.kind, PptxSlideKind::Title { .. })),
+            None => "ppt/slideLayouts/slideLayout1.xml",
+        }
+    }
+
+    /// `a:xfrm` for a title or subtitle placeholder. With a reference document
+    /// the placeholder keeps the position its layout gives it.
+    fn placeholder_xfrm(&self, x: i64, y: i64, cx: i64, cy: i64) -> String {
+        match self.reference {
+            Some(_) => String::new(),
+            None => format!("<a:xfrm>{}</a:xfrm>", self.frame(x, y, cx, cy)),
+        }
+    }
+
+    /// Body placeholder of the reference layout for content slides, if any.
+    /// Without one, text and lists are laid out as on the built-in layout.
+    fn body_placeholder(&self) -> Option<&Placeholder> {
+        self.reference
+            .as_ref()
+            .and_then(|reference| reference.placeholder(false, &["body", "obj"]))
+    }
+
+    /// Parse a `background` setting and register its image, if any
+    fn background_setting(
+        &mut self,
+        spec: &str,
+        overlay: Option<&str>,
+    ) -> Result<Option<Background>> {
+        let mut background = Background::parse(spec)?;
+        if let Some(overlay) = overlay {
+            background = background.with_overlay(parse_overlay(overlay)?);
+        }
+        if let Some(path) = background.image_path() {
+            self.media.add(path)?;
+        }
+        Ok(Some(background))
+    }
+
+    /// `p:bg` for the master: the deck background or the theme color
+    fn master_background_xml(&self) -> String {
+        let background = self
+            .background
+            .clone()
+            .unwrap_or_else(|| Background::Solid(self.template.get_theme_colors().background));
+        let image_rel = background.image_path().map(|_| MASTER_IMAGE_REL);
+        background.to_xml(image_rel)
+    }
+
+    /// `p:bg` for a slide with its own background. A reference document keeps
+    /// its master as-is, so there the deck background goes on every slide.
+    fn slide_background_xml(&self, slide: &PptxSlide, context: &mut SlideContext) -> String {
+        let deck = self.reference.as_ref().and(self.background.as_ref());
+        let Some(background) = slide.background.as_ref().or(deck) else {
+            return String::new();
+        };
+        let image_rel = background
+            .image_path()
+            .and_then(|path| self.media.part_name(path))
+            .map(|part| context.media(part));
+        background.to_xml(image_rel.as_deref())
+    }
+
+    /// Logo picture and watermark text box, drawn once on the master so
+    /// every slide shows them
+    fn master_decorations_xml(&self) -> String {
+        let lang = &self.language;
+        let mut xml = String::new();
+        if let Some(logo) = &self.logo {
+            let width = i64::from(logo.width);
+            let height = media::image_size(&logo.path)
+                .map(|(px_width, px_height)| width * i64::from(px_height) / i64::from(px_width))
+                .unwrap_or(width);
+            let (slide_width, slide_height) = (
+                i64::from(self.layout.slide_width),
+                i64::from(self.layout.slide_height),
+            );
+            let x = match logo.position {
+                LogoPosition::TopLeft | LogoPosition::BottomLeft => LOGO_INSET,
+                LogoPosition::TopRight | LogoPosition::BottomRight => {
+                    slide_width - width - LOGO_INSET
+                }
+            };
+            let y = match logo.position {
+                LogoPosition::TopLeft | LogoPosition::TopRight => LOGO_INSET,
+                LogoPosition::BottomLeft | LogoPosition::BottomRight => {
+                    slide_height - height - LOGO_INSET
+                }
+            };
+            let alpha = match logo.opacity {
+                100.. => String::new(),
+                opacity => format!(r#"<a:alphaModFix amt="{}"/>"#, u32::from(opacity) * 1000),
+            };
+            xml.push_str(&format!(
+                r#"
+            <p:pic>
+                <p:nvPicPr>
+                    <p:cNvPr id="{}" name="Logo"/>
+                    <p:cNvPicPr>
+                        <a:picLocks noChangeAspect="1"/>
+                    </p:cNvPicPr>
+                    <p:nvPr userDrawn="1"/>
+                </p:nvPicPr>
+                <p:blipFill>
+                    <a:blip r:embed="{}">{}</a:blip>
+                    <a:stretch>
+                        <a:fillRect/>
+                    </a:stretch>
+                </p:blipFill>
+                <p:spPr>
+                    <a:xfrm>
+                        <a:off x="{}" y="{}"/>
+                        <a:ext cx="{}" cy="{}"/>
+                    </a:xfrm>
+                    <a:prstGeom prst="rect">
+                        <a:avLst/>
+                    </a:prstGeom>
+                </p:spPr>
+            </p:pic>"#,
+                MASTER_DECORATION_SHAPE_ID, MASTER_LOGO_REL, alpha, x, y, width, height
+            ));
+        }
+        if let Some(text) = &self.watermark {
+            xml.push_str(&format!(
+                r#"
+            <p:sp>
+                <p:nvSpPr>
+                    <p:cNvPr id="{}" name="Watermark"/>
+                    <p:cNvSpPr txBox="1"/>
+                    <p:nvPr userDrawn="1"/>
+                </p:nvSpPr>
+                <p:spPr>
+                    <a:xfrm rot="18900000">
+                        {}
+                    </a:xfrm>
+                    <a:prstGeom prst="rect">
+                        <a:avLst/>
+                    </a:prstGeom>
+                    <a:noFill/>
+                </p:spPr>
+                <p:txBody>
+                    <a:bodyPr wrap="none" anchor="ctr">
+                        <a:noAutofit/>
+                    </a:bodyPr>
+                    <a:lstStyle/>
+                    <a:p>
+                        <a:pPr algn="ctr"/>
+                        <a:r>
+                            <a:rPr lang="{lang}" sz="7200" b="1">
+                                <a:solidFill>
+                                    <a:srgbClr val="{}">
+                                        <a:alpha val="25000"/>
+                                    </a:srgbClr>
+                                </a:solidFill>
+                            </a:rPr>
+                            <a:t>{}</a:t>
+                        </a:r>
+                    </a:p>
+                </p:txBody>
+            </p:sp>"#,
+                MASTER_DECORATION_SHAPE_ID + 1,
+                self.frame(457200, 2743200, 8229600, 1371600),
+                self.template.get_theme_colors().text_secondary,
+                escape_xml(text)
+            ));
+        }
+        xml
+    }
+
+    /// Master relationship to the logo image, if there is a logo
+    fn master_logo_relationship(&self) -> String {
+        self.logo
+            .as_ref()
+            .and_then(|logo| self.media.part_name(&logo.path))
+            .map(|part| {
+                format!(
+                    r#"
+    <Relationship Id="{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../{}"/>"#,
+                    MASTER_LOGO_REL,
+                    part.trim_start_matches("ppt/")
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    /// The slide's own transition, else the deck transition
+    fn transition_xml(&self, slide: &PptxSlide) -> String {
+        slide
+            .transition
+            .or(self.transition)
+            .map(|transition| transition.to_xml())
+            .unwrap_or_default()
+    }
+
+    /// Master footer placeholders, which own the footer geometry
+    fn master_footer_placeholders(&self) -> String {
+        let lang = &self.language;
+        MASTER_FOOTER_PLACEHOLDERS
+            .iter()
+            .enumerate()
+            .map(|(i, (ph_type, idx, x, width, align))| {
+                let size = if *ph_type == "dt" { "half" } else { "quarter" };
+                let body = match *ph_type {
+                    "sldNum" => format!(r#"<a:fld id="{{B6F15528-21DE-4FAA-801E-634DDDAF4B2B}}" type="slidenum"><a:rPr lang="{lang}"/><a:t>‹#›</a:t></a:fld>"#),
+                    _ => String::new(),
+                };
+                format!(
+                    r#"
+                <p:sp>
+                    <p:nvSpPr>
+                        <p:cNvPr id="{}" name="{} Placeholder {}"/>
+                        <p:cNvSpPr>
+                            <a:spLocks noGrp="1"/>
+                        </p:cNvSpPr>
+                        <p:nvPr>
+                            <p:ph type="{}" sz="{}" idx="{}"/>
+                        </p:nvPr>
+                    </p:nvSpPr>
+                    <p:spPr>
+                        <a:xfrm>
+                            {}
+                        </a:xfrm>
+                        <a:prstGeom prst="rect">
+                            <a:avLst/>
+                        </a:prstGeom>
+                    </p:spPr>
+                    <p:txBody>
+                        <a:bodyPr vert="horz" anchor="ctr"/>
+                        <a:lstStyle>
+                            <a:lvl1pPr algn="{}">
+                                <a:defRPr sz="1200"/>
+                            </a:lvl1pPr>
+                        </a:lstStyle>
+                        <a:p>{}<a:endParaRPr lang="{lang}"/></a:p>
+                    </p:txBody>
+                </p:sp>"#,
+                    i + 2,
+                    ph_type,
+                    i + 1,
+                    ph_type,
+                    size,
+                    idx,
+                    self.frame(*x, FOOTER_Y, *width, FOOTER_HEIGHT),
+                    align,
+                    body
+                )
+            })
+            .collect()
+    }
+
+    /// `a:off` and `a:ext` for a box laid out on the 4:3 reference slide,
+    /// scaled to the chosen slide size
+    fn frame(&self, x: i64, y: i64, cx: i64, cy: i64) -> String {
+        format!(
+            r#"<a:off x="{}" y="{}"/><a:ext cx="{}" cy="{}"/>"#,
+            self.scale_x(x),
+            self.scale_y(y),
+            self.scale_x(cx),
+            self.scale_y(cy)
+        )
+    }
+
+    /// `a:tbl` with a header row and banded body rows in the template's
+    /// table colors. Short rows are padded with empty cells.
+    fn table_xml(
+        &self,
+        headers: &[InlineText],
+        rows: &[Vec<InlineText>],
+        context: &mut SlideContext,
+    ) -> String {
+        let lang = &self.language;
+        let style = self.template.get_table_style();
+        let columns = headers.len().max(1);
+        let column_width = self.scale_x(7772400) / columns as i64;
+        let row_height = self.scale_y(TABLE_ROW_HEIGHT);
+
+        let empty = InlineText::default();
+        let mut cell = |text: &InlineText, fill: &str, color: &str, header: bool| {
+            let border = |side: &str| {
+                format!(
+                    r#"<a:{side} w="12700"><a:solidFill><a:srgbClr val="{}"/></a:solidFill></a:{side}>"#,
+                    style.border
+                )
+            };
+            format!(
+                r#"
+                            <a:tc>
+                                <a:txBody>
+                                    <a:bodyPr/>
+                                    <a:lstStyle/>
+                                    <a:p>{}
+                                        {}
+                                    </a:p>
+                                </a:txBody>
+                                <a:tcPr>
+                                    {}{}{}{}
+                                    <a:solidFill>
+                                        <a:srgbClr val="{}"/>
+                                    </a:solidFill>
+                                </a:tcPr>
+                            </a:tc>"#,
+                direction_ppr(&text.to_string()),
+                colored_text_runs(
+                    text,
+                    &if header {
+                        format!(r#"lang="{lang}" sz="1400" b="1""#)
+                    } else {
+                        format!(r#"lang="{lang}" sz="1400""#)
+                    },
+                    Some(color),
+                    context
+                ),
+                border("lnL"),
+                border("lnR"),
+                border("lnT"),
+                border("lnB"),
+                fill
+            )
+        };
+
+        let mut table_rows = String::new();
+        let header_cells: String = (0..columns)
+            .map(|i| {
+                let text = headers.get(i).unwrap_or(&empty);
+                cell(text, &style.header_background, &style.header_text, true)
+            })
+            .collect();
+        table_rows.push_str(&format!(
+            r#"
+                        <a:tr h="{}">{}
+                        </a:tr>"#,
+            row_height, header_cells
+        ));
+        for (index, row) in rows.iter().enumerate() {
+            let fill = if index % 2 == 0 {
+                &style.row_background
+            } else {
+                &style.banded_row_background
+            };
+            let cells: String = (0..columns)
+                .map(|i| {
+                    let text = row.get(i).unwrap_or(&empty);
+                    cell(text, fill, &style.text, false)
+                })
+                .collect();
+            table_rows.push_str(&format!(
+                r#"
+                        <a:tr h="{}">{}
+                        </a:tr>"#,
+                row_height, cells
+            ));
+        }
+
+        format!(
+            r#"<a:tbl>
+                            <a:tblPr firstRow="{}" bandRow="1"/>
+                            <a:tblGrid>{}</a:tblGrid>{}
+                        </a:tbl>"#,
+            // Mark the header row so screen readers announce the column names
+            u8::from(headers.iter().any(|header| !header.is_empty())),
+            format!(r#"<a:gridCol w="{}"/>"#, column_width).repeat(columns),
+            table_rows
+        )
+    }
+
+    fn scale_x(&self, emu: i64) -> i64 {
+        emu * i64::from(self.layout.slide_width) / REFERENCE_SLIDE_WIDTH
+    }
+
+    fn scale_y(&self, emu: i64) -> i64 {
+        emu * i64::from(self.layout.slide_height) / REFERENCE_SLIDE_HEIGHT
+    }
+
+    fn slide_size_xml(&self) -> String {
+        let size = self.layout.slide_size();
+        let (width, height) = size.dimensions();
+        match size.sld_sz_type() {
+            Some(size_type) => format!(
+                r#"<p:sldSz cx="{}" cy="{}" type="{}"/>"#,
+                width, height, size_type
+            ),
+            None => format!(r#"<p:sldSz cx="{}" cy="{}"/>"#, width, height),
+        }
+    }
+
+    /// `p:hf` for the master and layout, switching off the footer parts not in use
+    fn header_footer_xml(&self) -> String {
+        format!(
+            r#"<p:hf hdr="0" ftr="{}" dt="{}" sldNum="{}"/>"#,
+            u8::from(self.footer.text.is_some()),
+            u8::from(self.footer.shows_date()),
+            u8::from(self.footer.slide_number)
+        )
+    }
+
+    /// Footer, date and slide number placeholders for one slide. Geometry is
+    /// inherited from the layout, which inherits it from the master. With a
+    /// reference document, parts its layout has no placeholder for are left out.
+    fn footer_shapes(&self, slide_num: usize, title_slide: bool) -> String {
+        let lang = &self.language;
+        let footer = &self.footer;
+        let mut shapes = String::new();
+        let idx = |ph_type: &str, default: usize| match &self.reference {
+            Some(reference) => reference
+                .placeholder(title_slide, &[ph_type])
+                .map(|placeholder| placeholder.idx as usize),
+            None => Some(default),
+        };
+
+        if let (true, Some(idx)) = (footer.shows_date(), idx("dt", 10)) {
+            let date = match &footer.fixed_date {
+                Some(date) => format!(
+                    r#"<a:r><a:rPr lang="{lang}"/><a:t>{}</a:t></a:r>"#,
+                    escape_xml(date)
+                ),
+                None => format!(
+                    r#"<a:fld id="{{{}}}" type="datetime1"><a:rPr lang="{lang}"/><a:t>{}</a:t></a:fld>"#,
+                    Uuid::new_v4().to_string().to_uppercase(),
+                    self.metadata.created.format("%-m/%-d/%Y")
+                ),
+            };
+            shapes.push_str(&footer_placeholder_xml(
+                FOOTER_SHAPE_ID_BASE,
+                "Date Placeholder",
+                "dt",
+                idx,
+                &date,
+                &self.language,
+            ));
+        }
+        if let (Some(text), Some(idx)) = (&footer.text, idx("ftr", 11)) {
+            let runs = format!(
+                r#"<a:r><a:rPr lang="{lang}"/><a:t>{}</a:t></a:r>"#,
+                escape_xml(text)
+            );
+            shapes.push_str(&footer_placeholder_xml(
+                FOOTER_SHAPE_ID_BASE + 1,
+                "Footer Placeholder",
+                "ftr",
+                idx,
+                &runs,
+                &self.language,
+            ));
+        }
+        if let (true, Some(idx)) = (footer.slide_number, idx("sldNum", 12)) {
+            let field = format!(
+                r#"<a:fld id="{{{}}}" type="slidenum"><a:rPr lang="{lang}"/><a:t>{}</a:t></a:fld>"#,
+                Uuid::new_v4().to_string().to_uppercase(),
+                slide_num
+            );
+            shapes.push_str(&footer_placeholder_xml(
+                FOOTER_SHAPE_ID_BASE + 2,
+                "Slide Number Placeholder",
+                "sldNum",
+                idx,
+                &field,
+                &self.language,
+            ));
+        }
+
+        shapes
+    }
+
+    fn generate_content_shapes(
+        &self,
+        content: &[PptxElement],
+        context: &mut SlideContext,
+    ) -> String {
+        let lang = &self.language;
+        if content.is_empty() {
+            return String::new();
+        }
+
+        // With a reference document, text and lists share one shape in the
+        // layout's body placeholder and take its position and bullet styles
+        let body = self.body_placeholder();
+        let mut body_xml = String::new();
+        let mut body_paragraphs = 0;
 
+        let mut shapes = String::new();
+        let mut shape_id = if body.is_some() { BODY_SHAPE_ID + 1 } else { 3 };
+        let mut y_pos = 1828800; // Starting Y position below title
+
+        for element in content {
+            match element {
+                PptxElement::Text(text) if body.is_some() => {
+                    body_xml.push_str(&format!(
+                        r#"
+                    <a:p>
+                        <a:pPr marL="0" indent="0"{}>
+                            <a:buNone/>
+                        </a:pPr>
+                        {}
+                        <a:endParaRPr lang="{lang}"/>
+                    </a:p>"#,
+                        direction_attrs(&text.to_string()),
+                        text_runs(text, &format!(r#"lang="{lang}""#), context)
+                    ));
+                    body_paragraphs += 1;
+                }
+                PptxElement::Text(text) => {
+                    let direction = direction_ppr(&text.to_string());
                     shapes.push_str(&format!(
                         r#"
             <p:sp>
@@ -623,76 +2050,215 @@ impl PresentationBuilder {
                 </p:nvSpPr>
                 <p:spPr>
                     <a:xfrm>
-                        <a:off x="685800" y="{}"/>
-                        <a:ext cx="7772400" cy="1600000"/>
+                        {}
                     </a:xfrm>
                 </p:spPr>
                 <p:txBody>
                     <a:bodyPr/>
                     <a:lstStyle/>
-                    {}
+                    <a:p>{direction}
+                        {}
+                        <a:endParaRPr lang="{lang}"/>
+                    </a:p>
                 </p:txBody>
             </p:sp>"#,
-                        shape_id, shape_id, y_pos, list_items
+                        shape_id,
+                        shape_id,
+                        self.frame(685800, y_pos, 7772400, 1200000),
+                        text_runs(text, &format!(r#"lang="{lang}""#), context)
                     ));
 
                     shape_id += 1;
-                    y_pos += 500000;
+                    y_pos += 400000;
                 }
-                PptxElement::Code { content, .. } => {
+                PptxElement::Quote(text) => {
+                    let accent = self.template.get_theme_colors().accent_1;
+                    let direction = direction_ppr(&text.to_string());
                     shapes.push_str(&format!(
                         r#"
             <p:sp>
                 <p:nvSpPr>
-                    <p:cNvPr id="{}" name="Code {}"/>
-                    <p:cNvSpPr>
-                        <a:spLocks noGrp="1"/>
-                    </p:cNvSpPr>
-                    <p:nvPr>
-                        <p:ph type="body" idx="2"/>
-                    </p:nvPr>
+                    <p:cNvPr id="{}" name="Quote Bar {}"/>
+                    <p:cNvSpPr/>
+                    <p:nvPr/>
+                </p:nvSpPr>
+                <p:spPr>
+                    <a:xfrm>
+                        {}
+                    </a:xfrm>
+                    <a:prstGeom prst="rect">
+                        <a:avLst/>
+                    </a:prstGeom>
+                    <a:solidFill>
+                        <a:srgbClr val="{}"/>
+                    </a:solidFill>
+                    <a:ln>
+                        <a:noFill/>
+                    </a:ln>
+                </p:spPr>
+            </p:sp>
+            <p:sp>
+                <p:nvSpPr>
+                    <p:cNvPr id="{}" name="Quote {}"/>
+                    <p:cNvSpPr txBox="1"/>
+                    <p:nvPr/>
+                </p:nvSpPr>
+                <p:spPr>
+                    <a:xfrm>
+                        {}
+                    </a:xfrm>
+                    <a:prstGeom prst="rect">
+                        <a:avLst/>
+                    </a:prstGeom>
+                    <a:noFill/>
+                </p:spPr>
+                <p:txBody>
+                    <a:bodyPr anchor="ctr"/>
+                    <a:lstStyle/>
+                    <a:p>{direction}
+                        {}
+                        <a:endParaRPr lang="{lang}"/>
+                    </a:p>
+                </p:txBody>
+            </p:sp>"#,
+                        shape_id,
+                        shape_id,
+                        self.frame(685800, y_pos, 76200, 800000),
+                        accent,
+                        shape_id + 1,
+                        shape_id + 1,
+                        self.frame(914400, y_pos, 7543800, 800000),
+                        text_runs(text, &format!(r#"lang="{lang}" sz="2000" i="1""#), context)
+                    ));
+
+                    shape_id += 2;
+                    y_pos += 900000;
+                }
+                PptxElement::Callout { kind, text } => {
+                    let (glyph, accent, fill) =
+                        callout_style(*kind, &self.template.get_theme_colors().background);
+                    let direction = direction_ppr(&text.to_string());
+                    shapes.push_str(&format!(
+                        r#"
+            <p:sp>
+                <p:nvSpPr>
+                    <p:cNvPr id="{}" name="Callout {}"/>
+                    <p:cNvSpPr/>
+                    <p:nvPr/>
                 </p:nvSpPr>
                 <p:spPr>
                     <a:xfrm>
-                        <a:off x="685800" y="{}"/>
-                        <a:ext cx="7772400" cy="1200000"/>
+                        {}
                     </a:xfrm>
+                    <a:prstGeom prst="roundRect">
+                        <a:avLst/>
+                    </a:prstGeom>
                     <a:solidFill>
-                        <a:srgbClr val="F8F8F8"/>
+                        <a:srgbClr val="{}"/>
                     </a:solidFill>
+                    <a:ln w="19050">
+                        <a:solidFill>
+                            <a:srgbClr val="{}"/>
+                        </a:solidFill>
+                    </a:ln>
                 </p:spPr>
                 <p:txBody>
-                    <a:bodyPr/>
+                    <a:bodyPr lIns="182880" rIns="182880" anchor="ctr"/>
                     <a:lstStyle/>
-                    <a:p>
+                    <a:p>{direction}
                         <a:r>
-                            <a:rPr lang="en-US">
-                                <a:latin typeface="Consolas"/>
+                            <a:rPr lang="{lang}" sz="1800" b="1">
+                                <a:solidFill>
+                                    <a:srgbClr val="{}"/>
+                                </a:solidFill>
                             </a:rPr>
-                            <a:t>{}</a:t>
+                            <a:t>{} {}</a:t>
                         </a:r>
-                        <a:endParaRPr lang="en-US"/>
+                    </a:p>
+                    <a:p>{direction}
+                        {}
+                        <a:endParaRPr lang="{lang}"/>
                     </a:p>
                 </p:txBody>
             </p:sp>"#,
                         shape_id,
                         shape_id,
-                        y_pos,
-                        escape_xml(content)
+                        self.frame(685800, y_pos, 7772400, 900000),
+                        fill,
+                        accent,
+                        accent,
+                        glyph,
+                        kind.label(),
+                        text_runs(text, &format!(r#"lang="{lang}" sz="1800""#), context)
                     ));
 
                     shape_id += 1;
-                    y_pos += 600000;
+                    y_pos += 1000000;
                 }
-                _ => {
-                    // For now, convert other elements to text
-                    let text = match element {
-                        PptxElement::Image { alt, .. } => format!("[Image: {}]", alt),
-                        PptxElement::Table { headers, .. } => {
-                            format!("[Table: {}]", headers.join(", "))
-                        }
-                        _ => "[Unsupported element]".to_string(),
+                PptxElement::BulletList { .. }
+                | PptxElement::NumberedList { .. }
+                | PptxElement::LinkList(_) => {
+                    // (runs, paragraph direction) per item
+                    let runs: Vec<(String, &str)> = match element {
+                        PptxElement::LinkList(links) => links
+                            .iter()
+                            .map(|(label, slide_num)| {
+                                let runs = format!(
+                                    r#"<a:r><a:rPr lang="{lang}">{}</a:rPr><a:t>{}</a:t></a:r>"#,
+                                    context.link(LinkTarget::Slide(*slide_num)),
+                                    escape_xml(label)
+                                );
+                                (runs, direction_attrs(label))
+                            })
+                            .collect(),
+                        PptxElement::BulletList { items, .. }
+                        | PptxElement::NumberedList { items, .. } => items
+                            .iter()
+                            .map(|item| {
+                                let runs = text_runs(item, &format!(r#"lang="{lang}""#), context);
+                                (runs, direction_attrs(&item.to_string()))
+                            })
+                            .collect(),
+                        _ => unreachable!(),
                     };
+                    if let PptxElement::BulletList {
+                        incremental: true, ..
+                    }
+                    | PptxElement::NumberedList {
+                        incremental: true, ..
+                    } = element
+                    {
+                        if !runs.is_empty() {
+                            context.builds.push(ParagraphBuild {
+                                shape_id: if body.is_some() {
+                                    BODY_SHAPE_ID
+                                } else {
+                                    shape_id
+                                },
+                                first: body_paragraphs,
+                                paragraphs: runs.len(),
+                            });
+                        }
+                    }
+                    let list_items = runs
+                        .iter()
+                        .map(|(item_runs, direction)| {
+                            format!(
+                                r#"
+                    <a:p>
+                        <a:pPr lvl="0"{}/>
+                        {}
+                        <a:endParaRPr lang="{lang}"/>
+                    </a:p>"#,
+                                direction, item_runs
+                            )
+                        })
+                        .collect::<String>();
+                    if body.is_some() {
+                        body_xml.push_str(&list_items);
+                        body_paragraphs += runs.len();
+                        continue;
+                    }
 
                     shapes.push_str(&format!(
                         r#"
@@ -702,53 +2268,290 @@ impl PresentationBuilder {
                     <p:cNvSpPr>
                         <a:spLocks noGrp="1"/>
                     </p:cNvSpPr>
+                    <p:nvPr>
+                        <p:ph type="body" idx="1"/>
+                    </p:nvPr>
+                </p:nvSpPr>
+                <p:spPr>
+                    <a:xfrm>
+                        {}
+                    </a:xfrm>
+                </p:spPr>
+                <p:txBody>
+                    <a:bodyPr/>
+                    <a:lstStyle/>
+                    {}
+                </p:txBody>
+            </p:sp>"#,
+                        shape_id,
+                        shape_id,
+                        self.frame(685800, y_pos, 7772400, 1600000),
+                        list_items
+                    ));
+
+                    shape_id += 1;
+                    y_pos += 500000;
+                }
+                PptxElement::Code { content, .. } => {
+                    let palette = self.template.get_code_palette();
+                    shapes.push_str(&format!(
+                        r#"
+            <p:sp>
+                <p:nvSpPr>
+                    <p:cNvPr id="{}" name="Code {}"/>
+                    <p:cNvSpPr>
+                        <a:spLocks noGrp="1"/>
+                    </p:cNvSpPr>
                     <p:nvPr>
                         <p:ph type="body" idx="2"/>
                     </p:nvPr>
                 </p:nvSpPr>
                 <p:spPr>
                     <a:xfrm>
-                        <a:off x="685800" y="{}"/>
-                        <a:ext cx="7772400" cy="600000"/>
+                        {}
                     </a:xfrm>
+                    <a:solidFill>
+                        <a:srgbClr val="{}"/>
+                    </a:solidFill>
                 </p:spPr>
                 <p:txBody>
                     <a:bodyPr/>
                     <a:lstStyle/>
                     <a:p>
                         <a:r>
-                            <a:rPr lang="en-US"/>
+                            <a:rPr lang="{lang}">
+                                <a:solidFill>
+                                    <a:srgbClr val="{}"/>
+                                </a:solidFill>
+                                <a:latin typeface="{}"/>
+                            </a:rPr>
                             <a:t>{}</a:t>
                         </a:r>
-                        <a:endParaRPr lang="en-US"/>
-                        </a:p>
-                        </p:txBody>
-                        </p:sp>"#,
+                        <a:endParaRPr lang="{lang}"/>
+                    </a:p>
+                </p:txBody>
+            </p:sp>"#,
                         shape_id,
                         shape_id,
-                        y_pos,
-                        escape_xml(&text)
+                        self.frame(685800, y_pos, 7772400, 1200000),
+                        palette.background,
+                        palette.text,
+                        escape_xml(&self.template.get_fonts().code_font),
+                        escape_xml(content)
                     ));
 
                     shape_id += 1;
-                    y_pos += 400000;
+                    y_pos += 600000;
                 }
-            }
-        }
-
-        shapes
-    }
+                PptxElement::Table { headers, rows } => {
+                    let row_count = rows.len() as i64 + 1;
+                    shapes.push_str(&format!(
+                        r#"
+            <p:graphicFrame>
+                <p:nvGraphicFramePr>
+                    <p:cNvPr id="{}" name="Table {}"/>
+                    <p:cNvGraphicFramePr>
+                        <a:graphicFrameLocks noGrp="1"/>
+                    </p:cNvGraphicFramePr>
+                    <p:nvPr/>
+                </p:nvGraphicFramePr>
+                <p:xfrm>
+                    {}
+                </p:xfrm>
+                <a:graphic>
+                    <a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/table">
+                        {}
+                    </a:graphicData>
+                </a:graphic>
+            </p:graphicFrame>"#,
+                        shape_id,
+                        shape_id,
+                        self.frame(685800, y_pos, 7772400, TABLE_ROW_HEIGHT * row_count),
+                        self.table_xml(headers, rows, context)
+                    ));
+
+                    shape_id += 1;
+                    y_pos += TABLE_ROW_HEIGHT * row_count + 228600;
+                }
+                PptxElement::Image {
+                    alt,
+                    decorative,
+                    path: Some(path),
+                    ..
+                } => {
+                    let image_rel = self.media.part_name(path).map(|part| context.media(part));
+                    // Fit the image in the content area, keeping its aspect ratio
+                    let (max_width, max_height) =
+                        (self.scale_x(7772400), self.scale_y(IMAGE_MAX_HEIGHT));
+                    let (width, height) = match media::image_size(path) {
+                        Some((px_width, px_height)) if px_width > 0 && px_height > 0 => {
+                            let (px_width, px_height) = (i64::from(px_width), i64::from(px_height));
+                            if px_width * max_height > px_height * max_width {
+                                (max_width, max_width * px_height / px_width)
+                            } else {
+                                (max_height * px_width / px_height, max_height)
+                            }
+                        }
+                        _ => (max_width, max_height),
+                    };
+                    shapes.push_str(&format!(
+                        r#"
+            <p:pic>
+                <p:nvPicPr>
+                    {}
+                    <p:cNvPicPr>
+                        <a:picLocks noChangeAspect="1"/>
+                    </p:cNvPicPr>
+                    <p:nvPr/>
+                </p:nvPicPr>
+                <p:blipFill>
+                    <a:blip r:embed="{}"/>
+                    <a:stretch>
+                        <a:fillRect/>
+                    </a:stretch>
+                </p:blipFill>
+                <p:spPr>
+                    <a:xfrm>
+                        <a:off x="{}" y="{}"/>
+                        <a:ext cx="{}" cy="{}"/>
+                    </a:xfrm>
+                    <a:prstGeom prst="rect">
+                        <a:avLst/>
+                    </a:prstGeom>
+                </p:spPr>
+            </p:pic>"#,
+                        shape_properties(
+                            shape_id,
+                            &format!("Picture {}", shape_id),
+                            alt,
+                            *decorative
+                        ),
+                        image_rel.unwrap_or_default(),
+                        self.scale_x(685800) + (max_width - width) / 2,
+                        self.scale_y(y_pos),
+                        width,
+                        height
+                    ));
+
+                    shape_id += 1;
+                    y_pos += IMAGE_MAX_HEIGHT * height / max_height + 228600;
+                }
+                _ => {
+                    // For now, convert other elements to text
+                    let (text, properties) = match element {
+                        PptxElement::Image {
+                            alt, decorative, ..
+                        } => (
+                            format!("[Image: {}]", alt),
+                            shape_properties(
+                                shape_id,
+                                &format!("Content {}", shape_id),
+                                alt,
+                                *decorative,
+                            ),
+                        ),
+                        _ => (
+                            "[Unsupported element]".to_string(),
+                            format!(
+                                r#"<p:cNvPr id="{}" name="Content {}"/>"#,
+                                shape_id, shape_id
+                            ),
+                        ),
+                    };
+
+                    shapes.push_str(&format!(
+                        r#"
+            <p:sp>
+                <p:nvSpPr>
+                    {}
+                    <p:cNvSpPr>
+                        <a:spLocks noGrp="1"/>
+                    </p:cNvSpPr>
+                    <p:nvPr>
+                        <p:ph type="body" idx="2"/>
+                    </p:nvPr>
+                </p:nvSpPr>
+                <p:spPr>
+                    <a:xfrm>
+                        {}
+                    </a:xfrm>
+                </p:spPr>
+                <p:txBody>
+                    <a:bodyPr/>
+                    <a:lstStyle/>
+                    <a:p>
+                        <a:r>
+                            <a:rPr lang="{lang}"/>
+                            <a:t>{}</a:t>
+                        </a:r>
+                        <a:endParaRPr lang="{lang}"/>
+                        </a:p>
+                        </p:txBody>
+                        </p:sp>"#,
+                        properties,
+                        self.frame(685800, y_pos, 7772400, 600000),
+                        escape_xml(&text)
+                    ));
+
+                    shape_id += 1;
+                    y_pos += 400000;
+                }
+            }
+        }
+
+        match body {
+            Some(placeholder) if !body_xml.is_empty() => format!(
+                r#"
+            <p:sp>
+                <p:nvSpPr>
+                    <p:cNvPr id="{}" name="Content Placeholder {}"/>
+                    <p:cNvSpPr>
+                        <a:spLocks noGrp="1"/>
+                    </p:cNvSpPr>
+                    <p:nvPr>
+                        {}
+                    </p:nvPr>
+                </p:nvSpPr>
+                <p:spPr/>
+                <p:txBody>
+                    <a:bodyPr/>
+                    <a:lstStyle/>{}
+                </p:txBody>
+            </p:sp>{}"#,
+                BODY_SHAPE_ID,
+                BODY_SHAPE_ID,
+                placeholder.to_xml(),
+                body_xml,
+                shapes
+            ),
+            _ => shapes,
+        }
+    }
 
     fn add_slide_relationships(
         &self,
         zip: &mut ZipWriter<Cursor<&mut Vec<u8>>>,
-        _slide: &PptxSlide,
+        slide: &PptxSlide,
+        context: &SlideContext,
         slide_num: usize,
     ) -> Result<()> {
-        let relationships = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+        let relationships = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
-    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideLayout" Target="../slideLayouts/slideLayout1.xml"/>
-</Relationships>"#;
+    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideLayout" Target="../{}"/>
+{}{}</Relationships>"#,
+            self.layout_part(slide).trim_start_matches("ppt/"),
+            context.relationships_xml(),
+            if slide.notes.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    r#"    <Relationship Id="{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide" Target="../notesSlides/notesSlide{}.xml"/>
+"#,
+                    NOTES_REL, slide_num
+                )
+            }
+        );
 
         zip.start_file(
             format!("ppt/slides/_rels/slide{}.xml.rels", slide_num),
@@ -759,226 +2562,458 @@ impl PresentationBuilder {
     }
 
     fn add_theme(&self, zip: &mut ZipWriter<Cursor<&mut Vec<u8>>>) -> Result<()> {
-        let theme = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-<a:theme xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" name="Office Theme">
-    <a:themeElements>
-        <a:clrScheme name="Office">
-            <a:dk1>
-                <a:sysClr val="windowText" lastClr="000000"/>
-            </a:dk1>
-            <a:lt1>
-                <a:sysClr val="window" lastClr="FFFFFF"/>
-            </a:lt1>
-            <a:dk2>
-                <a:srgbClr val="1F497D"/>
-            </a:dk2>
-            <a:lt2>
-                <a:srgbClr val="EEECE1"/>
-            </a:lt2>
-            <a:accent1>
-                <a:srgbClr val="4F81BD"/>
-            </a:accent1>
-            <a:accent2>
-                <a:srgbClr val="F79646"/>
-            </a:accent2>
-            <a:accent3>
-                <a:srgbClr val="9BBB59"/>
-            </a:accent3>
-            <a:accent4>
-                <a:srgbClr val="8064A2"/>
-            </a:accent4>
-            <a:accent5>
-                <a:srgbClr val="4BACC6"/>
-            </a:accent5>
-            <a:accent6>
-                <a:srgbClr val="F39646"/>
-            </a:accent6>
-            <a:hlink>
-                <a:srgbClr val="0000FF"/>
-            </a:hlink>
-            <a:folHlink>
-                <a:srgbClr val="800080"/>
-            </a:folHlink>
-        </a:clrScheme>
-        <a:fontScheme name="Office">
-            <a:majorFont>
-                <a:latin typeface="Calibri"/>
-                <a:ea typeface=""/>
-                <a:cs typeface=""/>
-            </a:majorFont>
-            <a:minorFont>
-                <a:latin typeface="Calibri"/>
-                <a:ea typeface=""/>
-                <a:cs typeface=""/>
-            </a:minorFont>
-        </a:fontScheme>
-        <a:fmtScheme name="Office">
-            <a:fillStyleLst>
-                <a:solidFill>
-                    <a:schemeClr val="phClr"/>
-                </a:solidFill>
-                <a:gradFill rotWithShape="1">
-                    <a:gsLst>
-                        <a:gs pos="0">
-                            <a:schemeClr val="phClr">
-                                <a:tint val="50000"/>
-                                <a:satMod val="300000"/>
-                            </a:schemeClr>
-                        </a:gs>
-                        <a:gs pos="35000">
-                            <a:schemeClr val="phClr">
-                                <a:tint val="37000"/>
-                                <a:satMod val="300000"/>
-                            </a:schemeClr>
-                        </a:gs>
-                        <a:gs pos="100000">
-                            <a:schemeClr val="phClr">
-                                <a:tint val="15000"/>
-                                <a:satMod val="350000"/>
-                            </a:schemeClr>
-                        </a:gs>
-                    </a:gsLst>
-                    <a:lin ang="16200000" scaled="1"/>
-                </a:gradFill>
-                <a:gradFill rotWithShape="1">
-                    <a:gsLst>
-                        <a:gs pos="0">
-                            <a:schemeClr val="phClr">
-                                <a:shade val="51000"/>
-                                <a:satMod val="130000"/>
-                            </a:schemeClr>
-                        </a:gs>
-                        <a:gs pos="80000">
-                            <a:schemeClr val="phClr">
-                                <a:shade val="93000"/>
-                                <a:satMod val="130000"/>
-                            </a:schemeClr>
-                        </a:gs>
-                        <a:gs pos="100000">
-                            <a:schemeClr val="phClr">
-                                <a:shade val="94000"/>
-                                <a:satMod val="135000"/>
-                            </a:schemeClr>
-                        </a:gs>
-                    </a:gsLst>
-                    <a:lin ang="16200000" scaled="0"/>
-                </a:gradFill>
-            </a:fillStyleLst>
-            <a:lnStyleLst>
-                <a:ln w="9525" cap="flat" cmpd="sng" algn="ctr">
-                    <a:solidFill>
-                        <a:schemeClr val="phClr">
-                            <a:shade val="95000"/>
-                            <a:satMod val="105000"/>
-                        </a:schemeClr>
-                    </a:solidFill>
-                    <a:prstDash val="solid"/>
-                </a:ln>
-                <a:ln w="25400" cap="flat" cmpd="sng" algn="ctr">
-                    <a:solidFill>
-                        <a:schemeClr val="phClr"/>
-                    </a:solidFill>
-                    <a:prstDash val="solid"/>
-                </a:ln>
-                <a:ln w="38100" cap="flat" cmpd="sng" algn="ctr">
-                    <a:solidFill>
-                        <a:schemeClr val="phClr"/>
-                    </a:solidFill>
-                    <a:prstDash val="solid"/>
-                </a:ln>
-            </a:lnStyleLst>
-            <a:effectStyleLst>
-                <a:effectStyle>
-                    <a:effectLst>
-                        <a:outerShdw blurRad="40000" dist="20000" dir="5400000" rotWithShape="0">
-                            <a:srgbClr val="000000">
-                                <a:alpha val="38000"/>
-                            </a:srgbClr>
-                        </a:outerShdw>
-                    </a:effectLst>
-                </a:effectStyle>
-                <a:effectStyle>
-                    <a:effectLst>
-                        <a:outerShdw blurRad="40000" dist="23000" dir="5400000" rotWithShape="0">
-                            <a:srgbClr val="000000">
-                                <a:alpha val="35000"/>
-                            </a:srgbClr>
-                        </a:outerShdw>
-                    </a:effectLst>
-                </a:effectStyle>
-                <a:effectStyle>
-                    <a:effectLst>
-                        <a:outerShdw blurRad="40000" dist="23000" dir="5400000" rotWithShape="0">
-                            <a:srgbClr val="000000">
-                                <a:alpha val="35000"/>
-                            </a:srgbClr>
-                        </a:outerShdw>
-                    </a:effectLst>
-                </a:effectStyle>
-            </a:effectStyleLst>
-            <a:bgFillStyleLst>
-                <a:solidFill>
-                    <a:schemeClr val="phClr"/>
-                </a:solidFill>
-                <a:gradFill rotWithShape="1">
-                    <a:gsLst>
-                        <a:gs pos="0">
-                            <a:schemeClr val="phClr">
-                                <a:tint val="40000"/>
-                                <a:satMod val="350000"/>
-                            </a:schemeClr>
-                        </a:gs>
-                        <a:gs pos="40000">
-                            <a:schemeClr val="phClr">
-                                <a:tint val="45000"/>
-                                <a:shade val="99000"/>
-                                <a:satMod val="350000"/>
-                            </a:schemeClr>
-                        </a:gs>
-                        <a:gs pos="100000">
-                            <a:schemeClr val="phClr">
-                                <a:shade val="20000"/>
-                                <a:satMod val="255000"/>
-                            </a:schemeClr>
-                        </a:gs>
-                    </a:gsLst>
-                    <a:path path="circle">
-                        <a:fillToRect l="50000" t="-80000" r="50000" b="180000"/>
-                    </a:path>
-                </a:gradFill>
-                <a:gradFill rotWithShape="1">
-                    <a:gsLst>
-                        <a:gs pos="0">
-                            <a:schemeClr val="phClr">
-                                <a:tint val="80000"/>
-                                <a:satMod val="300000"/>
-                            </a:schemeClr>
-                        </a:gs>
-                        <a:gs pos="100000">
-                            <a:schemeClr val="phClr">
-                                <a:shade val="30000"/>
-                                <a:satMod val="200000"/>
-                            </a:schemeClr>
-                        </a:gs>
-                    </a:gsLst>
-                    <a:path path="circle">
-                        <a:fillToRect l="50000" t="50000" r="50000" b="50000"/>
-                    </a:path>
-                </a:gradFill>
-            </a:bgFillStyleLst>
-        </a:fmtScheme>
-    </a:themeElements>
-    <a:objectDefaults/>
-    <a:extraClrSchemeLst/>
-</a:theme>"#;
-
         zip.start_file("ppt/theme/theme1.xml", Default::default())?;
-        zip.write_all(theme.as_bytes())?;
+        zip.write_all(self.template.get_theme_xml().as_bytes())?;
         Ok(())
     }
 }
 
-fn escape_xml(text: &str) -> String {
+/// Target of a slide relationship registered while rendering
+#[derive(Debug, Clone, PartialEq)]
+enum LinkTarget {
+    External(String),
+    Slide(usize),
+    /// Image part name, e.g. `ppt/media/md2pptx-image1.png`
+    Media(String),
+}
+
+/// Per-slide state collected while its shapes are rendered: hyperlink and
+/// image relationships and the paragraph builds for the slide timing.
+/// `rId1` is always the slide layout, so other targets start at `rId2`.
+struct SlideContext<'a> {
+    anchors: &'a HashMap<String, usize>,
+    targets: Vec<LinkTarget>,
+    builds: Vec<ParagraphBuild>,
+}
+
+impl<'a> SlideContext<'a> {
+    fn new(anchors: &'a HashMap<String, usize>) -> Self {
+        Self {
+            anchors,
+            targets: Vec::new(),
+            builds: Vec::new(),
+        }
+    }
+
+    /// Register a link URL and return its `a:hlinkClick` element, or `None` when
+    /// a `#anchor` does not match any slide title.
+    fn hyperlink(&mut self, url: &str) -> Option<String> {
+        let target = match url.strip_prefix('#') {
+            Some(anchor) => LinkTarget::Slide(*self.anchors.get(anchor)?),
+            None => LinkTarget::External(url.to_string()),
+        };
+        Some(self.link(target))
+    }
+
+    /// Register a link target and return its `a:hlinkClick` element
+    fn link(&mut self, target: LinkTarget) -> String {
+        let jump = matches!(target, LinkTarget::Slide(_));
+        let rel_id = self.register(target);
+        if jump {
+            format!(
+                r#"<a:hlinkClick r:id="{}" action="ppaction://hlinksldjump"/>"#,
+                rel_id
+            )
+        } else {
+            format!(r#"<a:hlinkClick r:id="{}"/>"#, rel_id)
+        }
+    }
+
+    /// Register an image part and return its relationship id
+    fn media(&mut self, part: &str) -> String {
+        self.register(LinkTarget::Media(part.to_string()))
+    }
+
+    /// Relationship id for a target, reusing the id of an identical target
+    fn register(&mut self, target: LinkTarget) -> String {
+        let index = match self.targets.iter().position(|t| *t == target) {
+            Some(index) => index,
+            None => {
+                self.targets.push(target);
+                self.targets.len() - 1
+            }
+        };
+        format!("rId{}", index + 2)
+    }
+
+    fn relationships_xml(&self) -> String {
+        self.targets
+            .iter()
+            .enumerate()
+            .map(|(i, target)| match target {
+                LinkTarget::External(url) => format!(
+                    r#"    <Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="{}" TargetMode="External"/>
+"#,
+                    i + 2,
+                    escape_xml(url)
+                ),
+                LinkTarget::Slide(slide_num) => format!(
+                    r#"    <Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slide{}.xml"/>
+"#,
+                    i + 2,
+                    slide_num
+                ),
+                LinkTarget::Media(part) => format!(
+                    r#"    <Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../{}"/>
+"#,
+                    i + 2,
+                    part.trim_start_matches("ppt/")
+                ),
+            })
+            .collect()
+    }
+}
+
+/// Shape geometry is written for a 10 x 7.5 inch slide and scaled from there
+const REFERENCE_SLIDE_WIDTH: i64 = 9144000;
+const REFERENCE_SLIDE_HEIGHT: i64 = 6858000;
+/// Height of a table row on the reference slide (0.4 inches)
+const TABLE_ROW_HEIGHT: i64 = 365760;
+/// Tallest a content image is drawn on the reference slide (4 inches)
+const IMAGE_MAX_HEIGHT: i64 = 3657600;
+/// `a:ext` URI of Office's decorative flag for pictures and shapes
+const DECORATIVE_EXT_URI: &str = "{C183D7F6-B498-43B3-948B-1728B52AA6E4}";
+
+/// Relationship id of a master background image, after the layout and theme
+const MASTER_IMAGE_REL: &str = "rId3";
+/// Logo relationship on the master. Not of the `rIdN` form, so it cannot
+/// clash with the relationships of a reference document's master.
+const MASTER_LOGO_REL: &str = "rIdMd2pptxLogo";
+/// Shape ids of the master logo and watermark, clear of the placeholders
+const MASTER_DECORATION_SHAPE_ID: usize = 2000;
+/// Distance of a logo from the slide edges: a quarter inch
+const LOGO_INSET: i64 = 228600;
+
+/// Footer placeholder shapes use ids from here up, well clear of content shapes
+const FOOTER_SHAPE_ID_BASE: usize = 1000;
+
+/// Master footer placeholder geometry as (type, idx, x, width, alignment).
+/// Layout and slide placeholders inherit their position from these.
+const MASTER_FOOTER_PLACEHOLDERS: [(&str, usize, i64, i64, &str); 3] = [
+    ("dt", 2, 457200, 2133600, "l"),
+    ("ftr", 3, 3124200, 2895600, "ctr"),
+    ("sldNum", 4, 6553200, 2133600, "r"),
+];
+const FOOTER_Y: i64 = 6356350;
+/// Shape id of the body placeholder holding a slide's text and lists when a
+/// reference document is used
+const BODY_SHAPE_ID: usize = 3;
+const FOOTER_HEIGHT: i64 = 365125;
+
+fn layout_footer_placeholders(lang: &str) -> String {
+    [
+        ("Date Placeholder", "dt", 10),
+        ("Footer Placeholder", "ftr", 11),
+        ("Slide Number Placeholder", "sldNum", 12),
+    ]
+    .iter()
+    .enumerate()
+    .map(|(i, (name, ph_type, idx))| footer_placeholder_xml(i + 4, name, ph_type, *idx, "", lang))
+    .collect()
+}
+
+/// A footer placeholder that inherits its geometry and style from its parent
+fn footer_placeholder_xml(
+    id: usize,
+    name: &str,
+    ph_type: &str,
+    idx: usize,
+    runs: &str,
+    lang: &str,
+) -> String {
+    let size = if ph_type == "dt" { "half" } else { "quarter" };
+    format!(
+        r#"
+            <p:sp>
+                <p:nvSpPr>
+                    <p:cNvPr id="{}" name="{} {}"/>
+                    <p:cNvSpPr>
+                        <a:spLocks noGrp="1"/>
+                    </p:cNvSpPr>
+                    <p:nvPr>
+                        <p:ph type="{}" sz="{}" idx="{}"/>
+                    </p:nvPr>
+                </p:nvSpPr>
+                <p:spPr/>
+                <p:txBody>
+                    <a:bodyPr/>
+                    <a:lstStyle/>
+                    <a:p>{}<a:endParaRPr lang="{lang}"/></a:p>
+                </p:txBody>
+            </p:sp>"#,
+        id, name, id, ph_type, size, idx, runs
+    )
+}
+
+/// Whether a front matter value or slide directive switches a setting on
+fn is_truthy(value: &str) -> bool {
+    matches!(value.to_lowercase().as_str(), "true" | "yes" | "on" | "1")
+}
+
+/// Whether a front matter value switches a setting off
+fn is_falsy(value: &str) -> bool {
+    matches!(value.to_lowercase().as_str(), "false" | "no" | "off" | "0")
+}
+
+/// Footer settings from front matter keys `footer`, `slide_numbers`,
+/// `footer_date` (`true` for a live date field, `false` for none, anything
+/// else is shown as-is) and `footer_on_title`. Keys that are not set leave
+/// the template's settings alone.
+fn footer_from_front_matter(properties: &HashMap<String, String>) -> FooterOverlay {
+    let flag = |key: &str| {
+        properties.get(key).and_then(|value| match value {
+            value if is_truthy(value) => Some(true),
+            value if is_falsy(value) => Some(false),
+            _ => None,
+        })
+    };
+
+    let mut footer = FooterOverlay {
+        text: properties.get("footer").cloned(),
+        slide_number: flag("slide_numbers"),
+        date: flag("footer_date"),
+        show_on_title_slide: flag("footer_on_title"),
+        ..Default::default()
+    };
+    if footer.date.is_none() {
+        footer.fixed_date = properties.get("footer_date").cloned();
+    }
+    footer
+}
+
+/// Agenda entries as `(label, index into the remaining Markdown slides)`, where
+/// `skipped` leading slides were dropped in favour of a generated title slide.
+fn agenda_entries(
+    markdown_doc: &MarkdownDocument,
+    source: AgendaSource,
+    skipped: usize,
+) -> Vec<(String, usize)> {
+    if source == AgendaSource::Sections && !markdown_doc.sections.is_empty() {
+        return markdown_doc
+            .sections
+            .iter()
+            .map(|section| {
+                (
+                    section.name.clone(),
+                    section.start_slide.saturating_sub(skipped),
+                )
+            })
+            .collect();
+    }
+
+    markdown_doc
+        .slides
+        .iter()
+        .skip(skipped)
+        .enumerate()
+        .filter_map(|(index, slide)| slide.title.clone().map(|title| (title, index)))
+        .collect()
+}
+
+/// Render text as `a:r` runs, linked runs with an `a:hlinkClick`.
+/// Unresolvable `#anchor` links keep their text as a plain run.
+fn text_runs(text: &InlineText, run_attrs: &str, context: &mut SlideContext) -> String {
+    colored_text_runs(text, run_attrs, None, context)
+}
+
+/// `text_runs` with an explicit color on runs that are not links.
+/// Runs in a `<span lang="...">` with a valid tag get their own `lang`.
+fn colored_text_runs(
+    text: &InlineText,
+    run_attrs: &str,
+    color: Option<&str>,
+    context: &mut SlideContext,
+) -> String {
+    let fill = color
+        .map(|color| format!(r#"<a:solidFill><a:srgbClr val="{}"/></a:solidFill>"#, color))
+        .unwrap_or_default();
+    let run = |attrs: &str, properties: &str, text: &str| {
+        if properties.is_empty() {
+            format!(
+                r#"<a:r><a:rPr {}/><a:t>{}</a:t></a:r>"#,
+                attrs,
+                escape_xml(text)
+            )
+        } else {
+            format!(
+                r#"<a:r><a:rPr {}>{}</a:rPr><a:t>{}</a:t></a:r>"#,
+                attrs,
+                properties,
+                escape_xml(text)
+            )
+        }
+    };
+
+    let mut runs = String::new();
+    for inline in &text.runs {
+        let attrs = match inline.lang.as_deref().filter(|lang| is_language_tag(lang)) {
+            Some(language) => with_language(run_attrs, language),
+            None => run_attrs.to_string(),
+        };
+        let properties = match inline.link.as_deref() {
+            Some(url) => context.hyperlink(url).unwrap_or_else(|| fill.clone()),
+            None => fill.clone(),
+        };
+        runs.push_str(&run(&attrs, &properties, &inline.text));
+    }
+    if runs.is_empty() {
+        runs = run(run_attrs, &fill, "");
+    }
+    runs
+}
+
+/// `p:cNvPr` of a picture: its alt text as `descr`, or Office's decorative
+/// flag so screen readers skip it
+fn shape_properties(id: usize, name: &str, alt: &str, decorative: bool) -> String {
+    if decorative {
+        format!(
+            r#"<p:cNvPr id="{}" name="{}"><a:extLst><a:ext uri="{}"><adec:decorative xmlns:adec="http://schemas.microsoft.com/office/drawing/2017/decorative" val="1"/></a:ext></a:extLst></p:cNvPr>"#,
+            id,
+            escape_xml(name),
+            DECORATIVE_EXT_URI
+        )
+    } else {
+        format!(
+            r#"<p:cNvPr id="{}" name="{}" descr="{}"/>"#,
+            id,
+            escape_xml(name),
+            escape_xml(alt)
+        )
+    }
+}
+
+/// Run attributes with their `lang` value replaced
+fn with_language(run_attrs: &str, language: &str) -> String {
+    match run_attrs.find(r#"lang=""#) {
+        Some(start) => {
+            let value_start = start + r#"lang=""#.len();
+            let value_end = run_attrs[value_start..]
+                .find('"')
+                .map_or(run_attrs.len(), |end| value_start + end);
+            format!(
+                "{}{}{}",
+                &run_attrs[..value_start],
+                language,
+                &run_attrs[value_end..]
+            )
+        }
+        None => format!(r#"lang="{}" {}"#, language, run_attrs),
+    }
+}
+
+/// A BCP 47 language tag such as `en`, `ja-JP` or `zh-Hant-TW`: a 2 to 8
+/// letter language followed by 1 to 8 character alphanumeric subtags
+fn is_language_tag(tag: &str) -> bool {
+    let mut subtags = tag.split('-');
+    let language = subtags.next().unwrap_or_default();
+    (2..=8).contains(&language.len())
+        && language.chars().all(|c| c.is_ascii_alphabetic())
+        && subtags.all(|subtag| {
+            (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+/// Whether the first strongly directional letter of `text` is Hebrew,
+/// Arabic or another right-to-left script
+fn is_rtl_text(text: &str) -> bool {
+    text.chars().find(|c| c.is_alphabetic()).is_some_and(|c| {
+        matches!(
+            u32::from(c),
+            0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF
+        )
+    })
+}
+
+/// `a:pPr` setting only the direction of a left-aligned paragraph, if needed
+fn direction_ppr(text: &str) -> String {
+    match direction_attrs(text) {
+        "" => String::new(),
+        attrs => format!("<a:pPr{}/>", attrs),
+    }
+}
+
+/// `a:pPr` attributes for a left-aligned paragraph: right-to-left and
+/// right-aligned when the text is in a right-to-left script
+fn direction_attrs(text: &str) -> &'static str {
+    if is_rtl_text(text) {
+        r#" algn="r" rtl="1""#
+    } else {
+        ""
+    }
+}
+
+/// Icon glyph, accent (border and label) color and fill color for a callout kind.
+/// The palette follows GitHub's alert colors so decks match the rendered README;
+/// on dark backgrounds it uses GitHub's dark accents over a tinted fill.
+fn callout_style(kind: CalloutKind, background: &str) -> (&'static str, String, String) {
+    let (glyph, light_accent, light_fill, dark_accent) = match kind {
+        CalloutKind::Note => ("\u{2139}", "0969DA", "DDF4FF", "4493F8"),
+        CalloutKind::Tip => ("\u{1F4A1}", "1A7F37", "DAFBE1", "3FB950"),
+        CalloutKind::Important => ("\u{2757}", "8250DF", "FBEFFF", "AB7DF8"),
+        CalloutKind::Warning => ("\u{26A0}", "9A6700", "FFF8C5", "D29922"),
+        CalloutKind::Caution => ("\u{26D4}", "CF222E", "FFEBE9", "F85149"),
+    };
+    match (Rgb::from_hex(background), Rgb::from_hex(dark_accent)) {
+        (Some(background), Some(accent)) if background.is_dark() => (
+            glyph,
+            dark_accent.to_string(),
+            accent.mix(background, 0.15).to_hex(),
+        ),
+        _ => (glyph, light_accent.to_string(), light_fill.to_string()),
+    }
+}
+
+/// Front matter keys read as deck settings or core properties, which are
+/// not repeated as custom document properties
+const FRONT_MATTER_SETTINGS: [&str; 17] = [
+    "background",
+    "background_overlay",
+    "category",
+    "draft",
+    "footer",
+    "footer_date",
+    "footer_on_title",
+    "incremental",
+    "keywords",
+    "lang",
+    "language",
+    "last_modified_by",
+    "slide_numbers",
+    "slide_size",
+    "subject",
+    "transition",
+    "watermark",
+];
+
+/// Every other front matter key as a typed custom property, sorted by name
+fn custom_document_properties(
+    properties: &HashMap<String, String>,
+) -> Vec<(String, PropertyValue)> {
+    let mut custom: Vec<_> = properties
+        .iter()
+        .filter(|(key, _)| !FRONT_MATTER_SETTINGS.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), PropertyValue::infer(value)))
+        .collect();
+    custom.sort_by(|a, b| a.0.cmp(&b.0));
+    custom
+}
+
+/// `_rels` part holding the relationships of `part`
+fn rels_part_name(part: &str) -> String {
+    let (dir, file) = part.rsplit_once('/').unwrap_or(("", part));
+    format!("{}/_rels/{}.rels", dir, file)
+}
+
+/// Copied XML part with `insert` placed before the last `tag`
+fn insert_before_last(data: &[u8], tag: &str, insert: &str) -> String {
+    let mut xml = String::from_utf8_lossy(data).into_owned();
+    if let Some(index) = xml.rfind(tag) {
+        xml.insert_str(index, insert);
+    }
+    xml
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
     text.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
@@ -989,8 +3024,19 @@ fn escape_xml(text: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::presentation::package::Package;
     use crate::presentation::templates::SlideTemplate;
 
+    /// Build the deck and read it back as a package
+    fn built_parts(builder: &PresentationBuilder) -> Package {
+        Package::from_bytes(&builder.build().unwrap(), "deck.pptx").unwrap()
+    }
+
+    /// Text of a part that must exist
+    fn part(package: &Package, name: &str) -> String {
+        String::from_utf8_lossy(package.part(name).unwrap()).into_owned()
+    }
+
     #[test]
     fn test_escape_xml() {
         assert_eq!(escape_xml("Hello & <world>"), "Hello &amp; &lt;world&gt;");
@@ -1006,4 +3052,582 @@ mod tests {
         assert_eq!(builder.slides.len(), 0);
         assert_eq!(builder.metadata.title, "Converted Presentation");
     }
+
+    #[test]
+    fn test_callout_and_quote_shapes() {
+        let doc =
+            MarkdownDocument::parse("# Notes\n\n> [!TIP]\n> Use callouts.\n\n> A plain quote.\n")
+                .unwrap();
+        let builder = PresentationBuilder::from_markdown(&doc, SlideTemplate::Default).unwrap();
+        let anchors = HashMap::new();
+        let mut context = SlideContext::new(&anchors);
+        let shapes = builder.generate_content_shapes(&builder.slides[0].content, &mut context);
+
+        assert!(shapes.contains(r#"prst="roundRect""#));
+        assert!(shapes.contains("DAFBE1"));
+        assert!(shapes.contains("Tip"));
+        assert!(shapes.contains("Use callouts."));
+        assert!(shapes.contains(r#"name="Quote Bar"#));
+        assert!(shapes.contains(r#"i="1""#));
+    }
+
+    #[test]
+    fn test_dark_template_tables_code_and_callouts() {
+        let doc = MarkdownDocument::parse(
+            "# Data\n\n| Name | Role |\n|---|---|\n| Ann | Lead |\n| Bo |\n\n```\nlet x = 1;\n```\n\n> [!NOTE]\n> Dark.\n",
+        )
+        .unwrap();
+        let builder = PresentationBuilder::from_markdown(&doc, SlideTemplate::Dark).unwrap();
+        let anchors = HashMap::new();
+        let mut context = SlideContext::new(&anchors);
+        let shapes = builder.generate_content_shapes(&builder.slides[0].content, &mut context);
+
+        let style = SlideTemplate::Dark.get_table_style();
+        assert!(shapes.contains(r#"<a:tblPr firstRow="1" bandRow="1"/>"#));
+        assert_eq!(shapes.matches("<a:gridCol ").count(), 2);
+        // Header row plus two body rows, the short one padded
+        assert_eq!(shapes.matches("<a:tc>").count(), 6);
+        assert!(shapes.contains(&format!(
+            r#"b="1"><a:solidFill><a:srgbClr val="{}"/></a:solidFill></a:rPr><a:t>Name</a:t>"#,
+            style.header_text
+        )));
+        assert!(shapes.contains(&style.banded_row_background));
+
+        let code = SlideTemplate::Dark.get_code_palette();
+        assert!(shapes.contains(&format!(r#"<a:srgbClr val="{}"/>"#, code.background)));
+        assert!(shapes.contains(r#"<a:latin typeface="Cascadia Code"/>"#));
+
+        assert!(shapes.contains("4493F8"));
+        assert!(!shapes.contains("DDF4FF"));
+
+        let package = built_parts(&builder);
+        let master = package
+            .elements("ppt/slideMasters/slideMaster1.xml")
+            .unwrap();
+        assert_eq!(master[2].name, "bg");
+        assert!(part(&package, "ppt/theme/theme1.xml").contains("1E1E1E"));
+    }
+
+    #[test]
+    fn test_hyperlink_runs_and_relationships() {
+        let doc = MarkdownDocument::parse(
+            "# Getting Started\n\nRead [docs](https://example.com) and [docs again](https://example.com).\n\n## Links\n\n- [Mail](mailto:a@example.com)\n- [Back](#getting-started)\n- [Nowhere](#missing)\n",
+        )
+        .unwrap();
+        let builder = PresentationBuilder::from_markdown(&doc, SlideTemplate::Default).unwrap();
+        let anchors = builder.slide_anchors();
+
+        let mut first = SlideContext::new(&anchors);
+        let shapes = builder.generate_content_shapes(&builder.slides[0].content, &mut first);
+        assert_eq!(shapes.matches(r#"<a:hlinkClick r:id="rId2"/>"#).count(), 2);
+        assert!(first
+            .relationships_xml()
+            .contains(r#"Target="https://example.com" TargetMode="External""#));
+
+        let mut second = SlideContext::new(&anchors);
+        let shapes = builder.generate_content_shapes(&builder.slides[1].content, &mut second);
+        assert!(shapes.contains(r#"action="ppaction://hlinksldjump""#));
+        assert!(shapes.contains("<a:t>Nowhere</a:t>"));
+        let rels = second.relationships_xml();
+        assert!(rels.contains(r#"Target="mailto:a@example.com" TargetMode="External""#));
+        assert!(rels.contains(r#"relationships/slide" Target="slide1.xml""#));
+    }
+
+    #[test]
+    fn test_section_list() {
+        let doc =
+            MarkdownDocument::parse("Intro text\n\n# Part One\n\n## A\n\n# Part Two\n\n## B\n")
+                .unwrap();
+        let builder = PresentationBuilder::from_markdown(&doc, SlideTemplate::Default).unwrap();
+        let xml = builder.section_list_xml();
+
+        assert!(xml.contains("p14:sectionLst"));
+        assert!(xml.contains(r#"name="Default Section""#));
+        assert!(xml.contains(r#"name="Part One""#));
+        assert!(xml.contains(r#"<p14:sldIdLst><p14:sldId id="256"/></p14:sldIdLst>"#));
+        assert!(xml.contains(r#"<p14:sldId id="259"/><p14:sldId id="260"/>"#));
+
+        let flat = MarkdownDocument::parse("# Only\n\nText\n").unwrap();
+        let builder = PresentationBuilder::from_markdown(&flat, SlideTemplate::Default).unwrap();
+        assert!(builder.section_list_xml().is_empty());
+    }
+
+    #[test]
+    fn test_title_slide_from_metadata() {
+        let doc = MarkdownDocument::parse(
+            "---\ntitle: Roadmap\ndescription: Plans for next year\nauthor: Jane Doe\ndate: 2024-05-01\n---\n# Roadmap\n\n# Goals\n\nShip it.\n",
+        )
+        .unwrap();
+        let options = PresentationOptions {
+            title_slide: true,
+            ..Default::default()
+        };
+        let builder =
+            PresentationBuilder::from_markdown_with_options(&doc, SlideTemplate::Default, &options)
+                .unwrap();
+
+        // The heading-only "Roadmap" slide is replaced by the title slide
+        assert_eq!(builder.slides.len(), 2);
+        assert_eq!(builder.metadata.author, "Jane Doe");
+        match &builder.slides[0].kind {
+            PptxSlideKind::Title { subtitle_lines } => assert_eq!(
+                subtitle_lines,
+                &vec![
+                    "Plans for next year".to_string(),
+                    "Jane Doe".to_string(),
+                    "2024-05-01".to_string()
+                ]
+            ),
+            other => panic!("Expected title slide, got {:?}", other),
+        }
+        assert_eq!(builder.slides[1].title.as_deref(), Some("Goals"));
+    }
+
+    #[test]
+    fn test_agenda_slides_paginate_and_link() {
+        let markdown = (1..=10)
+            .map(|i| format!("# Topic {}\n\nBody {}\n\n", i, i))
+            .collect::<String>();
+        let doc = MarkdownDocument::parse(&markdown).unwrap();
+        let options = PresentationOptions {
+            title_slide: true,
+            agenda: Some(AgendaSource::Slides),
+            ..Default::default()
+        };
+        let builder =
+            PresentationBuilder::from_markdown_with_options(&doc, SlideTemplate::Default, &options)
+                .unwrap();
+
+        // Title slide, two agenda pages, then the ten topics
+        assert_eq!(builder.slides.len(), 13);
+        assert_eq!(builder.slides[1].title.as_deref(), Some("Agenda (1/2)"));
+        assert_eq!(builder.slides[2].title.as_deref(), Some("Agenda (2/2)"));
+
+        let anchors = builder.slide_anchors();
+        let mut context = SlideContext::new(&anchors);
+        let shapes = builder.generate_content_shapes(&builder.slides[2].content, &mut context);
+        assert!(shapes.contains("<a:t>Topic 9</a:t>"));
+        assert!(context
+            .relationships_xml()
+            .contains(r#"Target="slide12.xml""#));
+        assert!(context
+            .relationships_xml()
+            .contains(r#"Target="slide13.xml""#));
+    }
+
+    #[test]
+    fn test_footer_layers_and_placeholders() {
+        let doc = MarkdownDocument::parse(
+            "---\nfooter: Internal\nfooter_date: 2024-05-01\n---\n# Only\n\nText\n",
+        )
+        .unwrap();
+        let options = PresentationOptions {
+            title_slide: true,
+            footer: Some(FooterOverlay {
+                text: Some("Confidential".to_string()),
+                slide_number: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let builder =
+            PresentationBuilder::from_markdown_with_options(&doc, SlideTemplate::Default, &options)
+                .unwrap();
+
+        assert_eq!(builder.footer.text.as_deref(), Some("Confidential"));
+        assert_eq!(builder.footer.fixed_date.as_deref(), Some("2024-05-01"));
+        assert_eq!(
+            builder.header_footer_xml(),
+            r#"<p:hf hdr="0" ftr="1" dt="1" sldNum="1"/>"#
+        );
+
+        let shapes = builder.footer_shapes(3, false);
+        assert!(shapes.contains(r#"<p:ph type="ftr" sz="quarter" idx="11"/>"#));
+        assert!(shapes.contains("<a:t>Confidential</a:t>"));
+        assert!(shapes.contains(r#"type="slidenum"><a:rPr lang="en-US"/><a:t>3</a:t>"#));
+        assert!(shapes.contains("<a:t>2024-05-01</a:t>"));
+
+        // Front matter can switch a template's footer flags off
+        let mut footer = FooterSettings {
+            slide_number: true,
+            date: true,
+            ..Default::default()
+        };
+        let properties = HashMap::from([
+            ("slide_numbers".to_string(), "false".to_string()),
+            ("footer_date".to_string(), "off".to_string()),
+        ]);
+        footer.overlay(&footer_from_front_matter(&properties));
+        assert!(!footer.is_visible());
+    }
+
+    #[test]
+    fn test_deck_and_slide_backgrounds() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = dir.path().join("hero.png");
+        std::fs::write(&image, b"\x89PNG\r\n\x1a\n").unwrap();
+        let markdown = format!(
+            "---\nbackground: linear-gradient(#1E1E1E, #3A3A3A)\n---\n# Plain\n\nText\n\n# Hero\n<!-- background: url({}) -->\n<!-- background_overlay: 30% -->\n\nText\n",
+            image.display()
+        );
+        let doc = MarkdownDocument::parse(&markdown).unwrap();
+        let builder = PresentationBuilder::from_markdown(&doc, SlideTemplate::Default).unwrap();
+
+        let package = built_parts(&builder);
+        let part = |name: &str| part(&package, name);
+        assert!(part("ppt/slideMasters/slideMaster1.xml").contains("<a:gradFill"));
+        assert!(!part("ppt/slides/slide1.xml").contains("<p:bg>"));
+        assert!(part("ppt/slides/slide2.xml")
+            .contains(r#"<a:blip r:embed="rId2"><a:lum bright="-30000"/></a:blip>"#));
+        assert!(part("ppt/slides/_rels/slide2.xml.rels")
+            .contains(r#"Target="../media/md2pptx-image1.png""#));
+        assert!(package.part("ppt/media/md2pptx-image1.png").is_some());
+        assert_eq!(
+            package
+                .content_type("ppt/media/md2pptx-image1.png")
+                .unwrap(),
+            Some("image/png".to_string())
+        );
+
+        // Bad settings are reported, not ignored
+        for markdown in [
+            "# A\n<!-- background: teal -->\n\nText\n",
+            "# A\n<!-- background: url(missing.png) -->\n\nText\n",
+        ] {
+            let doc = MarkdownDocument::parse(markdown).unwrap();
+            assert!(PresentationBuilder::from_markdown(&doc, SlideTemplate::Default).is_err());
+        }
+    }
+
+    #[test]
+    fn test_core_and_custom_properties() {
+        let doc = MarkdownDocument::parse(
+            "---\ntitle: Q3 Review\nauthor: Dana\ndescription: Quarterly numbers\nsubject: Finance\nkeywords: revenue, costs\ncategory: Reports\ndepartment: R&D\nbudget: 1200\napproved: true\nreview_date: 2024-10-01\ntransition: fade\n---\n# One\n",
+        )
+        .unwrap();
+        let builder = PresentationBuilder::from_markdown(&doc, SlideTemplate::Default).unwrap();
+
+        let package = built_parts(&builder);
+        let part = |name: &str| part(&package, name);
+        let core = part("docProps/core.xml");
+        assert!(core.contains("<dc:subject>Finance</dc:subject>"));
+        assert!(core.contains("<cp:keywords>revenue, costs</cp:keywords>"));
+        assert!(core.contains("<cp:category>Reports</cp:category>"));
+        assert!(core.contains("<dc:description>Quarterly numbers</dc:description>"));
+        assert!(core.contains("<cp:lastModifiedBy>Dana</cp:lastModifiedBy>"));
+
+        // Settings such as `transition` are not custom properties
+        let names: Vec<&str> = builder
+            .metadata
+            .custom_properties
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(names, ["approved", "budget", "department", "review_date"]);
+        let custom = part("docProps/custom.xml");
+        assert!(custom.contains(r#"name="budget"><vt:i4>1200</vt:i4>"#));
+        assert!(custom
+            .contains(r#"name="review_date"><vt:filetime>2024-10-01T00:00:00Z</vt:filetime>"#));
+        assert!(part("_rels/.rels").contains(r#"Target="docProps/custom.xml""#));
+        assert!(package
+            .content_type("docProps/custom.xml")
+            .unwrap()
+            .is_some());
+
+        let doc = MarkdownDocument::parse("# One\n").unwrap();
+        let builder = PresentationBuilder::from_markdown(&doc, SlideTemplate::Default).unwrap();
+        assert!(builder.metadata.custom_properties.is_empty());
+    }
+
+    #[test]
+    fn test_language_and_right_to_left_text() {
+        let doc = MarkdownDocument::parse(
+            "---\nlang: ja-JP\n---\n# 概要\n\nこんにちは <span lang=\"en-US\">Hello</span>\n\nمرحبا بالعالم\n",
+        )
+        .unwrap();
+        let builder = PresentationBuilder::from_markdown(&doc, SlideTemplate::Default).unwrap();
+        assert_eq!(builder.language, "ja-JP");
+
+        let package = built_parts(&builder);
+        let slide = part(&package, "ppt/slides/slide1.xml");
+        assert!(slide.contains(r#"<a:rPr lang="ja-JP"/><a:t>こんにちは </a:t>"#));
+        assert!(slide.contains(r#"<a:rPr lang="en-US"/><a:t>Hello</a:t>"#));
+        assert!(slide.contains(r#"<a:p><a:pPr algn="r" rtl="1"/>"#));
+        assert!(!slide.contains("<span"));
+        assert!(part(&package, "ppt/theme/theme1.xml")
+            .contains(r#"<a:font script="Jpan" typeface="游ゴシック"/>"#));
+
+        // The CLI language wins over front matter, and must be a valid tag
+        let options = PresentationOptions {
+            language: Some("ar-SA".to_string()),
+            ..Default::default()
+        };
+        let builder =
+            PresentationBuilder::from_markdown_with_options(&doc, SlideTemplate::Default, &options)
+                .unwrap();
+        assert_eq!(builder.language, "ar-SA");
+        let options = PresentationOptions {
+            language: Some("english!".to_string()),
+            ..Default::default()
+        };
+        assert!(PresentationBuilder::from_markdown_with_options(
+            &doc,
+            SlideTemplate::Default,
+            &options
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_inline_runs() {
+        let doc = MarkdownDocument::parse(
+            "# Runs\n\nSay <span lang=\"fr-FR\">bonjour</span> to <span lang=\"x y\">odd</span> [Rust](https://en.wikipedia.org/wiki/Rust_(programming_language)) and `a[0](x)`\n",
+        )
+        .unwrap();
+        let SlideElement::Paragraph { text } = &doc.slides[0].content[0].node else {
+            panic!("Expected paragraph");
+        };
+        let anchors = HashMap::new();
+        let mut context = SlideContext::new(&anchors);
+        let runs = text_runs(text, r#"lang="en-US""#, &mut context);
+        assert!(runs.starts_with(r#"<a:r><a:rPr lang="en-US"/><a:t>Say </a:t></a:r><a:r><a:rPr lang="fr-FR"/><a:t>bonjour</a:t></a:r>"#));
+        // An invalid language tag keeps the document language
+        assert!(runs.contains(r#"<a:rPr lang="en-US"/><a:t>odd</a:t>"#));
+        assert!(runs
+            .contains(r#"<a:rPr lang="en-US"><a:hlinkClick r:id="rId2"/></a:rPr><a:t>Rust</a:t>"#));
+        assert!(runs.ends_with(r#"<a:t> and `a[0](x)`</a:t></a:r>"#));
+        assert_eq!(context.targets.len(), 1);
+        assert!(context
+            .relationships_xml()
+            .contains(r#"Target="https://en.wikipedia.org/wiki/Rust_(programming_language)""#));
+
+        assert_eq!(
+            with_language(r#"lang="en-US" sz="1800""#, "he-IL"),
+            r#"lang="he-IL" sz="1800""#
+        );
+        assert!(is_rtl_text("123 שלום world"));
+        assert!(!is_rtl_text("Hello مرحبا"));
+    }
+
+    #[test]
+    fn test_image_alt_text_and_accessibility_issues() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend_from_slice(&[0, 0, 0, 200, 0, 0, 0, 100]);
+        let image = dir.path().join("chart.png");
+        std::fs::write(&image, png).unwrap();
+        let markdown = format!(
+            "# Results\n\n![Revenue & costs]({0})\n\n![]({0} \"decorative\")\n\n![](https://example.com/x.png)\n\n| Region | Sales |\n|---|---|\n| EU | 3 |\n\n# Results\n",
+            image.display()
+        );
+        let doc = MarkdownDocument::parse(&markdown).unwrap();
+        let options = PresentationOptions {
+            footer: Some(FooterOverlay {
+                slide_number: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let builder =
+            PresentationBuilder::from_markdown_with_options(&doc, SlideTemplate::Default, &options)
+                .unwrap();
+        let anchors = HashMap::new();
+        let mut context = SlideContext::new(&anchors);
+        let shapes = builder.generate_content_shapes(&builder.slides[0].content, &mut context);
+        assert!(shapes.contains(r#"name="Picture 3" descr="Revenue &amp; costs"/>"#));
+        assert!(shapes.contains(r#"<adec:decorative xmlns:adec="http://schemas.microsoft.com/office/drawing/2017/decorative" val="1"/>"#));
+        // Remote images keep a placeholder, still described for screen readers
+        assert!(shapes.contains(r#"name="Content 5" descr=""/>"#));
+        let warnings: Vec<_> = builder.diagnostics().iter().collect();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, WarningCode::ImagePlaceholder);
+        assert_eq!(warnings[0].span.map(|span| span.line), Some(7));
+        assert!(shapes.contains(r#"<a:tblPr firstRow="1" bandRow="1"/>"#));
+        // Shapes follow the source order, so screen readers read them that way
+        let picture = shapes.find("Picture 3").unwrap();
+        assert!(picture < shapes.find("Picture 4").unwrap());
+        assert!(shapes.find("Picture 4").unwrap() < shapes.find("Table 6").unwrap());
+
+        let issues: Vec<(AccessibilityRule, Option<usize>)> = builder
+            .accessibility_issues()
+            .unwrap()
+            .iter()
+            .map(|issue| (issue.rule, issue.slide))
+            .collect();
+        assert_eq!(
+            issues,
+            [
+                (AccessibilityRule::MissingAltText, Some(1)),
+                (AccessibilityRule::DuplicateTitle, Some(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_untitled_slides_and_unreadable_images() {
+        let doc = MarkdownDocument::parse("Just text\n\n![Chart](missing.png)\n").unwrap();
+        let builder = PresentationBuilder::from_markdown(&doc, SlideTemplate::Default).unwrap();
+
+        // The title placeholder is left empty rather than filled with a stand-in
+        let slide = part(&built_parts(&builder), "ppt/slides/slide1.xml");
+        assert!(slide.contains(r#"<p:ph type="title"/>"#));
+        assert!(!slide.contains("Slide Title"));
+        assert!(builder
+            .accessibility_issues()
+            .unwrap()
+            .iter()
+            .any(|issue| issue.rule == AccessibilityRule::MissingTitle));
+
+        // The warning says why the image could not be embedded
+        let warnings: Vec<_> = builder.diagnostics().iter().collect();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].message,
+            "image missing.png is shown as a placeholder: File not found: missing.png"
+        );
+    }
+
+    #[test]
+    fn test_master_logo_and_watermark() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend_from_slice(&[0, 0, 0, 200, 0, 0, 0, 100]);
+        std::fs::write(dir.path().join("logo.png"), png).unwrap();
+        let mut custom = SlideTemplate::Default.to_custom();
+        custom.logo = Some(LogoSettings {
+            path: dir.path().join("logo.png"),
+            position: LogoPosition::BottomLeft,
+            width: 914400,
+            opacity: 60,
+        });
+        custom.watermark = Some("CONFIDENTIAL".to_string());
+        let template = SlideTemplate::Custom(custom);
+
+        let doc = MarkdownDocument::parse("# One\n\nText\n\n# Two\n\nText\n").unwrap();
+        let builder = PresentationBuilder::from_markdown(&doc, template.clone()).unwrap();
+
+        let package = built_parts(&builder);
+        let part = |name: &str| part(&package, name);
+        let master = part("ppt/slideMasters/slideMaster1.xml");
+        // Half as tall as wide, in the bottom left corner
+        assert!(master.contains(r#"<a:off x="228600" y="6172200"/>"#));
+        assert!(master.contains(r#"<a:ext cx="914400" cy="457200"/>"#));
+        assert!(master.contains(r#"<a:alphaModFix amt="60000"/>"#));
+        assert!(master.contains("<a:t>CONFIDENTIAL</a:t>"));
+        assert!(part("ppt/slideMasters/_rels/slideMaster1.xml.rels")
+            .contains(r#"Id="rIdMd2pptxLogo" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/md2pptx-image1.png""#));
+        // Slides inherit them instead of repeating them
+        assert!(!part("ppt/slides/slide2.xml").contains("Watermark"));
+
+        // The command line wins over the front matter, which wins over the template
+        let doc = MarkdownDocument::parse("---\nwatermark: none\n---\n# One\n").unwrap();
+        let builder = PresentationBuilder::from_markdown(&doc, template.clone()).unwrap();
+        assert_eq!(builder.watermark, None);
+        let options = PresentationOptions {
+            watermark: Some("DRAFT".to_string()),
+            ..Default::default()
+        };
+        let builder =
+            PresentationBuilder::from_markdown_with_options(&doc, template, &options).unwrap();
+        assert_eq!(builder.watermark.as_deref(), Some("DRAFT"));
+        let doc = MarkdownDocument::parse("---\ndraft: true\n---\n# One\n").unwrap();
+        let builder = PresentationBuilder::from_markdown(&doc, SlideTemplate::Default).unwrap();
+        assert_eq!(builder.watermark.as_deref(), Some("DRAFT"));
+    }
+
+    #[test]
+    fn test_speaker_notes_pages() {
+        let doc =
+            MarkdownDocument::parse("# One\n\n<!-- notes: Welcome & thanks -->\n\n# Two\n\nText\n")
+                .unwrap();
+        let builder = PresentationBuilder::from_markdown(&doc, SlideTemplate::Default).unwrap();
+        let package = built_parts(&builder);
+        let xml = |name: &str| part(&package, name);
+
+        assert!(xml("ppt/notesSlides/notesSlide1.xml").contains("<a:t>Welcome &amp; thanks</a:t>"));
+        assert!(xml("ppt/notesSlides/_rels/notesSlide1.xml.rels")
+            .contains(r#"Target="../slides/slide1.xml""#));
+        assert!(xml("ppt/slides/_rels/slide1.xml.rels")
+            .contains(r#"Target="../notesSlides/notesSlide1.xml""#));
+        // Slides without notes get no notes page
+        assert!(package.part("ppt/notesSlides/notesSlide2.xml").is_none());
+        assert!(!xml("ppt/slides/_rels/slide2.xml.rels").contains("notesSlide"));
+
+        assert!(
+            xml("ppt/presentation.xml").contains(r#"<p:notesMasterId r:id="rIdMd2pptxNotes"/>"#)
+        );
+        assert!(xml("ppt/_rels/presentation.xml.rels")
+            .contains(r#"Target="notesMasters/notesMaster1.xml""#));
+        assert!(xml("ppt/notesMasters/_rels/notesMaster1.xml.rels")
+            .contains(r#"Target="../theme/md2pptx-notes-theme.xml""#));
+        assert!(package
+            .content_type("ppt/notesSlides/notesSlide1.xml")
+            .unwrap()
+            .is_some_and(|content_type| content_type.ends_with("notesSlide+xml")));
+        assert!(xml("docProps/app.xml").contains("<Notes>1</Notes>"));
+
+        // A deck without notes has no notes master
+        let doc = MarkdownDocument::parse("# One\n").unwrap();
+        let builder = PresentationBuilder::from_markdown(&doc, SlideTemplate::Default).unwrap();
+        let package = built_parts(&builder);
+        assert!(package.part(NOTES_MASTER_PART).is_none());
+        assert!(!part(&package, "ppt/presentation.xml").contains("notesMasterIdLst"));
+    }
+
+    #[test]
+    fn test_transitions_and_incremental_builds() {
+        let doc = MarkdownDocument::parse(
+            "---\ntransition: fade\n---\n# One\n\n> - a\n> - b\n\n# Two\n\n<!-- transition: push -->\n\n- c\n",
+        )
+        .unwrap();
+        let builder = PresentationBuilder::from_markdown(&doc, SlideTemplate::Default).unwrap();
+
+        assert_eq!(builder.transition, Some(Transition::Fade));
+        assert_eq!(
+            builder.transition_xml(&builder.slides[0]),
+            Transition::Fade.to_xml()
+        );
+        assert_eq!(
+            builder.transition_xml(&builder.slides[1]),
+            Transition::Push.to_xml()
+        );
+
+        let anchors = builder.slide_anchors();
+        let mut context = SlideContext::new(&anchors);
+        builder.generate_content_shapes(&builder.slides[0].content, &mut context);
+        assert_eq!(
+            context.builds,
+            vec![ParagraphBuild {
+                shape_id: 3,
+                first: 0,
+                paragraphs: 2
+            }]
+        );
+
+        let mut context = SlideContext::new(&anchors);
+        builder.generate_content_shapes(&builder.slides[1].content, &mut context);
+        assert!(context.builds.is_empty());
+    }
+
+    #[test]
+    fn test_slide_size_scales_geometry() {
+        let doc = MarkdownDocument::parse("---\nslide_size: a4\n---\n# Only\n").unwrap();
+        let builder = PresentationBuilder::from_markdown(&doc, SlideTemplate::Default).unwrap();
+        assert_eq!(
+            builder.slide_size_xml(),
+            r#"<p:sldSz cx="9906000" cy="6858000" type="A4"/>"#
+        );
+
+        let options = PresentationOptions {
+            slide_size: Some(SlideSize::Widescreen16x10),
+            ..Default::default()
+        };
+        let builder =
+            PresentationBuilder::from_markdown_with_options(&doc, SlideTemplate::Default, &options)
+                .unwrap();
+        assert_eq!(
+            builder.frame(685800, 6858000, 7772400, 1143000),
+            r#"<a:off x="685800" y="5715000"/><a:ext cx="7772400" cy="952500"/>"#
+        );
+    }
 }
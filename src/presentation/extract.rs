@@ -0,0 +1,209 @@
+use crate::presentation::package::{Package, XmlElement};
+use crate::presentation::templates::{CustomTemplate, SlideTemplate};
+use crate::utils::error::Error;
+use crate::Result;
+use std::path::Path;
+
+/// Build a `CustomTemplate` from the theme and first slide master of a
+/// `.pptx` or `.potx`. Anything the presentation does not define comes from
+/// the default template.
+pub fn extract_template(path: &Path) -> Result<CustomTemplate> {
+    let package = Package::open(path)?;
+    let mut template = SlideTemplate::Default.to_custom();
+    if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+        template.name = stem.to_string();
+    }
+
+    let relationships = package.relationships("ppt/presentation.xml")?;
+    let master = relationships
+        .iter()
+        .find(|rel| rel.is("slideMaster"))
+        .map(|rel| rel.target.clone())
+        .ok_or_else(|| Error::configuration(format!("{} has no slide master", path.display())))?;
+    let theme = package
+        .relationships(&master)?
+        .into_iter()
+        .find(|rel| rel.is("theme"))
+        .map(|rel| rel.target);
+
+    if let Some(theme) = theme {
+        let elements = package.elements(&theme)?;
+        let colors = &mut template.theme_colors;
+        for (slot, field) in [
+            ("lt1", &mut colors.background),
+            ("dk1", &mut colors.text_primary),
+            ("dk2", &mut colors.text_secondary),
+            ("lt2", &mut colors.background_secondary),
+            ("accent1", &mut colors.accent_1),
+            ("accent2", &mut colors.accent_2),
+            ("accent3", &mut colors.accent_3),
+            ("accent4", &mut colors.accent_4),
+            ("accent5", &mut colors.accent_5),
+            ("accent6", &mut colors.accent_6),
+            ("hlink", &mut colors.hyperlink),
+            ("folHlink", &mut colors.followed_hyperlink),
+        ] {
+            if let Some(color) = scheme_color(&elements, slot) {
+                *field = color;
+            }
+        }
+
+        let fonts = &mut template.fonts;
+        if let Some(font) = scheme_font(&elements, "majorFont", "latin") {
+            fonts.title_font = font;
+        }
+        if let Some(font) = scheme_font(&elements, "minorFont", "latin") {
+            fonts.body_font = font;
+        }
+        if let Some(font) = scheme_font(&elements, "minorFont", "ea") {
+            fonts.east_asian_font = font;
+        }
+        if let Some(font) = scheme_font(&elements, "minorFont", "cs") {
+            fonts.complex_script_font = font;
+        }
+    }
+
+    let layout = &mut template.layout_settings;
+    if let Some(size) = package
+        .elements("ppt/presentation.xml")?
+        .into_iter()
+        .find(|element| element.name == "sldSz")
+    {
+        if let (Some(width), Some(height)) = (emu(&size, "cx"), emu(&size, "cy")) {
+            layout.slide_width = width;
+            layout.slide_height = height;
+        }
+    }
+
+    // Margins follow the master's title and body placeholders
+    let placeholders = placeholder_frames(&package.elements(&master)?);
+    let frame = |types: &[&str]| {
+        placeholders
+            .iter()
+            .find(|(ph_type, _)| types.contains(&ph_type.as_str()))
+            .map(|(_, frame)| *frame)
+    };
+    if let Some((x, y, cx, cy)) = frame(&["title", "ctrTitle"]) {
+        layout.margin_left = x;
+        layout.margin_right = layout.slide_width - x - cx;
+        layout.margin_top = y;
+        layout.title_height = cy;
+    }
+    if let Some((_, y, _, cy)) = frame(&["body", "obj"]) {
+        layout.margin_bottom = layout.slide_height - y - cy;
+    }
+
+    template.validate().map_err(|err| {
+        Error::configuration(format!(
+            "cannot extract a template from {}: {}",
+            path.display(),
+            err
+        ))
+    })?;
+    Ok(template)
+}
+
+fn emu(element: &XmlElement, attribute: &str) -> Option<i32> {
+    element.attr(attribute)?.parse().ok()
+}
+
+/// The RGB value of a color scheme slot such as `accent1`. System colors use
+/// their last computed value.
+fn scheme_color(elements: &[XmlElement], slot: &str) -> Option<String> {
+    let start = elements.iter().position(|element| element.name == slot)?;
+    let color = elements.get(start + 1)?;
+    match color.name.as_str() {
+        "srgbClr" => color.attr("val"),
+        "sysClr" => color.attr("lastClr"),
+        _ => None,
+    }
+    .map(str::to_uppercase)
+}
+
+/// The `latin`, `ea` or `cs` typeface of the theme's `majorFont` or
+/// `minorFont`
+fn scheme_font(elements: &[XmlElement], font: &str, script: &str) -> Option<String> {
+    let start = elements.iter().position(|element| element.name == font)?;
+    elements[start + 1..]
+        .iter()
+        .find(|element| element.name == script)
+        .and_then(|element| element.attr("typeface"))
+        .filter(|typeface| !typeface.is_empty())
+        .map(str::to_string)
+}
+
+/// `(placeholder type, (x, y, cx, cy))` for placeholders with explicit
+/// geometry. A placeholder without a type is a body placeholder.
+fn placeholder_frames(elements: &[XmlElement]) -> Vec<(String, (i32, i32, i32, i32))> {
+    let mut frames = Vec::new();
+    let mut placeholder: Option<String> = None;
+    let mut offset = None;
+
+    for element in elements {
+        match element.name.as_str() {
+            "sp" => {
+                placeholder = None;
+                offset = None;
+            }
+            "ph" => placeholder = Some(element.attr("type").unwrap_or("body").to_string()),
+            "off" if placeholder.is_some() => offset = emu(element, "x").zip(emu(element, "y")),
+            "ext" => {
+                if let (Some(ph_type), Some((x, y))) = (placeholder.take(), offset.take()) {
+                    if let (Some(cx), Some(cy)) = (emu(element, "cx"), emu(element, "cy")) {
+                        frames.push((ph_type, (x, y, cx, cy)));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    frames
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::markdown::MarkdownDocument;
+    use crate::presentation::builder::PresentationBuilder;
+    use crate::presentation::package::xml_elements;
+
+    #[test]
+    fn test_scheme_colors_and_fonts() {
+        let elements = xml_elements(
+            br#"<a:clrScheme><a:dk1><a:sysClr val="windowText" lastClr="000000"/></a:dk1><a:accent1><a:srgbClr val="1f4e79"/></a:accent1></a:clrScheme><a:fontScheme><a:majorFont><a:latin typeface="Georgia"/></a:majorFont><a:minorFont><a:latin typeface=""/><a:ea typeface="Meiryo"/></a:minorFont></a:fontScheme>"#,
+        )
+        .unwrap();
+        assert_eq!(scheme_color(&elements, "dk1").as_deref(), Some("000000"));
+        assert_eq!(
+            scheme_color(&elements, "accent1").as_deref(),
+            Some("1F4E79")
+        );
+        assert_eq!(scheme_color(&elements, "accent2"), None);
+        assert_eq!(
+            scheme_font(&elements, "majorFont", "latin").as_deref(),
+            Some("Georgia")
+        );
+        assert_eq!(scheme_font(&elements, "minorFont", "latin"), None);
+        assert_eq!(
+            scheme_font(&elements, "minorFont", "ea").as_deref(),
+            Some("Meiryo")
+        );
+    }
+
+    #[test]
+    fn test_extract_template_from_generated_deck() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("brand.pptx");
+        let doc = MarkdownDocument::parse("# One\n").unwrap();
+        let builder = PresentationBuilder::from_markdown(&doc, SlideTemplate::Modern).unwrap();
+        std::fs::write(&path, builder.build().unwrap()).unwrap();
+
+        let template = extract_template(&path).unwrap();
+        assert_eq!(template.name, "brand");
+        assert_eq!(template.theme_colors.accent_1, "007BFF");
+        assert_eq!(template.theme_colors.text_secondary, "6C757D");
+        assert_eq!(template.theme_colors.hyperlink, "0056B3");
+        assert_eq!(template.fonts.title_font, "Roboto");
+        assert_eq!(template.layout_settings.slide_width, 9144000);
+    }
+}
@@ -0,0 +1,169 @@
+use crate::utils::error::Error;
+use crate::Result;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use zip::ZipWriter;
+
+/// Local images embedded in the package. Each file is written once, however
+/// many slides use it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MediaParts {
+    /// `(source file, part name)`
+    parts: Vec<(PathBuf, String)>,
+}
+
+impl MediaParts {
+    /// Register an image and return its part name, e.g.
+    /// `ppt/media/md2pptx-image1.png`
+    pub fn add(&mut self, path: &Path) -> Result<String> {
+        if let Some(name) = self.part_name(path) {
+            return Ok(name.to_string());
+        }
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase)
+            .filter(|ext| content_type(ext).is_some())
+            .ok_or_else(|| {
+                Error::configuration(format!(
+                    "unsupported image {}: expected .png, .jpg, .jpeg, .gif or .bmp",
+                    path.display()
+                ))
+            })?;
+        if !path.is_file() {
+            return Err(Error::file_not_found(path.display().to_string()));
+        }
+
+        let name = format!(
+            "ppt/media/md2pptx-image{}.{}",
+            self.parts.len() + 1,
+            extension
+        );
+        self.parts.push((path.to_path_buf(), name.clone()));
+        Ok(name)
+    }
+
+    pub fn part_name(&self, path: &Path) -> Option<&str> {
+        self.parts
+            .iter()
+            .find(|(source, _)| source == path)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// `Default` content type entries for the image extensions in use
+    pub fn content_types(&self) -> String {
+        let mut extensions: Vec<&str> = self
+            .parts
+            .iter()
+            .filter_map(|(_, name)| name.rsplit('.').next())
+            .collect();
+        extensions.sort_unstable();
+        extensions.dedup();
+        extensions
+            .into_iter()
+            .filter_map(|ext| {
+                content_type(ext).map(|content_type| {
+                    format!(
+                        r#"
+    <Default Extension="{}" ContentType="{}"/>"#,
+                        ext, content_type
+                    )
+                })
+            })
+            .collect()
+    }
+
+    pub fn write(&self, zip: &mut ZipWriter<Cursor<&mut Vec<u8>>>) -> Result<()> {
+        for (source, name) in &self.parts {
+            let data = std::fs::read(source).map_err(|err| Error::from(err).in_file(source))?;
+            zip.start_file(name.as_str(), Default::default())?;
+            zip.write_all(&data)?;
+        }
+        Ok(())
+    }
+}
+
+/// Pixel `(width, height)` read from a PNG, GIF, BMP or JPEG header
+pub(crate) fn image_size(path: &Path) -> Option<(u32, u32)> {
+    let data = std::fs::read(path).ok()?;
+    let be16 = |i: usize| {
+        Some(u32::from(u16::from_be_bytes([
+            *data.get(i)?,
+            *data.get(i + 1)?,
+        ])))
+    };
+    let le16 = |i: usize| {
+        Some(u32::from(u16::from_le_bytes([
+            *data.get(i)?,
+            *data.get(i + 1)?,
+        ])))
+    };
+    let bytes4 = |i: usize| -> Option<[u8; 4]> { data.get(i..i + 4)?.try_into().ok() };
+
+    let size = if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        (
+            u32::from_be_bytes(bytes4(16)?),
+            u32::from_be_bytes(bytes4(20)?),
+        )
+    } else if data.starts_with(b"GIF8") {
+        (le16(6)?, le16(8)?)
+    } else if data.starts_with(b"BM") {
+        (
+            i32::from_le_bytes(bytes4(18)?).unsigned_abs(),
+            i32::from_le_bytes(bytes4(22)?).unsigned_abs(),
+        )
+    } else if data.starts_with(&[0xFF, 0xD8]) {
+        // Walk the JPEG segments to the first start-of-frame marker
+        let mut i = 2;
+        loop {
+            if *data.get(i)? != 0xFF {
+                return None;
+            }
+            let marker = *data.get(i + 1)?;
+            if (0xC0..=0xCF).contains(&marker) && ![0xC4, 0xC8, 0xCC].contains(&marker) {
+                break (be16(i + 7)?, be16(i + 5)?);
+            }
+            i += 2 + be16(i + 2)? as usize;
+        }
+    } else {
+        return None;
+    };
+    (size.0 > 0 && size.1 > 0).then_some(size)
+}
+
+fn content_type(extension: &str) -> Option<&'static str> {
+    match extension {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "bmp" => Some("image/bmp"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend_from_slice(&[0, 0, 1, 0x90, 0, 0, 0, 0x64]);
+        let gif = b"GIF89a\x20\x00\x10\x00".to_vec();
+        let jpeg = vec![
+            0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x00,
+            0x30, 0x00, 0x40,
+        ];
+        for (name, data, size) in [
+            ("logo.png", png, Some((400, 100))),
+            ("logo.gif", gif, Some((32, 16))),
+            ("logo.jpg", jpeg, Some((64, 48))),
+            ("logo.bmp", b"BM".to_vec(), None),
+        ] {
+            let path = dir.path().join(name);
+            std::fs::write(&path, data).unwrap();
+            assert_eq!(image_size(&path), size, "{}", name);
+        }
+    }
+}
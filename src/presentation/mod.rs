@@ -1,2 +1,12 @@
+pub mod accessibility;
+pub mod background;
 pub mod builder;
+pub mod extract;
+pub(crate) mod media;
+pub(crate) mod notes;
+pub mod package;
+pub mod palette;
+pub mod properties;
+pub mod reference;
 pub mod templates;
+pub mod transitions;
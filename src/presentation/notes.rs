@@ -0,0 +1,213 @@
+use crate::presentation::builder::escape_xml;
+
+/// Notes master shared by all notes pages
+pub(crate) const NOTES_MASTER_PART: &str = "ppt/notesMasters/notesMaster1.xml";
+/// Theme of the notes master, named so it cannot clash with the themes of a
+/// reference document
+pub(crate) const NOTES_THEME_PART: &str = "ppt/theme/md2pptx-notes-theme.xml";
+/// Relationship id of the notes page in a slide's and of the notes master in
+/// the presentation's relationships
+pub(crate) const NOTES_REL: &str = "rIdMd2pptxNotes";
+
+/// `p:notesMaster` with a slide image above the notes text, on a portrait
+/// page of `p:notesSz`
+pub(crate) fn notes_master_xml(lang: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:notesMaster xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+    <p:cSld>
+        <p:bg>
+            <p:bgRef idx="1001">
+                <a:schemeClr val="bg1"/>
+            </p:bgRef>
+        </p:bg>
+        <p:spTree>
+            <p:nvGrpSpPr>
+                <p:cNvPr id="1" name=""/>
+                <p:cNvGrpSpPr/>
+                <p:nvPr/>
+            </p:nvGrpSpPr>
+            <p:grpSpPr>
+                <a:xfrm>
+                    <a:off x="0" y="0"/>
+                    <a:ext cx="0" cy="0"/>
+                    <a:chOff x="0" y="0"/>
+                    <a:chExt cx="0" cy="0"/>
+                </a:xfrm>
+            </p:grpSpPr>
+            <p:sp>
+                <p:nvSpPr>
+                    <p:cNvPr id="2" name="Slide Image Placeholder 1"/>
+                    <p:cNvSpPr>
+                        <a:spLocks noGrp="1" noRot="1" noChangeAspect="1"/>
+                    </p:cNvSpPr>
+                    <p:nvPr>
+                        <p:ph type="sldImg" idx="2"/>
+                    </p:nvPr>
+                </p:nvSpPr>
+                <p:spPr>
+                    <a:xfrm>
+                        <a:off x="381000" y="685800"/>
+                        <a:ext cx="6096000" cy="3429000"/>
+                    </a:xfrm>
+                    <a:prstGeom prst="rect">
+                        <a:avLst/>
+                    </a:prstGeom>
+                    <a:noFill/>
+                    <a:ln w="12700">
+                        <a:solidFill>
+                            <a:prstClr val="black"/>
+                        </a:solidFill>
+                    </a:ln>
+                </p:spPr>
+            </p:sp>
+            <p:sp>
+                <p:nvSpPr>
+                    <p:cNvPr id="3" name="Notes Placeholder 2"/>
+                    <p:cNvSpPr>
+                        <a:spLocks noGrp="1"/>
+                    </p:cNvSpPr>
+                    <p:nvPr>
+                        <p:ph type="body" sz="quarter" idx="3"/>
+                    </p:nvPr>
+                </p:nvSpPr>
+                <p:spPr>
+                    <a:xfrm>
+                        <a:off x="685800" y="4343400"/>
+                        <a:ext cx="5486400" cy="4114800"/>
+                    </a:xfrm>
+                    <a:prstGeom prst="rect">
+                        <a:avLst/>
+                    </a:prstGeom>
+                </p:spPr>
+                <p:txBody>
+                    <a:bodyPr/>
+                    <a:lstStyle/>
+                    <a:p>
+                        <a:r>
+                            <a:rPr lang="{lang}"/>
+                            <a:t>Click to edit Master text styles</a:t>
+                        </a:r>
+                    </a:p>
+                </p:txBody>
+            </p:sp>
+        </p:spTree>
+    </p:cSld>
+    <p:clrMap bg1="lt1" tx1="dk1" bg2="lt2" tx2="dk2" accent1="accent1" accent2="accent2" accent3="accent3" accent4="accent4" accent5="accent5" accent6="accent6" hlink="hlink" folHlink="folHlink"/>
+    <p:notesStyle>
+        <a:lvl1pPr marL="0" algn="l" defTabSz="914400" rtl="0" eaLnBrk="1" latinLnBrk="0" hangingPunct="1">
+            <a:defRPr sz="1200" kern="1200">
+                <a:solidFill>
+                    <a:schemeClr val="tx1"/>
+                </a:solidFill>
+                <a:latin typeface="+mn-lt"/>
+                <a:ea typeface="+mn-ea"/>
+                <a:cs typeface="+mn-cs"/>
+            </a:defRPr>
+        </a:lvl1pPr>
+    </p:notesStyle>
+</p:notesMaster>"#
+    )
+}
+
+pub(crate) fn notes_master_relationships_xml() -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme" Target="../theme/{}"/>
+</Relationships>"#,
+        NOTES_THEME_PART.trim_start_matches("ppt/theme/")
+    )
+}
+
+/// `p:notes` page for one slide, with one paragraph per notes entry. Line
+/// breaks inside an entry become `a:br`.
+pub(crate) fn notes_slide_xml(notes: &[String], lang: &str) -> String {
+    let paragraphs = notes
+        .iter()
+        .map(|note| {
+            let runs = note
+                .lines()
+                .map(|line| {
+                    format!(
+                        r#"<a:r><a:rPr lang="{lang}"/><a:t>{}</a:t></a:r>"#,
+                        escape_xml(line)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(&format!(r#"<a:br><a:rPr lang="{lang}"/></a:br>"#));
+            format!(
+                r#"
+                    <a:p>{}</a:p>"#,
+                runs
+            )
+        })
+        .collect::<String>();
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:notes xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+    <p:cSld>
+        <p:spTree>
+            <p:nvGrpSpPr>
+                <p:cNvPr id="1" name=""/>
+                <p:cNvGrpSpPr/>
+                <p:nvPr/>
+            </p:nvGrpSpPr>
+            <p:grpSpPr>
+                <a:xfrm>
+                    <a:off x="0" y="0"/>
+                    <a:ext cx="0" cy="0"/>
+                    <a:chOff x="0" y="0"/>
+                    <a:chExt cx="0" cy="0"/>
+                </a:xfrm>
+            </p:grpSpPr>
+            <p:sp>
+                <p:nvSpPr>
+                    <p:cNvPr id="2" name="Slide Image Placeholder 1"/>
+                    <p:cNvSpPr>
+                        <a:spLocks noGrp="1" noRot="1" noChangeAspect="1"/>
+                    </p:cNvSpPr>
+                    <p:nvPr>
+                        <p:ph type="sldImg"/>
+                    </p:nvPr>
+                </p:nvSpPr>
+                <p:spPr/>
+            </p:sp>
+            <p:sp>
+                <p:nvSpPr>
+                    <p:cNvPr id="3" name="Notes Placeholder 2"/>
+                    <p:cNvSpPr>
+                        <a:spLocks noGrp="1"/>
+                    </p:cNvSpPr>
+                    <p:nvPr>
+                        <p:ph type="body" idx="1"/>
+                    </p:nvPr>
+                </p:nvSpPr>
+                <p:spPr/>
+                <p:txBody>
+                    <a:bodyPr/>
+                    <a:lstStyle/>{}
+                </p:txBody>
+            </p:sp>
+        </p:spTree>
+    </p:cSld>
+    <p:clrMapOvr>
+        <a:masterClrMapping/>
+    </p:clrMapOvr>
+</p:notes>"#,
+        paragraphs
+    )
+}
+
+/// Relationships of a notes page: its master and the slide it belongs to
+pub(crate) fn notes_slide_relationships_xml(slide_num: usize) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesMaster" Target="../notesMasters/notesMaster1.xml"/>
+    <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="../slides/slide{}.xml"/>
+</Relationships>"#,
+        slide_num
+    )
+}
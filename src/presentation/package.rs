@@ -0,0 +1,229 @@
+use crate::utils::error::Error;
+use crate::Result;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+use std::path::Path;
+
+/// An OOXML package read into memory, with part names as zip entry names
+/// (no leading `/`)
+pub(crate) struct Package {
+    parts: HashMap<String, Vec<u8>>,
+}
+
+/// A relationship with its target resolved to a part name
+#[derive(Debug, Clone)]
+pub(crate) struct Relationship {
+    pub id: String,
+    pub rel_type: String,
+    pub target: String,
+    pub external: bool,
+}
+
+impl Relationship {
+    /// Whether the relationship type ends in `/kind`, e.g. `slideLayout`
+    pub fn is(&self, kind: &str) -> bool {
+        self.rel_type.rsplit('/').next() == Some(kind)
+    }
+}
+
+/// A start or empty element: local name and attributes by qualified name
+#[derive(Debug, Clone)]
+pub(crate) struct XmlElement {
+    pub name: String,
+    pub attributes: HashMap<String, String>,
+}
+
+impl XmlElement {
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(String::as_str)
+    }
+}
+
+impl Package {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path).map_err(|err| Error::from(err).in_file(path))?;
+        Self::read(file, &path.display().to_string())
+    }
+
+    /// Read a package held in memory, such as a deck just built
+    pub fn from_bytes(data: &[u8], name: &str) -> Result<Self> {
+        Self::read(std::io::Cursor::new(data), name)
+    }
+
+    fn read(reader: impl Read + Seek, name: &str) -> Result<Self> {
+        let mut archive = zip::ZipArchive::new(reader).map_err(|err| {
+            Error::from(err)
+                .in_file(name)
+                .with_hint("expected a PowerPoint file, such as a .pptx or .potx")
+        })?;
+
+        let mut parts = HashMap::new();
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index)?;
+            if entry.is_dir() {
+                continue;
+            }
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)?;
+            parts.insert(entry.name().to_string(), data);
+        }
+
+        let package = Self { parts };
+        if package.part("ppt/presentation.xml").is_none() {
+            return Err(Error::invalid_file_format(name));
+        }
+        Ok(package)
+    }
+
+    pub fn part(&self, name: &str) -> Option<&[u8]> {
+        self.parts.get(name).map(Vec::as_slice)
+    }
+
+    pub fn part_names(&self) -> impl Iterator<Item = &str> {
+        self.parts.keys().map(String::as_str)
+    }
+
+    /// Elements of an XML part, or an empty list when the part is missing
+    pub fn elements(&self, name: &str) -> Result<Vec<XmlElement>> {
+        match self.part(name) {
+            Some(xml) => xml_elements(xml),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Relationships of a part, from its `_rels/<name>.rels` part
+    pub fn relationships(&self, name: &str) -> Result<Vec<Relationship>> {
+        let (dir, file) = name.rsplit_once('/').unwrap_or(("", name));
+        let rels_name = match dir {
+            "" => format!("_rels/{}.rels", file),
+            dir => format!("{}/_rels/{}.rels", dir, file),
+        };
+
+        Ok(self
+            .elements(&rels_name)?
+            .into_iter()
+            .filter(|element| element.name == "Relationship")
+            .filter_map(|element| {
+                let external = element.attr("TargetMode") == Some("External");
+                let target = element.attr("Target")?;
+                Some(Relationship {
+                    id: element.attr("Id")?.to_string(),
+                    rel_type: element.attr("Type")?.to_string(),
+                    target: if external {
+                        target.to_string()
+                    } else {
+                        resolve_target(dir, target)
+                    },
+                    external,
+                })
+            })
+            .collect())
+    }
+
+    /// Content type of a part from `[Content_Types].xml`, preferring an
+    /// override over the default for its extension
+    pub fn content_type(&self, name: &str) -> Result<Option<String>> {
+        let types = self.elements("[Content_Types].xml")?;
+        let part_name = format!("/{}", name);
+        let extension = name.rsplit('.').next().unwrap_or_default().to_lowercase();
+
+        let by_override = types.iter().find(|element| {
+            element.name == "Override" && element.attr("PartName") == Some(part_name.as_str())
+        });
+        let by_default = || {
+            types.iter().find(|element| {
+                element.name == "Default"
+                    && element
+                        .attr("Extension")
+                        .is_some_and(|ext| ext.to_lowercase() == extension)
+            })
+        };
+        Ok(by_override
+            .or_else(by_default)
+            .and_then(|element| element.attr("ContentType"))
+            .map(str::to_string))
+    }
+}
+
+/// Resolve a relationship target against the directory of its source part
+fn resolve_target(dir: &str, target: &str) -> String {
+    let mut segments: Vec<&str> = match target.strip_prefix('/') {
+        Some(_) => Vec::new(),
+        None => dir.split('/').filter(|s| !s.is_empty()).collect(),
+    };
+    for segment in target.trim_start_matches('/').split('/') {
+        match segment {
+            ".." => {
+                segments.pop();
+            }
+            "." | "" => {}
+            segment => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+/// Every start and empty element of an XML document, in document order
+pub(crate) fn xml_elements(xml: &[u8]) -> Result<Vec<XmlElement>> {
+    let mut reader = Reader::from_reader(xml);
+    let mut buf = Vec::new();
+    let mut elements = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(element) | Event::Empty(element) => {
+                let mut attributes = HashMap::new();
+                for attribute in element.attributes() {
+                    let attribute = attribute.map_err(quick_xml::Error::from)?;
+                    attributes.insert(
+                        String::from_utf8_lossy(attribute.key.as_ref()).into_owned(),
+                        attribute.unescape_value()?.into_owned(),
+                    );
+                }
+                elements.push(XmlElement {
+                    name: String::from_utf8_lossy(element.local_name().as_ref()).into_owned(),
+                    attributes,
+                });
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_target() {
+        assert_eq!(
+            resolve_target("ppt/slideMasters", "../slideLayouts/slideLayout1.xml"),
+            "ppt/slideLayouts/slideLayout1.xml"
+        );
+        assert_eq!(
+            resolve_target("ppt", "slides/slide1.xml"),
+            "ppt/slides/slide1.xml"
+        );
+        assert_eq!(
+            resolve_target("ppt/slides", "/ppt/media/image1.png"),
+            "ppt/media/image1.png"
+        );
+    }
+
+    #[test]
+    fn test_xml_elements() {
+        let elements = xml_elements(
+            br#"<p:sldLayout type="title"><p:cSld name="Title &amp; Subtitle"/></p:sldLayout>"#,
+        )
+        .unwrap();
+        assert_eq!(elements[0].name, "sldLayout");
+        assert_eq!(elements[0].attr("type"), Some("title"));
+        assert_eq!(elements[1].attr("name"), Some("Title & Subtitle"));
+    }
+}
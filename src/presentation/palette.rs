@@ -0,0 +1,342 @@
+use crate::presentation::templates::{SlideTemplate, ThemeColors};
+use crate::utils::error::Error;
+use crate::Result;
+
+/// WCAG 2 AA minimum contrast for body text
+pub const TEXT_CONTRAST: f64 = 4.5;
+/// WCAG 2 AA minimum contrast for large text and graphics such as accents
+pub const GRAPHICS_CONTRAST: f64 = 3.0;
+
+/// An sRGB color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    /// Parse `RRGGBB`, with or without a leading `#`
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim().trim_start_matches('#');
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        Some(Self {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        })
+    }
+
+    pub fn to_hex(self) -> String {
+        format!("{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+    }
+
+    /// WCAG relative luminance
+    pub fn relative_luminance(self) -> f64 {
+        let linear = |channel: u8| {
+            let c = f64::from(channel) / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(self.r) + 0.7152 * linear(self.g) + 0.0722 * linear(self.b)
+    }
+
+    /// WCAG contrast ratio, from 1 to 21
+    pub fn contrast_ratio(self, other: Rgb) -> f64 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Blend `weight` (0..=1) of this color over `other`
+    pub fn mix(self, other: Rgb, weight: f64) -> Rgb {
+        let blend =
+            |a: u8, b: u8| (f64::from(a) * weight + f64::from(b) * (1.0 - weight)).round() as u8;
+        Rgb {
+            r: blend(self.r, other.r),
+            g: blend(self.g, other.g),
+            b: blend(self.b, other.b),
+        }
+    }
+
+    /// Whether white text reads better on this color than black text
+    pub fn is_dark(self) -> bool {
+        self.contrast_ratio(Rgb {
+            r: 255,
+            g: 255,
+            b: 255,
+        }) > self.contrast_ratio(Rgb { r: 0, g: 0, b: 0 })
+    }
+
+    /// Hue in degrees, saturation and lightness in 0..=1
+    fn to_hsl(self) -> (f64, f64, f64) {
+        let [r, g, b] = [self.r, self.g, self.b].map(|c| f64::from(c) / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let lightness = (max + min) / 2.0;
+        if max == min {
+            return (0.0, 0.0, lightness);
+        }
+
+        let delta = max - min;
+        let saturation = if lightness > 0.5 {
+            delta / (2.0 - max - min)
+        } else {
+            delta / (max + min)
+        };
+        let hue = if max == r {
+            (g - b) / delta + if g < b { 6.0 } else { 0.0 }
+        } else if max == g {
+            (b - r) / delta + 2.0
+        } else {
+            (r - g) / delta + 4.0
+        };
+        (hue * 60.0, saturation, lightness)
+    }
+
+    fn from_hsl(hue: f64, saturation: f64, lightness: f64) -> Self {
+        let hue = hue.rem_euclid(360.0) / 360.0;
+        let saturation = saturation.clamp(0.0, 1.0);
+        let lightness = lightness.clamp(0.0, 1.0);
+        let q = if lightness < 0.5 {
+            lightness * (1.0 + saturation)
+        } else {
+            lightness + saturation - lightness * saturation
+        };
+        let p = 2.0 * lightness - q;
+        let channel = |t: f64| {
+            let t = t.rem_euclid(1.0);
+            let value = if t < 1.0 / 6.0 {
+                p + (q - p) * 6.0 * t
+            } else if t < 0.5 {
+                q
+            } else if t < 2.0 / 3.0 {
+                p + (q - p) * (2.0 / 3.0 - t) * 6.0
+            } else {
+                p
+            };
+            (value * 255.0).round() as u8
+        };
+        Self {
+            r: channel(hue + 1.0 / 3.0),
+            g: channel(hue),
+            b: channel(hue - 1.0 / 3.0),
+        }
+    }
+}
+
+/// Contrast ratio of two hex colors, `None` if either is not a hex color
+pub fn contrast_ratio(foreground: &str, background: &str) -> Option<f64> {
+    Some(Rgb::from_hex(foreground)?.contrast_ratio(Rgb::from_hex(background)?))
+}
+
+/// One foreground/background pairing of a theme and its contrast
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContrastCheck {
+    pub foreground: &'static str,
+    pub background: &'static str,
+    pub ratio: f64,
+    pub required: f64,
+}
+
+impl ContrastCheck {
+    pub fn passes(&self) -> bool {
+        self.ratio >= self.required
+    }
+}
+
+/// Check every text and accent color against the backgrounds it is shown on.
+/// Text needs 4.5:1; accents, used for shapes and large text, need 3:1.
+pub fn check_contrast(colors: &ThemeColors) -> Vec<ContrastCheck> {
+    let text = [
+        ("text_primary", &colors.text_primary),
+        ("text_secondary", &colors.text_secondary),
+    ];
+    let backgrounds = [
+        ("background", &colors.background),
+        ("background_secondary", &colors.background_secondary),
+    ];
+    let accents = [
+        ("accent_1", &colors.accent_1),
+        ("accent_2", &colors.accent_2),
+        ("accent_3", &colors.accent_3),
+        ("accent_4", &colors.accent_4),
+        ("accent_5", &colors.accent_5),
+        ("accent_6", &colors.accent_6),
+    ];
+
+    let mut pairs = Vec::new();
+    for background in backgrounds {
+        for foreground in text {
+            pairs.push((foreground, background, TEXT_CONTRAST));
+        }
+    }
+    for link in [
+        ("hyperlink", &colors.hyperlink),
+        ("followed_hyperlink", &colors.followed_hyperlink),
+    ] {
+        pairs.push((link, backgrounds[0], TEXT_CONTRAST));
+    }
+    for accent in accents {
+        pairs.push((accent, backgrounds[0], GRAPHICS_CONTRAST));
+    }
+
+    pairs
+        .into_iter()
+        .filter_map(|((foreground, fg), (background, bg), required)| {
+            Some(ContrastCheck {
+                foreground,
+                background,
+                ratio: contrast_ratio(fg, bg)?,
+                required,
+            })
+        })
+        .collect()
+}
+
+/// `check_contrast` plus the code block and table colors the builder uses
+pub fn check_template_contrast(template: &SlideTemplate) -> Vec<ContrastCheck> {
+    let mut checks = check_contrast(&template.get_theme_colors());
+    let code = template.get_code_palette();
+    let table = template.get_table_style();
+    for (foreground, fg, background, bg) in [
+        ("code_text", &code.text, "code_background", &code.background),
+        (
+            "table_header_text",
+            &table.header_text,
+            "table_header",
+            &table.header_background,
+        ),
+        (
+            "table_text",
+            &table.text,
+            "table_row",
+            &table.row_background,
+        ),
+        (
+            "table_text",
+            &table.text,
+            "table_banded_row",
+            &table.banded_row_background,
+        ),
+    ] {
+        if let Some(ratio) = contrast_ratio(fg, bg) {
+            checks.push(ContrastCheck {
+                foreground,
+                background,
+                ratio,
+                required: TEXT_CONTRAST,
+            });
+        }
+    }
+    checks
+}
+
+/// Build a complete light palette from one or two brand colors. The brand
+/// colors become `accent_1` and `accent_2` unchanged; the other accents are
+/// hue rotations of the primary color, darkened until they reach 3:1 on the
+/// white background, and the text and link colors are dark shades of it.
+pub fn generate_palette(primary: &str, secondary: Option<&str>) -> Result<ThemeColors> {
+    let parse = |hex: &str| {
+        Rgb::from_hex(hex).ok_or_else(|| {
+            Error::configuration(format!("'{}' is not a hex color such as 1F4E79", hex))
+        })
+    };
+    let primary = parse(primary)?;
+    let secondary = secondary.map(parse).transpose()?;
+
+    let white = Rgb::from_hex("FFFFFF").unwrap_or(primary);
+    let (hue, saturation, lightness) = primary.to_hsl();
+    let shade = |hue_shift: f64, sat: f64, light: f64| Rgb::from_hsl(hue + hue_shift, sat, light);
+    let accent = |hue_shift: f64, required: f64| {
+        darken_to_contrast(
+            shade(hue_shift, saturation, lightness.clamp(0.35, 0.6)),
+            white,
+            required,
+        )
+    };
+
+    Ok(ThemeColors {
+        background: white.to_hex(),
+        background_secondary: shade(0.0, saturation.min(0.4), 0.95).to_hex(),
+        text_primary: shade(0.0, saturation.min(0.25), 0.12).to_hex(),
+        text_secondary: shade(0.0, saturation.min(0.6), 0.28).to_hex(),
+        accent_1: primary.to_hex(),
+        accent_2: secondary
+            .unwrap_or_else(|| accent(180.0, GRAPHICS_CONTRAST))
+            .to_hex(),
+        accent_3: accent(120.0, GRAPHICS_CONTRAST).to_hex(),
+        accent_4: accent(240.0, GRAPHICS_CONTRAST).to_hex(),
+        accent_5: accent(30.0, GRAPHICS_CONTRAST).to_hex(),
+        accent_6: accent(300.0, GRAPHICS_CONTRAST).to_hex(),
+        hyperlink: darken_to_contrast(primary, white, TEXT_CONTRAST).to_hex(),
+        followed_hyperlink: accent(60.0, TEXT_CONTRAST).to_hex(),
+    })
+}
+
+/// Reduce lightness until `color` reaches `required` contrast on `background`
+fn darken_to_contrast(color: Rgb, background: Rgb, required: f64) -> Rgb {
+    let (hue, saturation, mut lightness) = color.to_hsl();
+    let mut adjusted = color;
+    while adjusted.contrast_ratio(background) < required && lightness > 0.0 {
+        lightness = (lightness - 0.02).max(0.0);
+        adjusted = Rgb::from_hsl(hue, saturation, lightness);
+    }
+    adjusted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contrast_ratio() {
+        assert!((contrast_ratio("000000", "FFFFFF").unwrap() - 21.0).abs() < 0.01);
+        assert!((contrast_ratio("#767676", "FFFFFF").unwrap() - 4.54).abs() < 0.01);
+        assert_eq!(contrast_ratio("777", "FFFFFF"), None);
+    }
+
+    #[test]
+    fn test_hsl_round_trip() {
+        for hex in ["1F4E79", "C65911", "70AD47", "808080"] {
+            let color = Rgb::from_hex(hex).unwrap();
+            let (h, s, l) = color.to_hsl();
+            assert_eq!(Rgb::from_hsl(h, s, l), color);
+        }
+    }
+
+    #[test]
+    fn test_generated_palette_passes_contrast_checks() {
+        let colors = generate_palette("#00A3E0", None).unwrap();
+        assert_eq!(colors.accent_1, "00A3E0");
+        let failures: Vec<_> = check_contrast(&colors)
+            .into_iter()
+            .filter(|check| !check.passes())
+            .map(|check| check.foreground)
+            .collect();
+        // The light brand color itself is kept exact and reported
+        assert_eq!(failures, vec!["accent_1"]);
+
+        let colors = generate_palette("1F4E79", Some("C65911")).unwrap();
+        assert_eq!(colors.accent_2, "C65911");
+        assert!(check_contrast(&colors).iter().all(ContrastCheck::passes));
+
+        assert!(generate_palette("blue", None).is_err());
+    }
+
+    #[test]
+    fn test_accessible_templates_pass_contrast_checks() {
+        for template in [SlideTemplate::Dark, SlideTemplate::HighContrast] {
+            let failures: Vec<_> = check_template_contrast(&template)
+                .into_iter()
+                .filter(|check| !check.passes())
+                .collect();
+            assert!(failures.is_empty(), "{}: {:?}", template.name(), failures);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::presentation::builder::escape_xml;
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// Format id shared by all user-defined custom properties
+const CUSTOM_PROPERTY_FMTID: &str = "{D5CDD505-2E9C-101B-9397-08002B2CF9AE}";
+
+/// Typed value of a `docProps/custom.xml` property
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue {
+    Text(String),
+    Integer(i32),
+    Number(f64),
+    Bool(bool),
+    Date(DateTime<Utc>),
+}
+
+impl PropertyValue {
+    /// Infer the type of a front matter value: `true`/`false`, numbers
+    /// without leading zeros, `YYYY-MM-DD` or RFC 3339 dates, and text for
+    /// everything else
+    pub fn infer(value: &str) -> Self {
+        let value = value.trim();
+        match value.to_lowercase().as_str() {
+            "true" => return PropertyValue::Bool(true),
+            "false" => return PropertyValue::Bool(false),
+            _ => {}
+        }
+        if is_plain_number(value) {
+            if let Ok(integer) = value.parse::<i32>() {
+                return PropertyValue::Integer(integer);
+            }
+            if let Ok(number) = value.parse::<f64>() {
+                return PropertyValue::Number(number);
+            }
+        }
+        if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            if let Some(midnight) = date.and_hms_opt(0, 0, 0) {
+                return PropertyValue::Date(midnight.and_utc());
+            }
+        }
+        if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+            return PropertyValue::Date(date.with_timezone(&Utc));
+        }
+        PropertyValue::Text(value.to_string())
+    }
+
+    /// `vt:` element holding the value
+    fn to_xml(&self) -> String {
+        match self {
+            PropertyValue::Text(text) => format!("<vt:lpwstr>{}</vt:lpwstr>", escape_xml(text)),
+            PropertyValue::Integer(integer) => format!("<vt:i4>{}</vt:i4>", integer),
+            PropertyValue::Number(number) => format!("<vt:r8>{}</vt:r8>", number),
+            PropertyValue::Bool(value) => format!("<vt:bool>{}</vt:bool>", value),
+            PropertyValue::Date(date) => format!(
+                "<vt:filetime>{}</vt:filetime>",
+                date.format("%Y-%m-%dT%H:%M:%SZ")
+            ),
+        }
+    }
+}
+
+/// `docProps/custom.xml` for `(name, value)` pairs, in the order given
+pub fn custom_properties_xml(properties: &[(String, PropertyValue)]) -> String {
+    let entries: String = properties
+        .iter()
+        .enumerate()
+        .map(|(i, (name, value))| {
+            // pid 0 and 1 are reserved
+            format!(
+                r#"
+    <property fmtid="{}" pid="{}" name="{}">{}</property>"#,
+                CUSTOM_PROPERTY_FMTID,
+                i + 2,
+                escape_xml(name),
+                value.to_xml()
+            )
+        })
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/custom-properties" xmlns:vt="http://schemas.openxmlformats.org/officeDocument/2006/docPropsVTypes">{}
+</Properties>"#,
+        entries
+    )
+}
+
+/// Digits with an optional sign and fraction. A leading zero, as in a zip
+/// code or `007`, keeps the value as text.
+fn is_plain_number(value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, "0"));
+    let all_digits = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    all_digits(whole) && all_digits(fraction) && (whole == "0" || !whole.starts_with('0'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_property_types() {
+        assert_eq!(PropertyValue::infer("true"), PropertyValue::Bool(true));
+        assert_eq!(PropertyValue::infer("42"), PropertyValue::Integer(42));
+        assert_eq!(PropertyValue::infer("-2.5"), PropertyValue::Number(-2.5));
+        assert_eq!(
+            PropertyValue::infer("0042"),
+            PropertyValue::Text("0042".to_string())
+        );
+        assert_eq!(
+            PropertyValue::infer("1.2.3"),
+            PropertyValue::Text("1.2.3".to_string())
+        );
+        assert_eq!(
+            PropertyValue::infer("2024-03-01").to_xml(),
+            "<vt:filetime>2024-03-01T00:00:00Z</vt:filetime>"
+        );
+        assert_eq!(
+            PropertyValue::infer("2024-03-01T09:30:00+01:00").to_xml(),
+            "<vt:filetime>2024-03-01T08:30:00Z</vt:filetime>"
+        );
+
+        let xml = custom_properties_xml(&[
+            ("department".to_string(), PropertyValue::infer("R&D")),
+            ("reviewed".to_string(), PropertyValue::infer("false")),
+        ]);
+        assert!(xml.contains(r#"pid="2" name="department"><vt:lpwstr>R&amp;D</vt:lpwstr>"#));
+        assert!(xml.contains(r#"pid="3" name="reviewed"><vt:bool>false</vt:bool>"#));
+    }
+}
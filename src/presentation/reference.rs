@@ -0,0 +1,412 @@
+use crate::presentation::package::Package;
+use crate::utils::error::Error;
+use crate::Result;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Slide masters, layouts, themes and media taken from an existing `.pptx` or
+/// `.potx`, used in place of the built-in master, layout and theme
+#[derive(Debug, Clone)]
+pub struct ReferenceDoc {
+    /// Parts copied as-is, with their content types. `.rels` parts have none.
+    parts: Vec<ReferencePart>,
+    /// `(sldMasterId id, part name)` in the reference's order
+    masters: Vec<(u32, String)>,
+    /// Theme related to the presentation part, if any
+    theme: Option<String>,
+    layouts: Vec<ReferenceLayout>,
+    slide_size: (i32, i32),
+}
+
+#[derive(Debug, Clone)]
+struct ReferencePart {
+    name: String,
+    content_type: Option<String>,
+    data: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+struct ReferenceLayout {
+    part: String,
+    /// `ST_SlideLayoutType` such as `title` or `obj`, if given
+    layout_type: Option<String>,
+    placeholders: Vec<Placeholder>,
+}
+
+/// A placeholder on a reference layout, which slide shapes pick up by type
+/// and index to inherit its position and text styles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Placeholder {
+    /// `ST_PlaceholderType`; `obj` when the layout leaves it out
+    pub ph_type: String,
+    pub idx: u32,
+}
+
+impl Placeholder {
+    /// `p:ph` referring to this placeholder
+    pub fn to_xml(&self) -> String {
+        match self.ph_type.as_str() {
+            "obj" => format!(r#"<p:ph idx="{}"/>"#, self.idx),
+            ph_type => format!(r#"<p:ph type="{}" idx="{}"/>"#, ph_type, self.idx),
+        }
+    }
+}
+
+impl ReferenceDoc {
+    pub fn open(path: &Path) -> Result<Self> {
+        let package = Package::open(path)?;
+        let invalid = |message: &str| {
+            Error::configuration(format!("reference document {} {}", path.display(), message))
+        };
+
+        let presentation = package.elements("ppt/presentation.xml")?;
+        let slide_size = presentation
+            .iter()
+            .find(|element| element.name == "sldSz")
+            .and_then(|element| {
+                Some((
+                    element.attr("cx")?.parse().ok()?,
+                    element.attr("cy")?.parse().ok()?,
+                ))
+            })
+            .ok_or_else(|| invalid("has no slide size"))?;
+
+        let relationships = package.relationships("ppt/presentation.xml")?;
+        let masters: Vec<(u32, String)> = presentation
+            .iter()
+            .filter(|element| element.name == "sldMasterId")
+            .filter_map(|element| {
+                let id = element.attr("id")?.parse().ok()?;
+                let rel_id = element.attr("r:id")?;
+                let relationship = relationships.iter().find(|rel| rel.id == rel_id)?;
+                Some((id, relationship.target.clone()))
+            })
+            .collect();
+        if masters.is_empty() {
+            return Err(invalid("has no slide masters"));
+        }
+        let theme = relationships
+            .iter()
+            .find(|rel| rel.is("theme"))
+            .map(|rel| rel.target.clone());
+
+        // Copy everything reachable from the masters and theme, except slides
+        // and notes belonging to the reference deck itself
+        let mut pending: Vec<String> = masters.iter().map(|(_, part)| part.clone()).collect();
+        pending.extend(theme.clone());
+        let mut visited = HashSet::new();
+        let mut parts = Vec::new();
+        while let Some(name) = pending.pop() {
+            if !visited.insert(name.clone()) {
+                continue;
+            }
+            let Some(data) = package.part(&name) else {
+                continue;
+            };
+            parts.push(ReferencePart {
+                content_type: package.content_type(&name)?,
+                name: name.clone(),
+                data: data.to_vec(),
+            });
+
+            let (dir, file) = name.rsplit_once('/').unwrap_or(("", &name));
+            let rels_name = format!("{}/_rels/{}.rels", dir, file);
+            if let Some(rels) = package.part(&rels_name) {
+                parts.push(ReferencePart {
+                    name: rels_name,
+                    content_type: None,
+                    data: rels.to_vec(),
+                });
+            }
+            for relationship in package.relationships(&name)? {
+                let skipped = ["slide", "notesSlide", "notesMaster", "handoutMaster"]
+                    .iter()
+                    .any(|kind| relationship.is(kind));
+                if !relationship.external && !skipped {
+                    pending.push(relationship.target);
+                }
+            }
+        }
+
+        let mut layouts = Vec::new();
+        for (_, master) in &masters {
+            for relationship in package.relationships(master)? {
+                if relationship.is("slideLayout") {
+                    let elements = package.elements(&relationship.target)?;
+                    let layout_type = elements
+                        .iter()
+                        .find(|element| element.name == "sldLayout")
+                        .and_then(|element| element.attr("type").map(str::to_string));
+                    let placeholders = elements
+                        .iter()
+                        .filter(|element| element.name == "ph")
+                        .map(|element| Placeholder {
+                            ph_type: element.attr("type").unwrap_or("obj").to_string(),
+                            idx: element
+                                .attr("idx")
+                                .and_then(|idx| idx.parse().ok())
+                                .unwrap_or(0),
+                        })
+                        .collect();
+                    layouts.push(ReferenceLayout {
+                        part: relationship.target,
+                        layout_type,
+                        placeholders,
+                    });
+                }
+            }
+        }
+        if layouts.is_empty() {
+            return Err(invalid("has no slide layouts"));
+        }
+
+        parts.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(Self {
+            parts,
+            masters,
+            theme,
+            layouts,
+            slide_size,
+        })
+    }
+
+    pub fn slide_size(&self) -> (i32, i32) {
+        self.slide_size
+    }
+
+    /// Part names of the slide masters, in `sldMasterIdLst` order, with ids
+    pub(crate) fn masters(&self) -> &[(u32, String)] {
+        &self.masters
+    }
+
+    pub(crate) fn theme(&self) -> Option<&str> {
+        self.theme.as_deref()
+    }
+
+    /// Parts to write into the generated package as `(name, data)`
+    pub(crate) fn parts(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.parts
+            .iter()
+            .map(|part| (part.name.as_str(), part.data.as_slice()))
+    }
+
+    /// `(part name, content type)` overrides for the copied parts
+    pub(crate) fn content_types(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.parts.iter().filter_map(|part| {
+            part.content_type
+                .as_deref()
+                .map(|content_type| (part.name.as_str(), content_type))
+        })
+    }
+
+    /// Layout part for a title slide or a content slide
+    pub(crate) fn layout(&self, title_slide: bool) -> &str {
+        self.find_layout(title_slide).part.as_str()
+    }
+
+    /// First placeholder of one of `types` on the layout a title slide or a
+    /// content slide uses
+    pub(crate) fn placeholder(&self, title_slide: bool, types: &[&str]) -> Option<&Placeholder> {
+        let layout = self.find_layout(title_slide);
+        types.iter().find_map(|ph_type| {
+            layout
+                .placeholders
+                .iter()
+                .find(|placeholder| placeholder.ph_type == *ph_type)
+        })
+    }
+
+    /// Falls back to the first layout of the first master when no layout has
+    /// the wanted type
+    fn find_layout(&self, title_slide: bool) -> &ReferenceLayout {
+        let wanted: &[&str] = if title_slide {
+            &["title"]
+        } else {
+            &["obj", "tx", "titleOnly"]
+        };
+        wanted
+            .iter()
+            .find_map(|kind| {
+                self.layouts
+                    .iter()
+                    .find(|layout| layout.layout_type.as_deref() == Some(*kind))
+            })
+            .unwrap_or(&self.layouts[0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::markdown::MarkdownDocument;
+    use crate::presentation::builder::{PresentationBuilder, PresentationOptions};
+    use crate::presentation::templates::{FooterOverlay, SlideTemplate};
+
+    fn write_deck(path: &Path, options: &PresentationOptions) {
+        let doc = MarkdownDocument::parse("# One\n\nText\n").unwrap();
+        let builder =
+            PresentationBuilder::from_markdown_with_options(&doc, SlideTemplate::Default, options)
+                .unwrap();
+        std::fs::write(path, builder.build().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_reference_doc_parts_and_layouts() {
+        let dir = tempfile::tempdir().unwrap();
+        let reference_path = dir.path().join("corp.pptx");
+        write_deck(
+            &reference_path,
+            &PresentationOptions {
+                slide_size: Some(crate::presentation::templates::SlideSize::Widescreen16x9),
+                ..Default::default()
+            },
+        );
+
+        let reference = ReferenceDoc::open(&reference_path).unwrap();
+        assert_eq!(reference.slide_size(), (12192000, 6858000));
+        assert_eq!(
+            reference.masters(),
+            &[(2147483648, "ppt/slideMasters/slideMaster1.xml".to_string())]
+        );
+        assert_eq!(reference.theme(), Some("ppt/theme/theme1.xml"));
+        assert_eq!(reference.layout(false), "ppt/slideLayouts/slideLayout1.xml");
+        assert_eq!(
+            reference.placeholder(false, &["ftr"]),
+            Some(&Placeholder {
+                ph_type: "ftr".to_string(),
+                idx: 11
+            })
+        );
+        assert_eq!(reference.placeholder(false, &["body", "obj"]), None);
+
+        let names: Vec<&str> = reference.parts().map(|(name, _)| name).collect();
+        assert!(names.contains(&"ppt/slideLayouts/_rels/slideLayout1.xml.rels"));
+        assert!(!names.iter().any(|name| name.starts_with("ppt/slides/")));
+        assert!(reference
+            .content_types()
+            .any(|(name, content_type)| name == "ppt/theme/theme1.xml"
+                && content_type.ends_with("theme+xml")));
+
+        // A deck built on the reference takes its size
+        let output = dir.path().join("out.pptx");
+        write_deck(
+            &output,
+            &PresentationOptions {
+                reference_doc: Some(reference_path),
+                ..Default::default()
+            },
+        );
+        let package = Package::open(&output).unwrap();
+        let size = package
+            .elements("ppt/presentation.xml")
+            .unwrap()
+            .into_iter()
+            .find(|element| element.name == "sldSz")
+            .unwrap();
+        assert_eq!(size.attr("cx"), Some("12192000"));
+    }
+
+    /// Content layout with its body in the lower right quarter and the footer
+    /// under index 7, unlike the built-in layout
+    const CONTENT_LAYOUT: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:sldLayout xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" type="obj" preserve="1">
+    <p:cSld name="Title and Content">
+        <p:spTree>
+            <p:nvGrpSpPr><p:cNvPr id="1" name=""/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr>
+            <p:grpSpPr/>
+            <p:sp>
+                <p:nvSpPr><p:cNvPr id="2" name="Title 1"/><p:cNvSpPr><a:spLocks noGrp="1"/></p:cNvSpPr><p:nvPr><p:ph type="title"/></p:nvPr></p:nvSpPr>
+                <p:spPr/>
+                <p:txBody><a:bodyPr/><a:lstStyle/><a:p/></p:txBody>
+            </p:sp>
+            <p:sp>
+                <p:nvSpPr><p:cNvPr id="3" name="Content Placeholder 2"/><p:cNvSpPr><a:spLocks noGrp="1"/></p:cNvSpPr><p:nvPr><p:ph idx="1"/></p:nvPr></p:nvSpPr>
+                <p:spPr><a:xfrm><a:off x="4572000" y="3429000"/><a:ext cx="4114800" cy="2743200"/></a:xfrm></p:spPr>
+                <p:txBody><a:bodyPr/><a:lstStyle/><a:p/></p:txBody>
+            </p:sp>
+            <p:sp>
+                <p:nvSpPr><p:cNvPr id="4" name="Footer Placeholder 3"/><p:cNvSpPr><a:spLocks noGrp="1"/></p:cNvSpPr><p:nvPr><p:ph type="ftr" sz="quarter" idx="7"/></p:nvPr></p:nvSpPr>
+                <p:spPr/>
+                <p:txBody><a:bodyPr/><a:lstStyle/><a:p/></p:txBody>
+            </p:sp>
+        </p:spTree>
+    </p:cSld>
+    <p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr>
+</p:sldLayout>"#;
+
+    #[test]
+    fn test_reference_body_and_footer_placeholders() {
+        let dir = tempfile::tempdir().unwrap();
+        let base_path = dir.path().join("base.pptx");
+        write_deck(&base_path, &PresentationOptions::default());
+
+        let reference_path = dir.path().join("corp.pptx");
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&base_path).unwrap()).unwrap();
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&reference_path).unwrap());
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index).unwrap();
+            let name = entry.name().to_string();
+            let mut data = Vec::new();
+            std::io::Read::read_to_end(&mut entry, &mut data).unwrap();
+            if name == "ppt/slideLayouts/slideLayout1.xml" {
+                data = CONTENT_LAYOUT.as_bytes().to_vec();
+            }
+            zip.start_file(name, Default::default()).unwrap();
+            std::io::Write::write_all(&mut zip, &data).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let doc = MarkdownDocument::parse(
+            "# One
+
+Intro
+
+- a
+- b
+",
+        )
+        .unwrap();
+        let options = PresentationOptions {
+            reference_doc: Some(reference_path),
+            footer: Some(FooterOverlay {
+                text: Some("Internal".to_string()),
+                slide_number: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let builder =
+            PresentationBuilder::from_markdown_with_options(&doc, SlideTemplate::Default, &options)
+                .unwrap();
+        let package = Package::from_bytes(&builder.build().unwrap(), "out.pptx").unwrap();
+        let slide = String::from_utf8_lossy(package.part("ppt/slides/slide1.xml").unwrap());
+
+        // Text and list share the layout's body placeholder and keep its position
+        assert_eq!(slide.matches(r#"<p:ph idx="1"/>"#).count(), 1);
+        assert!(!slide.contains(r#"type="body""#));
+        let body = &slide[slide.find(r#"<p:ph idx="1"/>"#).unwrap()..];
+        let body = &body[..body.find("</p:sp>").unwrap()];
+        assert!(body.contains("<p:spPr/>"));
+        assert!(body.contains("<a:t>Intro</a:t>") && body.contains("<a:t>b</a:t>"));
+
+        // The footer uses the layout's index; there is no slide number placeholder
+        assert!(slide.contains(r#"<p:ph type="ftr" sz="quarter" idx="7"/>"#));
+        assert!(!slide.contains(r#"type="sldNum""#));
+    }
+
+    #[test]
+    fn test_reference_doc_rejects_non_presentations() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.pptx");
+        std::fs::write(&path, "not a zip").unwrap();
+        let err = ReferenceDoc::open(&path).unwrap_err();
+        assert_eq!(err.code(), "E010");
+        assert_eq!(err.exit_code(), 4);
+        // The zip error says why the file was rejected
+        assert!(std::error::Error::source(err.root()).is_some());
+        assert!(err.render().contains("= hint: expected a PowerPoint file"));
+
+        let err = ReferenceDoc::open(&dir.path().join("missing.pptx")).unwrap_err();
+        assert_eq!(err.exit_code(), 5);
+    }
+}
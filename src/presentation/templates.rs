@@ -1,33 +1,195 @@
+use crate::parser::markdown::SourceSpan;
+use crate::presentation::background::{self, Background};
+use crate::presentation::builder::escape_xml;
+use crate::utils::error::Error;
+use crate::Result;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[derive(Default)]
+/// Names accepted by `SlideTemplate::from_name`
+pub const BUILT_IN_TEMPLATES: [&str; 6] = [
+    "default",
+    "professional",
+    "modern",
+    "minimal",
+    "dark",
+    "high-contrast",
+];
+
+/// Directory searched for `<name>.toml` and `<name>.json` templates:
+/// `$MD2PPTX_TEMPLATE_DIR`, else `md2pptx/templates` under the user config
+/// directory (`$XDG_CONFIG_HOME`, `~/.config` or `%APPDATA%`)
+pub fn user_template_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("MD2PPTX_TEMPLATE_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))?;
+    Some(config.join("md2pptx").join("templates"))
+}
+
+/// Template files in the user template directory, sorted by name
+pub fn user_templates() -> Vec<PathBuf> {
+    user_template_dir()
+        .map(|dir| user_templates_in(&dir))
+        .unwrap_or_default()
+}
+
+/// Template files in `dir`, sorted by name
+pub fn user_templates_in(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut templates: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some("toml" | "json")
+            )
+        })
+        .collect();
+    templates.sort();
+    templates
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[allow(clippy::large_enum_variant)]
 pub enum SlideTemplate {
     #[default]
     Default,
     Professional,
     Modern,
     Minimal,
+    /// Light text on a near-black background, for conference stages
+    Dark,
+    /// White and yellow on black, well above WCAG AA contrast
+    HighContrast,
     Custom(CustomTemplate),
 }
 
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CustomTemplate {
     pub name: String,
     pub theme_colors: ThemeColors,
     pub fonts: FontScheme,
     pub layout_settings: LayoutSettings,
+    #[serde(default)]
+    pub footer: FooterSettings,
+    /// Deck background, e.g. `linear-gradient(#1E1E1E, #3A3A3A)` or
+    /// `url(hero.jpg)`; the theme background color when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    /// Percentage by which an image background is darkened
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background_overlay: Option<u8>,
+    /// Logo image shown on every slide
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logo: Option<LogoSettings>,
+    /// Diagonal text such as `CONFIDENTIAL` across every slide
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watermark: Option<String>,
 }
 
+/// Theme color scheme. `background`, `text_primary`, `text_secondary` and
+/// `background_secondary` are the theme's lt1, dk1, dk2 and lt2 slots.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThemeColors {
     pub background: String,
+    #[serde(default = "default_background_secondary")]
+    pub background_secondary: String,
     pub text_primary: String,
     pub text_secondary: String,
     pub accent_1: String,
     pub accent_2: String,
     pub accent_3: String,
+    #[serde(default = "default_accent_4")]
+    pub accent_4: String,
+    #[serde(default = "default_accent_5")]
+    pub accent_5: String,
+    #[serde(default = "default_accent_6")]
+    pub accent_6: String,
+    #[serde(default = "default_hyperlink")]
+    pub hyperlink: String,
+    #[serde(default = "default_followed_hyperlink")]
+    pub followed_hyperlink: String,
+}
+
+impl ThemeColors {
+    /// Every color by its field name
+    pub fn fields(&self) -> [(&'static str, &String); 12] {
+        [
+            ("background", &self.background),
+            ("background_secondary", &self.background_secondary),
+            ("text_primary", &self.text_primary),
+            ("text_secondary", &self.text_secondary),
+            ("accent_1", &self.accent_1),
+            ("accent_2", &self.accent_2),
+            ("accent_3", &self.accent_3),
+            ("accent_4", &self.accent_4),
+            ("accent_5", &self.accent_5),
+            ("accent_6", &self.accent_6),
+            ("hyperlink", &self.hyperlink),
+            ("followed_hyperlink", &self.followed_hyperlink),
+        ]
+    }
+
+    fn fields_mut(&mut self) -> [(&'static str, &mut String); 12] {
+        [
+            ("background", &mut self.background),
+            ("background_secondary", &mut self.background_secondary),
+            ("text_primary", &mut self.text_primary),
+            ("text_secondary", &mut self.text_secondary),
+            ("accent_1", &mut self.accent_1),
+            ("accent_2", &mut self.accent_2),
+            ("accent_3", &mut self.accent_3),
+            ("accent_4", &mut self.accent_4),
+            ("accent_5", &mut self.accent_5),
+            ("accent_6", &mut self.accent_6),
+            ("hyperlink", &mut self.hyperlink),
+            ("followed_hyperlink", &mut self.followed_hyperlink),
+        ]
+    }
+}
+
+/// Colors of code blocks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodePalette {
+    pub background: String,
+    pub text: String,
+}
+
+/// Colors of tables: a header row, alternating body rows and cell borders
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableStyle {
+    pub header_background: String,
+    pub header_text: String,
+    pub row_background: String,
+    pub banded_row_background: String,
+    pub text: String,
+    pub border: String,
+}
+
+// Office defaults for colors added after the original six
+fn default_background_secondary() -> String {
+    "EEECE1".to_string()
+}
+fn default_accent_4() -> String {
+    "8064A2".to_string()
+}
+fn default_accent_5() -> String {
+    "4BACC6".to_string()
+}
+fn default_accent_6() -> String {
+    "F39646".to_string()
+}
+fn default_hyperlink() -> String {
+    "0000FF".to_string()
+}
+fn default_followed_hyperlink() -> String {
+    "800080".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +197,52 @@ pub struct FontScheme {
     pub title_font: String,
     pub body_font: String,
     pub code_font: String,
+    /// Font for Chinese, Japanese and Korean text. When empty, each script
+    /// falls back to its usual Office font.
+    #[serde(default)]
+    pub east_asian_font: String,
+    /// Font for Arabic, Hebrew, Thai and other complex scripts. When empty,
+    /// each script falls back to its usual Office font.
+    #[serde(default)]
+    pub complex_script_font: String,
+}
+
+/// Per-script `(script, heading font, body font)` fallbacks written to the
+/// theme, matching the Office theme defaults
+const SCRIPT_FONTS: &[(&str, &str, &str)] = &[
+    ("Jpan", "游ゴシック Light", "游ゴシック"),
+    ("Hang", "맑은 고딕", "맑은 고딕"),
+    ("Hans", "等线 Light", "等线"),
+    ("Hant", "新細明體", "新細明體"),
+    ("Arab", "Times New Roman", "Arial"),
+    ("Hebr", "Times New Roman", "Arial"),
+    ("Thai", "Angsana New", "Cordia New"),
+];
+
+/// `a:latin`, `a:ea`, `a:cs` and per-script `a:font` entries of a theme
+/// major or minor font
+fn theme_font_xml(latin: &str, fonts: &FontScheme, heading: bool) -> String {
+    let scripts: String = SCRIPT_FONTS
+        .iter()
+        .map(|(script, heading_font, body_font)| {
+            format!(
+                r#"
+                <a:font script="{}" typeface="{}"/>"#,
+                script,
+                if heading { heading_font } else { body_font }
+            )
+        })
+        .collect();
+    format!(
+        r#"
+                <a:latin typeface="{}"/>
+                <a:ea typeface="{}"/>
+                <a:cs typeface="{}"/>{}"#,
+        escape_xml(latin),
+        escape_xml(&fonts.east_asian_font),
+        escape_xml(&fonts.complex_script_font),
+        scripts
+    )
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,14 +257,482 @@ pub struct LayoutSettings {
     pub content_spacing: i32,
 }
 
+impl CustomTemplate {
+    /// Load a template from a TOML or JSON file. Fields left out of the file
+    /// come from the built-in template named by `extends`, `default` if unset.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).map_err(|err| Error::from(err).in_file(path))?;
+        let invalid =
+            |err: String| Error::configuration(format!("invalid template: {}", err)).in_file(path);
+        let unreadable = |err: Error| {
+            err.in_file(path)
+                .with_hint("run `md2pptx templates export default` for a template with every field")
+        };
+
+        let mut overrides: serde_json::Value = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_str(&content).map_err(|err| unreadable(err.into()))?,
+            _ => toml::from_str(&content).map_err(|err| {
+                let span = err.span().map(|range| SourceSpan::locate(&content, range));
+                let error = unreadable(err.into());
+                match span {
+                    Some(span) => error.at(span),
+                    None => error,
+                }
+            })?,
+        };
+
+        let base_name = match overrides
+            .as_object_mut()
+            .and_then(|map| map.remove("extends"))
+        {
+            Some(serde_json::Value::String(name)) => name,
+            Some(_) => return Err(invalid("`extends` must be a template name".to_string())),
+            None => "default".to_string(),
+        };
+        let base = SlideTemplate::from_name(&base_name).ok_or_else(|| {
+            invalid(format!(
+                "cannot extend unknown template '{}'; expected one of {}",
+                base_name,
+                BUILT_IN_TEMPLATES.join(", ")
+            ))
+        })?;
+
+        let mut merged = serde_json::to_value(base.to_custom())?;
+        merge_values(&mut merged, overrides);
+        let mut template: CustomTemplate =
+            serde_json::from_value(merged).map_err(|err| unreadable(err.into()))?;
+        if template.name == base.name() {
+            if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+                template.name = stem.to_string();
+            }
+        }
+
+        template.validate().map_err(invalid)?;
+        // Image backgrounds and logos are relative to the template file
+        if let Some(dir) = path.parent() {
+            if let Some(spec) = &template.background {
+                template.background = Some(background::resolve_spec(spec, dir));
+            }
+            if let Some(logo) = &mut template.logo {
+                logo.path = dir.join(&logo.path);
+            }
+        }
+        Ok(template)
+    }
+
+    /// Serialize as TOML, the format `from_file` reads for `.toml` files
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    /// Write the template as JSON when `path` ends in `.json`, TOML otherwise
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::to_string_pretty(self)?,
+            _ => self.to_toml()?,
+        };
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Check colors are hex RGB and sizes are usable EMU values. Colors may be
+    /// written with a leading `#` and in either case; they are normalised.
+    pub fn validate(&mut self) -> std::result::Result<(), String> {
+        for (field, value) in self.theme_colors.fields_mut() {
+            let hex = value.trim_start_matches('#');
+            if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(format!(
+                    "theme_colors.{} is '{}', expected a hex color such as 1F4E79",
+                    field, value
+                ));
+            }
+            *value = hex.to_uppercase();
+        }
+
+        if let Some(spec) = &self.background {
+            Background::parse(spec).map_err(|err| format!("background: {}", err))?;
+        }
+        if let Some(overlay) = self.background_overlay {
+            background::parse_overlay(&overlay.to_string())
+                .map_err(|err| format!("background_overlay: {}", err))?;
+        }
+
+        if let Some(logo) = &self.logo {
+            if logo.opacity > 100 {
+                return Err(format!(
+                    "logo.opacity is {}, expected 0 to 100",
+                    logo.opacity
+                ));
+            }
+            if !(1..=self.layout_settings.slide_width).contains(&logo.width) {
+                return Err(format!(
+                    "logo.width is {} EMU, expected 1 to {} EMU",
+                    logo.width, self.layout_settings.slide_width
+                ));
+            }
+        }
+
+        let layout = &self.layout_settings;
+        if let SlideSize::Custom { .. } = layout.slide_size() {
+            let size = format!("{}x{}", layout.slide_width, layout.slide_height);
+            if SlideSize::from_name(&size).is_none() {
+                return Err(format!(
+                    "slide size {} EMU is outside PowerPoint's 914400 to 51206400 EMU range",
+                    size
+                ));
+            }
+        }
+        for (field, value, limit) in [
+            ("margin_left", layout.margin_left, layout.slide_width / 2),
+            ("margin_right", layout.margin_right, layout.slide_width / 2),
+            ("margin_top", layout.margin_top, layout.slide_height / 2),
+            (
+                "margin_bottom",
+                layout.margin_bottom,
+                layout.slide_height / 2,
+            ),
+            ("title_height", layout.title_height, layout.slide_height),
+            (
+                "content_spacing",
+                layout.content_spacing,
+                layout.slide_height,
+            ),
+        ] {
+            if !(0..limit).contains(&value) {
+                return Err(format!(
+                    "layout_settings.{} is {} EMU, expected 0 to {} EMU",
+                    field, value, limit
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Recursively replace the fields of `base` present in `overrides`
+fn merge_values(base: &mut serde_json::Value, overrides: serde_json::Value) {
+    match (base, overrides) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
+/// Slide dimensions, replacing the template's `slide_width` and `slide_height`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlideSize {
+    /// 10 x 7.5 inches
+    Standard4x3,
+    /// 13.333 x 7.5 inches, PowerPoint's default for new decks
+    Widescreen16x9,
+    /// 10 x 6.25 inches
+    Widescreen16x10,
+    /// A4 paper, 10.833 x 7.5 inches
+    A4,
+    /// Width and height in EMUs
+    Custom { width: i32, height: i32 },
+}
+
+impl SlideSize {
+    /// Parse `4:3`, `16:9`, `16:10`, `a4` or a custom `WIDTHxHEIGHT` in EMUs
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "4:3" | "standard" => Some(SlideSize::Standard4x3),
+            "16:9" | "widescreen" => Some(SlideSize::Widescreen16x9),
+            "16:10" => Some(SlideSize::Widescreen16x10),
+            "a4" => Some(SlideSize::A4),
+            custom => {
+                let (width, height) = custom.split_once('x')?;
+                let width = width.trim().parse().ok()?;
+                let height = height.trim().parse().ok()?;
+                // PowerPoint accepts slides between 1 and 56 inches on each side
+                let valid = 914400..=51206400;
+                (valid.contains(&width) && valid.contains(&height))
+                    .then_some(SlideSize::Custom { width, height })
+            }
+        }
+    }
+
+    pub fn dimensions(&self) -> (i32, i32) {
+        match self {
+            SlideSize::Standard4x3 => (9144000, 6858000),
+            SlideSize::Widescreen16x9 => (12192000, 6858000),
+            SlideSize::Widescreen16x10 => (9144000, 5715000),
+            SlideSize::A4 => (9906000, 6858000),
+            SlideSize::Custom { width, height } => (*width, *height),
+        }
+    }
+
+    /// Name PowerPoint shows for the size, used as the presentation format
+    pub fn label(&self) -> &'static str {
+        match self {
+            SlideSize::Standard4x3 => "On-screen Show (4:3)",
+            SlideSize::Widescreen16x9 => "Widescreen",
+            SlideSize::Widescreen16x10 => "On-screen Show (16:10)",
+            SlideSize::A4 => "A4 Paper (210x297 mm)",
+            SlideSize::Custom { .. } => "Custom",
+        }
+    }
+
+    /// The `type` attribute of `p:sldSz`, when the size has one
+    pub fn sld_sz_type(&self) -> Option<&'static str> {
+        match self {
+            SlideSize::Standard4x3 => Some("screen4x3"),
+            SlideSize::Widescreen16x10 => Some("screen16x10"),
+            SlideSize::A4 => Some("A4"),
+            SlideSize::Widescreen16x9 | SlideSize::Custom { .. } => None,
+        }
+    }
+}
+
+impl LayoutSettings {
+    /// Replace the slide dimensions
+    pub fn set_slide_size(&mut self, size: SlideSize) {
+        (self.slide_width, self.slide_height) = size.dimensions();
+    }
+
+    /// The named size matching these dimensions, or a custom size
+    pub fn slide_size(&self) -> SlideSize {
+        [
+            SlideSize::Standard4x3,
+            SlideSize::Widescreen16x9,
+            SlideSize::Widescreen16x10,
+            SlideSize::A4,
+        ]
+        .into_iter()
+        .find(|size| size.dimensions() == (self.slide_width, self.slide_height))
+        .unwrap_or(SlideSize::Custom {
+            width: self.slide_width,
+            height: self.slide_height,
+        })
+    }
+}
+
+/// Footer text, date and slide number shown at the bottom of each slide
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FooterSettings {
+    pub text: Option<String>,
+    pub slide_number: bool,
+    pub date: bool,
+    /// Date text to show as-is; when unset the date is a field PowerPoint keeps current
+    pub fixed_date: Option<String>,
+    pub show_on_title_slide: bool,
+}
+
+impl FooterSettings {
+    pub fn shows_date(&self) -> bool {
+        self.date || self.fixed_date.is_some()
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.text.is_some() || self.slide_number || self.shows_date()
+    }
+
+    /// Layer settings from a higher-priority source on top of these. Every
+    /// field the overlay sets replaces the current value, so flags can be
+    /// switched off as well as on; `date: Some(false)` also drops a fixed date.
+    pub fn overlay(&mut self, other: &FooterOverlay) {
+        if other.text.is_some() {
+            self.text = other.text.clone();
+        }
+        if let Some(date) = other.date {
+            self.date = date;
+            if !date {
+                self.fixed_date = None;
+            }
+        }
+        if other.fixed_date.is_some() {
+            self.fixed_date = other.fixed_date.clone();
+        }
+        if let Some(slide_number) = other.slide_number {
+            self.slide_number = slide_number;
+        }
+        if let Some(show) = other.show_on_title_slide {
+            self.show_on_title_slide = show;
+        }
+    }
+}
+
+/// Footer settings from front matter or the command line, layered over a
+/// template's with `FooterSettings::overlay`. Unset fields keep the value
+/// underneath.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FooterOverlay {
+    pub text: Option<String>,
+    pub slide_number: Option<bool>,
+    pub date: Option<bool>,
+    pub fixed_date: Option<String>,
+    pub show_on_title_slide: Option<bool>,
+}
+
+/// Logo image placed once on the slide master
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogoSettings {
+    /// PNG, JPEG, GIF or BMP file, relative to the template file
+    pub path: PathBuf,
+    #[serde(default)]
+    pub position: LogoPosition,
+    /// Width in EMUs; the height keeps the image's aspect ratio
+    #[serde(default = "default_logo_width")]
+    pub width: i32,
+    /// Opacity in percent
+    #[serde(default = "default_logo_opacity")]
+    pub opacity: u8,
+}
+
+/// Slide corner a logo sits in, inset by a quarter inch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LogoPosition {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl LogoPosition {
+    pub fn name(self) -> &'static str {
+        match self {
+            LogoPosition::TopLeft => "top-left",
+            LogoPosition::TopRight => "top-right",
+            LogoPosition::BottomLeft => "bottom-left",
+            LogoPosition::BottomRight => "bottom-right",
+        }
+    }
+}
+
+fn default_logo_width() -> i32 {
+    914400 // 1 inch
+}
+
+fn default_logo_opacity() -> u8 {
+    100
+}
+
 impl SlideTemplate {
-    pub fn from_name(name: &str) -> Self {
+    pub fn from_name(name: &str) -> Option<Self> {
         match name.to_lowercase().as_str() {
-            "default" => SlideTemplate::Default,
-            "professional" => SlideTemplate::Professional,
-            "modern" => SlideTemplate::Modern,
-            "minimal" => SlideTemplate::Minimal,
-            _ => SlideTemplate::Default,
+            "default" => Some(SlideTemplate::Default),
+            "professional" => Some(SlideTemplate::Professional),
+            "modern" => Some(SlideTemplate::Modern),
+            "minimal" => Some(SlideTemplate::Minimal),
+            "dark" => Some(SlideTemplate::Dark),
+            "high-contrast" | "high_contrast" => Some(SlideTemplate::HighContrast),
+            _ => None,
+        }
+    }
+
+    /// Resolve a `--template` value: a built-in template name, the path of a
+    /// `.toml` or `.json` template file, or the name of a file in the user
+    /// template directory
+    pub fn load(spec: &str) -> Result<Self> {
+        Self::load_in(spec, user_template_dir().as_deref())
+    }
+
+    /// [`SlideTemplate::load`] with the user templates looked up in `template_dir`
+    fn load_in(spec: &str, template_dir: Option<&Path>) -> Result<Self> {
+        let path = Path::new(spec);
+        let is_file = matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("toml" | "json")
+        );
+        if is_file {
+            return Ok(SlideTemplate::Custom(CustomTemplate::from_file(path)?));
+        }
+
+        if let Some(template) = Self::from_name(spec) {
+            return Ok(template);
+        }
+        if let Some(path) = template_dir
+            .map(user_templates_in)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|path| path.file_stem().and_then(|stem| stem.to_str()) == Some(spec))
+        {
+            return Ok(SlideTemplate::Custom(CustomTemplate::from_file(&path)?));
+        }
+
+        Err(Error::configuration(format!(
+            "unknown template '{}'; expected one of {} or a .toml/.json template file",
+            spec,
+            BUILT_IN_TEMPLATES.join(", ")
+        ))
+        .with_hint("run `md2pptx templates list` to see user templates"))
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            SlideTemplate::Default => "default",
+            SlideTemplate::Professional => "professional",
+            SlideTemplate::Modern => "modern",
+            SlideTemplate::Minimal => "minimal",
+            SlideTemplate::Dark => "dark",
+            SlideTemplate::HighContrast => "high-contrast",
+            SlideTemplate::Custom(template) => &template.name,
+        }
+    }
+
+    /// Every setting of this template as an editable `CustomTemplate`
+    pub fn to_custom(&self) -> CustomTemplate {
+        let (background, background_overlay, logo, watermark) = match self {
+            SlideTemplate::Custom(template) => (
+                template.background.clone(),
+                template.background_overlay,
+                template.logo.clone(),
+                template.watermark.clone(),
+            ),
+            _ => (None, None, None, None),
+        };
+        CustomTemplate {
+            name: self.name().to_string(),
+            theme_colors: self.get_theme_colors(),
+            fonts: self.get_fonts(),
+            layout_settings: self.get_layout_settings(),
+            footer: self.get_footer(),
+            background,
+            background_overlay,
+            logo,
+            watermark,
+        }
+    }
+
+    pub fn get_logo(&self) -> Option<LogoSettings> {
+        match self {
+            SlideTemplate::Custom(template) => template.logo.clone(),
+            _ => None,
+        }
+    }
+
+    pub fn get_watermark(&self) -> Option<String> {
+        match self {
+            SlideTemplate::Custom(template) => template.watermark.clone(),
+            _ => None,
+        }
+    }
+
+    /// A custom template's deck background, or `None` when the deck uses the
+    /// theme background color
+    pub fn get_background(&self) -> Result<Option<Background>> {
+        match self {
+            SlideTemplate::Custom(template) => match &template.background {
+                Some(spec) => Ok(Some(
+                    Background::parse(spec)?.with_overlay(template.background_overlay.unwrap_or(0)),
+                )),
+                None => Ok(None),
+            },
+            _ => Ok(None),
         }
     }
 
@@ -64,35 +740,87 @@ impl SlideTemplate {
         match self {
             SlideTemplate::Default => ThemeColors {
                 background: "FFFFFF".to_string(),
+                background_secondary: "EEECE1".to_string(),
                 text_primary: "000000".to_string(),
                 text_secondary: "666666".to_string(),
                 accent_1: "4F81BD".to_string(),
                 accent_2: "F79646".to_string(),
                 accent_3: "9BBB59".to_string(),
+                accent_4: "8064A2".to_string(),
+                accent_5: "4BACC6".to_string(),
+                accent_6: "F39646".to_string(),
+                hyperlink: "0000FF".to_string(),
+                followed_hyperlink: "800080".to_string(),
             },
             SlideTemplate::Professional => ThemeColors {
                 background: "FFFFFF".to_string(),
+                background_secondary: "E7E6E6".to_string(),
                 text_primary: "1F1F1F".to_string(),
                 text_secondary: "757575".to_string(),
                 accent_1: "2E75B6".to_string(),
                 accent_2: "C65911".to_string(),
                 accent_3: "70AD47".to_string(),
+                accent_4: "7030A0".to_string(),
+                accent_5: "5B9BD5".to_string(),
+                accent_6: "BF8F00".to_string(),
+                hyperlink: "0563C1".to_string(),
+                followed_hyperlink: "954F72".to_string(),
             },
             SlideTemplate::Modern => ThemeColors {
                 background: "F8F9FA".to_string(),
+                background_secondary: "E9ECEF".to_string(),
                 text_primary: "212529".to_string(),
                 text_secondary: "6C757D".to_string(),
                 accent_1: "007BFF".to_string(),
                 accent_2: "FD7E14".to_string(),
                 accent_3: "28A745".to_string(),
+                accent_4: "6F42C1".to_string(),
+                accent_5: "17A2B8".to_string(),
+                accent_6: "DC3545".to_string(),
+                hyperlink: "0056B3".to_string(),
+                followed_hyperlink: "6F42C1".to_string(),
             },
             SlideTemplate::Minimal => ThemeColors {
                 background: "FFFFFF".to_string(),
+                background_secondary: "F2F2F2".to_string(),
                 text_primary: "2C2C2C".to_string(),
                 text_secondary: "8C8C8C".to_string(),
                 accent_1: "007ACC".to_string(),
                 accent_2: "FF6B35".to_string(),
                 accent_3: "32CD32".to_string(),
+                accent_4: "8E44AD".to_string(),
+                accent_5: "16A085".to_string(),
+                accent_6: "F1C40F".to_string(),
+                hyperlink: "005A9E".to_string(),
+                followed_hyperlink: "7B3F99".to_string(),
+            },
+            SlideTemplate::Dark => ThemeColors {
+                background: "1E1E1E".to_string(),
+                background_secondary: "2D2D30".to_string(),
+                text_primary: "F3F3F3".to_string(),
+                text_secondary: "C8C8C8".to_string(),
+                accent_1: "4FC3F7".to_string(),
+                accent_2: "FFB74D".to_string(),
+                accent_3: "81C784".to_string(),
+                accent_4: "CE93D8".to_string(),
+                accent_5: "4DD0E1".to_string(),
+                accent_6: "F48FB1".to_string(),
+                hyperlink: "6CB6FF".to_string(),
+                followed_hyperlink: "C39BFF".to_string(),
+            },
+            SlideTemplate::HighContrast => ThemeColors {
+                background: "000000".to_string(),
+                background_secondary: "1A1A1A".to_string(),
+                text_primary: "FFFFFF".to_string(),
+                text_secondary: "FFFF00".to_string(),
+                accent_1: "FFFF00".to_string(),
+                accent_2: "00FFFF".to_string(),
+                accent_3: "00FF00".to_string(),
+                accent_4: "FF80FF".to_string(),
+                accent_5: "FFA500".to_string(),
+                accent_6: "80C0FF".to_string(),
+                hyperlink: "00FFFF".to_string(),
+                followed_hyperlink: "FF80FF".to_string(),
             },
             SlideTemplate::Custom(template) => template.theme_colors.clone(),
         }
@@ -104,26 +832,86 @@ impl SlideTemplate {
                 title_font: "Calibri".to_string(),
                 body_font: "Calibri".to_string(),
                 code_font: "Consolas".to_string(),
+                east_asian_font: String::new(),
+                complex_script_font: String::new(),
             },
             SlideTemplate::Professional => FontScheme {
                 title_font: "Segoe UI".to_string(),
                 body_font: "Segoe UI".to_string(),
                 code_font: "Consolas".to_string(),
+                east_asian_font: String::new(),
+                complex_script_font: String::new(),
             },
             SlideTemplate::Modern => FontScheme {
                 title_font: "Roboto".to_string(),
                 body_font: "Roboto".to_string(),
                 code_font: "Fira Code".to_string(),
+                east_asian_font: String::new(),
+                complex_script_font: String::new(),
             },
             SlideTemplate::Minimal => FontScheme {
                 title_font: "Helvetica".to_string(),
                 body_font: "Helvetica".to_string(),
                 code_font: "Monaco".to_string(),
+                east_asian_font: String::new(),
+                complex_script_font: String::new(),
+            },
+            SlideTemplate::Dark => FontScheme {
+                title_font: "Segoe UI Semibold".to_string(),
+                body_font: "Segoe UI".to_string(),
+                code_font: "Cascadia Code".to_string(),
+                east_asian_font: String::new(),
+                complex_script_font: String::new(),
+            },
+            SlideTemplate::HighContrast => FontScheme {
+                title_font: "Verdana".to_string(),
+                body_font: "Verdana".to_string(),
+                code_font: "Consolas".to_string(),
+                east_asian_font: String::new(),
+                complex_script_font: String::new(),
             },
             SlideTemplate::Custom(template) => template.fonts.clone(),
         }
     }
 
+    pub fn get_footer(&self) -> FooterSettings {
+        match self {
+            SlideTemplate::Custom(template) => template.footer.clone(),
+            _ => FooterSettings::default(),
+        }
+    }
+
+    /// Fill and text color of code blocks. The light built-ins keep their
+    /// near-white fill; other templates use the secondary background.
+    pub fn get_code_palette(&self) -> CodePalette {
+        let colors = self.get_theme_colors();
+        let background = match self {
+            SlideTemplate::Default
+            | SlideTemplate::Professional
+            | SlideTemplate::Modern
+            | SlideTemplate::Minimal => "F8F8F8".to_string(),
+            _ => colors.background_secondary,
+        };
+        CodePalette {
+            background,
+            text: colors.text_primary,
+        }
+    }
+
+    /// Table colors, built from the theme's text and background pairs so
+    /// they have the same contrast as body text
+    pub fn get_table_style(&self) -> TableStyle {
+        let colors = self.get_theme_colors();
+        TableStyle {
+            header_background: colors.text_secondary.clone(),
+            header_text: colors.background.clone(),
+            row_background: colors.background,
+            banded_row_background: colors.background_secondary,
+            text: colors.text_primary,
+            border: colors.text_secondary,
+        }
+    }
+
     pub fn get_layout_settings(&self) -> LayoutSettings {
         let base_settings = LayoutSettings {
             slide_width: 9144000,  // 10 inches in EMUs
@@ -190,6 +978,8 @@ impl SlideTemplate {
                         <a:srgbClr val="{}"/>
                     </a:solidFill>
                     <a:latin typeface="{}"/>
+                    <a:ea typeface="+mj-ea"/>
+                    <a:cs typeface="+mj-cs"/>
                 </a:defRPr>
             </a:lvl1pPr>
         </p:titleStyle>
@@ -200,6 +990,8 @@ impl SlideTemplate {
                         <a:srgbClr val="{}"/>
                     </a:solidFill>
                     <a:latin typeface="{}"/>
+                    <a:ea typeface="+mn-ea"/>
+                    <a:cs typeface="+mn-cs"/>
                 </a:defRPr>
             </a:lvl1pPr>
         </p:bodyStyle>
@@ -210,6 +1002,8 @@ impl SlideTemplate {
                         <a:srgbClr val="{}"/>
                     </a:solidFill>
                     <a:latin typeface="{}"/>
+                    <a:ea typeface="+mn-ea"/>
+                    <a:cs typeface="+mn-cs"/>
                 </a:defRPr>
             </a:lvl1pPr>
         </p:otherStyle>
@@ -244,7 +1038,7 @@ impl SlideTemplate {
                 <a:srgbClr val="{}"/>
             </a:dk2>
             <a:lt2>
-                <a:srgbClr val="EEECE1"/>
+                <a:srgbClr val="{}"/>
             </a:lt2>
             <a:accent1>
                 <a:srgbClr val="{}"/>
@@ -256,31 +1050,25 @@ impl SlideTemplate {
                 <a:srgbClr val="{}"/>
             </a:accent3>
             <a:accent4>
-                <a:srgbClr val="8064A2"/>
+                <a:srgbClr val="{}"/>
             </a:accent4>
             <a:accent5>
-                <a:srgbClr val="4BACC6"/>
+                <a:srgbClr val="{}"/>
             </a:accent5>
             <a:accent6>
-                <a:srgbClr val="F39646"/>
+                <a:srgbClr val="{}"/>
             </a:accent6>
             <a:hlink>
-                <a:srgbClr val="0000FF"/>
+                <a:srgbClr val="{}"/>
             </a:hlink>
             <a:folHlink>
-                <a:srgbClr val="800080"/>
+                <a:srgbClr val="{}"/>
             </a:folHlink>
         </a:clrScheme>
         <a:fontScheme name="Custom">
-            <a:majorFont>
-                <a:latin typeface="{}"/>
-                <a:ea typeface=""/>
-                <a:cs typeface=""/>
+            <a:majorFont>{}
             </a:majorFont>
-            <a:minorFont>
-                <a:latin typeface="{}"/>
-                <a:ea typeface=""/>
-                <a:cs typeface=""/>
+            <a:minorFont>{}
             </a:minorFont>
         </a:fontScheme>
         <a:fmtScheme name="Office">
@@ -445,11 +1233,17 @@ impl SlideTemplate {
             colors.text_primary,
             colors.background,
             colors.text_secondary,
+            colors.background_secondary,
             colors.accent_1,
             colors.accent_2,
             colors.accent_3,
-            fonts.title_font,
-            fonts.body_font
+            colors.accent_4,
+            colors.accent_5,
+            colors.accent_6,
+            colors.hyperlink,
+            colors.followed_hyperlink,
+            theme_font_xml(&fonts.title_font, &fonts, true),
+            theme_font_xml(&fonts.body_font, &fonts, false)
         )
     }
 }
@@ -458,28 +1252,141 @@ impl SlideTemplate {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_slide_size_from_name() {
+        assert_eq!(
+            SlideSize::from_name("16:9"),
+            Some(SlideSize::Widescreen16x9)
+        );
+        assert_eq!(SlideSize::from_name("A4"), Some(SlideSize::A4));
+        assert_eq!(
+            SlideSize::from_name("10000000x5000000"),
+            Some(SlideSize::Custom {
+                width: 10000000,
+                height: 5000000
+            })
+        );
+        assert_eq!(SlideSize::from_name("10x5"), None);
+        assert_eq!(SlideSize::from_name("huge"), None);
+
+        let mut layout = SlideTemplate::Default.get_layout_settings();
+        assert_eq!(layout.slide_size(), SlideSize::Standard4x3);
+        layout.set_slide_size(SlideSize::Widescreen16x10);
+        assert_eq!(
+            (layout.slide_width, layout.slide_height),
+            (9144000, 5715000)
+        );
+        assert_eq!(layout.slide_size().sld_sz_type(), Some("screen16x10"));
+    }
+
     #[test]
     fn test_template_from_name() {
         assert!(matches!(
             SlideTemplate::from_name("default"),
-            SlideTemplate::Default
+            Some(SlideTemplate::Default)
         ));
         assert!(matches!(
             SlideTemplate::from_name("professional"),
-            SlideTemplate::Professional
+            Some(SlideTemplate::Professional)
         ));
         assert!(matches!(
             SlideTemplate::from_name("modern"),
-            SlideTemplate::Modern
+            Some(SlideTemplate::Modern)
         ));
         assert!(matches!(
             SlideTemplate::from_name("minimal"),
-            SlideTemplate::Minimal
-        ));
-        assert!(matches!(
-            SlideTemplate::from_name("unknown"),
-            SlideTemplate::Default
+            Some(SlideTemplate::Minimal)
         ));
+        assert_eq!(
+            SlideTemplate::from_name("high-contrast").map(|t| t.name().to_string()),
+            Some("high-contrast".to_string())
+        );
+        assert!(SlideTemplate::from_name("unknown").is_none());
+
+        let err = SlideTemplate::load("corporate").unwrap_err();
+        assert!(err.message().contains("unknown template 'corporate'"));
+    }
+
+    #[test]
+    fn test_user_template_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("team.toml"), "extends = \"modern\"\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "").unwrap();
+
+        assert_eq!(
+            user_templates_in(dir.path()),
+            vec![dir.path().join("team.toml")]
+        );
+        let template = SlideTemplate::load_in("team", Some(dir.path())).unwrap();
+        assert_eq!(template.name(), "team");
+        assert_eq!(template.get_fonts().title_font, "Roboto");
+
+        // Exported built-ins load back unchanged
+        let exported = SlideTemplate::Minimal.to_custom();
+        let path = dir.path().join("minimal-copy.toml");
+        exported.save(&path).unwrap();
+        let loaded = CustomTemplate::from_file(&path).unwrap();
+        assert_eq!(loaded.theme_colors.accent_1, exported.theme_colors.accent_1);
+        assert_eq!(loaded.layout_settings.margin_left, 1371600);
+    }
+
+    #[test]
+    fn test_load_template_file_extending_built_in() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("brand.toml");
+        std::fs::write(
+            &path,
+            "extends = \"professional\"\n\n[theme_colors]\naccent_1 = \"#1f4e79\"\n\n[layout_settings]\nslide_width = 12192000\n",
+        )
+        .unwrap();
+
+        let template = SlideTemplate::load(path.to_str().unwrap()).unwrap();
+        assert_eq!(template.name(), "brand");
+        let colors = template.get_theme_colors();
+        assert_eq!(colors.accent_1, "1F4E79");
+        assert_eq!(colors.accent_2, "C65911");
+        assert_eq!(template.get_fonts().title_font, "Segoe UI");
+        assert_eq!(template.get_layout_settings().slide_width, 12192000);
+
+        let path = dir.path().join("bad.json");
+        std::fs::write(&path, r#"{"theme_colors": {"background": "white"}}"#).unwrap();
+        let err = SlideTemplate::load(path.to_str().unwrap()).unwrap_err();
+        assert!(err.message().contains("theme_colors.background"));
+
+        std::fs::write(&path, r#"{"name": "#).unwrap();
+        let err = SlideTemplate::load(path.to_str().unwrap()).unwrap_err();
+        assert_eq!((err.code(), err.exit_code()), ("E011", 3));
+        assert!(err
+            .message()
+            .starts_with(&format!("{}: JSON", path.display())));
+
+        let err = SlideTemplate::load(dir.path().join("gone.json").to_str().unwrap()).unwrap_err();
+        assert_eq!((err.code(), err.exit_code()), ("E001", 5));
+
+        std::fs::write(&path, r#"{"layout_settings": {"margin_left": -5}}"#).unwrap();
+        let err = SlideTemplate::load(path.to_str().unwrap()).unwrap_err();
+        assert!(err.message().contains("layout_settings.margin_left"));
+
+        // Image backgrounds are relative to the template file
+        let path = dir.path().join("stage.toml");
+        std::fs::write(
+            &path,
+            "extends = \"dark\"\nbackground = \"url(stage.jpg)\"\nbackground_overlay = 50\n",
+        )
+        .unwrap();
+        let template = SlideTemplate::load(path.to_str().unwrap()).unwrap();
+        assert_eq!(
+            template.get_background().unwrap(),
+            Some(Background::Image {
+                path: dir.path().join("stage.jpg"),
+                overlay: 50,
+            })
+        );
+        assert_eq!(SlideTemplate::Dark.get_background().unwrap(), None);
+
+        std::fs::write(&path, "background = \"radial(#000000)\"\n").unwrap();
+        let err = SlideTemplate::load(path.to_str().unwrap()).unwrap_err();
+        assert!(err.message().contains("background"));
     }
 
     #[test]
@@ -488,6 +1395,51 @@ mod tests {
         let colors = template.get_theme_colors();
         assert_eq!(colors.text_primary, "1F1F1F");
         assert_eq!(colors.accent_1, "2E75B6");
+
+        // Every slot of the color scheme comes from the template
+        let theme = template.get_theme_xml();
+        assert!(theme.contains(
+            r#"<a:accent6>
+                <a:srgbClr val="BF8F00"/>"#
+        ));
+        assert!(theme.contains(
+            r#"<a:hlink>
+                <a:srgbClr val="0563C1"/>"#
+        ));
+        assert!(!theme.contains("EEECE1"));
+    }
+
+    #[test]
+    fn test_footer_overlay() {
+        let mut footer = FooterSettings {
+            text: Some("Internal".to_string()),
+            date: true,
+            show_on_title_slide: true,
+            ..Default::default()
+        };
+        footer.overlay(&FooterOverlay {
+            text: Some("Confidential".to_string()),
+            slide_number: Some(true),
+            ..Default::default()
+        });
+
+        assert_eq!(footer.text.as_deref(), Some("Confidential"));
+        assert!(footer.slide_number);
+        assert!(footer.date);
+        assert!(footer.show_on_title_slide);
+
+        // A higher-priority source can switch flags off again
+        footer.overlay(&FooterOverlay {
+            slide_number: Some(false),
+            date: Some(false),
+            show_on_title_slide: Some(false),
+            ..Default::default()
+        });
+        assert!(!footer.slide_number);
+        assert!(!footer.shows_date());
+        assert!(!footer.show_on_title_slide);
+        assert_eq!(footer.text.as_deref(), Some("Confidential"));
+        assert!(!SlideTemplate::Default.get_footer().is_visible());
     }
 
     #[test]
@@ -0,0 +1,126 @@
+/// Slide transition written as `p:transition`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    /// Explicitly no transition, used to switch off a deck-wide default on one slide
+    None,
+    Fade,
+    Push,
+    Wipe,
+    Morph,
+}
+
+impl Transition {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "none" => Some(Transition::None),
+            "fade" => Some(Transition::Fade),
+            "push" => Some(Transition::Push),
+            "wipe" => Some(Transition::Wipe),
+            "morph" => Some(Transition::Morph),
+            _ => None,
+        }
+    }
+
+    pub fn to_xml(&self) -> String {
+        match self {
+            Transition::None => String::new(),
+            Transition::Fade => r#"<p:transition spd="med"><p:fade/></p:transition>"#.to_string(),
+            Transition::Push => {
+                r#"<p:transition spd="med"><p:push dir="u"/></p:transition>"#.to_string()
+            }
+            Transition::Wipe => {
+                r#"<p:transition spd="med"><p:wipe dir="r"/></p:transition>"#.to_string()
+            }
+            // Morph is a PowerPoint 2019 extension; older readers fall back to a fade
+            Transition::Morph => r#"<mc:AlternateContent xmlns:mc="http://schemas.openxmlformats.org/markup-compatibility/2006"><mc:Choice xmlns:p159="http://schemas.microsoft.com/office/powerpoint/2015/09/main" Requires="p159"><p:transition spd="slow"><p159:morph option="byObject"/></p:transition></mc:Choice><mc:Fallback><p:transition spd="slow"><p:fade/></p:transition></mc:Fallback></mc:AlternateContent>"#.to_string(),
+        }
+    }
+}
+
+/// Paragraphs of a text shape that appear one click at a time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ParagraphBuild {
+    pub shape_id: usize,
+    /// Index of the first animated paragraph in the shape
+    pub first: usize,
+    pub paragraphs: usize,
+}
+
+/// Build the `p:timing` tree revealing each paragraph of each build on click,
+/// in shape order. Returns an empty string when there is nothing to animate.
+pub(crate) fn timing_xml(builds: &[ParagraphBuild]) -> String {
+    if builds.is_empty() {
+        return String::new();
+    }
+
+    // Ids 1 and 2 are the timing root and the main sequence
+    let mut next_id = 3;
+    let mut clicks = String::new();
+    for build in builds {
+        for paragraph in build.first..build.first + build.paragraphs {
+            clicks.push_str(&format!(
+                r#"<p:par><p:cTn id="{}" fill="hold"><p:stCondLst><p:cond delay="indefinite"/></p:stCondLst><p:childTnLst><p:par><p:cTn id="{}" fill="hold"><p:stCondLst><p:cond delay="0"/></p:stCondLst><p:childTnLst><p:par><p:cTn id="{}" presetID="1" presetClass="entr" presetSubtype="0" fill="hold" grpId="0" nodeType="clickEffect"><p:stCondLst><p:cond delay="0"/></p:stCondLst><p:childTnLst><p:set><p:cBhvr><p:cTn id="{}" dur="1" fill="hold"><p:stCondLst><p:cond delay="0"/></p:stCondLst></p:cTn><p:tgtEl><p:spTgt spid="{}"><p:txEl><p:pRg st="{}" end="{}"/></p:txEl></p:spTgt></p:tgtEl><p:attrNameLst><p:attrName>style.visibility</p:attrName></p:attrNameLst></p:cBhvr><p:to><p:strVal val="visible"/></p:to></p:set></p:childTnLst></p:cTn></p:par></p:childTnLst></p:cTn></p:par></p:childTnLst></p:cTn></p:par>"#,
+                next_id,
+                next_id + 1,
+                next_id + 2,
+                next_id + 3,
+                build.shape_id,
+                paragraph,
+                paragraph
+            ));
+            next_id += 4;
+        }
+    }
+
+    let mut shape_ids: Vec<usize> = builds.iter().map(|build| build.shape_id).collect();
+    shape_ids.dedup();
+    let build_list = shape_ids
+        .iter()
+        .map(|shape_id| format!(r#"<p:bldP spid="{}" grpId="0" build="p"/>"#, shape_id))
+        .collect::<String>();
+
+    format!(
+        r#"<p:timing><p:tnLst><p:par><p:cTn id="1" dur="indefinite" restart="never" nodeType="tmRoot"><p:childTnLst><p:seq concurrent="1" nextAc="seek"><p:cTn id="2" dur="indefinite" nodeType="mainSeq"><p:childTnLst>{}</p:childTnLst></p:cTn><p:prevCondLst><p:cond evt="onPrev" delay="0"><p:tgtEl><p:sldTgt/></p:tgtEl></p:cond></p:prevCondLst><p:nextCondLst><p:cond evt="onNext" delay="0"><p:tgtEl><p:sldTgt/></p:tgtEl></p:cond></p:nextCondLst></p:seq></p:childTnLst></p:cTn></p:par></p:tnLst><p:bldLst>{}</p:bldLst></p:timing>"#,
+        clicks, build_list
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transition_from_name() {
+        assert_eq!(Transition::from_name("Fade"), Some(Transition::Fade));
+        assert_eq!(Transition::from_name("none"), Some(Transition::None));
+        assert_eq!(Transition::from_name("spin"), None);
+        assert!(Transition::None.to_xml().is_empty());
+        assert!(Transition::Morph.to_xml().contains("p159:morph"));
+    }
+
+    #[test]
+    fn test_timing_xml_reveals_each_paragraph() {
+        assert!(timing_xml(&[]).is_empty());
+
+        let xml = timing_xml(&[
+            ParagraphBuild {
+                shape_id: 4,
+                first: 0,
+                paragraphs: 3,
+            },
+            ParagraphBuild {
+                shape_id: 4,
+                first: 5,
+                paragraphs: 1,
+            },
+        ]);
+        assert_eq!(xml.matches(r#"nodeType="clickEffect""#).count(), 4);
+        assert!(xml.contains(r#"<p:spTgt spid="4"><p:txEl><p:pRg st="2" end="2"/>"#));
+        assert!(xml.contains(r#"<p:spTgt spid="4"><p:txEl><p:pRg st="5" end="5"/>"#));
+        assert_eq!(
+            xml.matches(r#"<p:bldP spid="4" grpId="0" build="p"/>"#)
+                .count(),
+            1
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use crate::parser::lint::LintConfig;
+use crate::parser::markdown::{DocumentMetadata, SourceSpan};
+use crate::utils::error::Error;
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Project config file looked up in the input directory
+pub const PROJECT_CONFIG_FILE: &str = "md2pptx.toml";
+
+/// Settings shared by a directory of slides, from `md2pptx.toml`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    pub lint: LintConfig,
+    pub metadata: MetadataConfig,
+}
+
+/// Title slide metadata shared by a project's decks. Front matter and command
+/// line flags override it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetadataConfig {
+    pub title: Option<String>,
+    pub subtitle: Option<String>,
+    pub author: Option<String>,
+    pub date: Option<String>,
+}
+
+impl MetadataConfig {
+    pub fn to_metadata(&self) -> DocumentMetadata {
+        DocumentMetadata {
+            title: self.title.clone(),
+            author: self.author.clone(),
+            description: self.subtitle.clone(),
+            date: self.date.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl ProjectConfig {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).map_err(|err| Error::from(err).in_file(path))?;
+        let config: Self = toml::from_str(&content).map_err(|err| {
+            let span = err.span().map(|range| SourceSpan::locate(&content, range));
+            let error = Error::from(err)
+                .in_file(path)
+                .with_hint("md2pptx.toml takes [lint] and [metadata] tables; see the README");
+            match span {
+                Some(span) => error.at(span),
+                None => error,
+            }
+        })?;
+        config.lint.validate().map_err(|err| err.in_file(path))?;
+        Ok(config)
+    }
+
+    /// The `md2pptx.toml` in `dir`, or the defaults when there is none
+    pub fn find(dir: &Path) -> Result<Self> {
+        let path = dir.join(PROJECT_CONFIG_FILE);
+        if path.is_file() {
+            Self::from_file(&path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::lint::{LintRule, Severity};
+
+    #[test]
+    fn test_project_config() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            ProjectConfig::find(dir.path()).unwrap(),
+            ProjectConfig::default()
+        );
+
+        std::fs::write(
+            dir.path().join(PROJECT_CONFIG_FILE),
+            "[lint]\nmax_bullets = 4\n\n[lint.rules]\nduplicate-title = \"error\"\n",
+        )
+        .unwrap();
+        let config = ProjectConfig::find(dir.path()).unwrap();
+        assert_eq!(config.lint.max_bullets, 4);
+        assert_eq!(config.metadata, MetadataConfig::default());
+        assert_eq!(config.lint.max_words, 80);
+        assert_eq!(
+            config.lint.severity(LintRule::DuplicateTitle),
+            Severity::Error
+        );
+
+        std::fs::write(
+            dir.path().join(PROJECT_CONFIG_FILE),
+            "[lint.rules]\nduplicate-titles = \"error\"\n",
+        )
+        .unwrap();
+        assert!(ProjectConfig::find(dir.path()).is_err());
+
+        std::fs::write(
+            dir.path().join(PROJECT_CONFIG_FILE),
+            "[metadata]\nsubtitle = \"Quarterly review\"\nauthor = \"Platform team\"\n",
+        )
+        .unwrap();
+        let metadata = ProjectConfig::find(dir.path())
+            .unwrap()
+            .metadata
+            .to_metadata();
+        assert_eq!(metadata.description.as_deref(), Some("Quarterly review"));
+        assert_eq!(metadata.author.as_deref(), Some("Platform team"));
+        assert_eq!(metadata.title, None);
+
+        // Syntax errors point at the offending key
+        std::fs::write(
+            dir.path().join(PROJECT_CONFIG_FILE),
+            "[lint]\nmax_words = 60\nmax_bulets = 3\n",
+        )
+        .unwrap();
+        let err = ProjectConfig::find(dir.path()).unwrap_err();
+        assert_eq!(err.code(), "E013");
+        assert_eq!(err.exit_code(), 3);
+        assert!(err.message().starts_with(&format!(
+            "{}:3:1: ",
+            dir.path().join(PROJECT_CONFIG_FILE).display()
+        )));
+    }
+}
@@ -0,0 +1,172 @@
+use crate::parser::markdown::SourceSpan;
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Stable code of a non-fatal conversion problem: content the slides cannot
+/// show, which is dropped or replaced rather than failing the conversion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum WarningCode {
+    /// Raw HTML other than comments and `<span>` tags
+    #[serde(rename = "W001")]
+    HtmlDropped,
+    /// Footnote references and definitions
+    #[serde(rename = "W002")]
+    FootnoteDropped,
+    /// Images inside text, lists, quotes, headings or tables
+    #[serde(rename = "W003")]
+    InlineImage,
+    /// Links in headings and table cells, which keep only their text
+    #[serde(rename = "W004")]
+    LinkDropped,
+    /// Block elements with no slide equivalent, such as horizontal rules
+    #[serde(rename = "W005")]
+    UnsupportedElement,
+    /// Images that could not be embedded and show as placeholder text
+    #[serde(rename = "W006")]
+    ImagePlaceholder,
+}
+
+impl WarningCode {
+    pub fn code(&self) -> &'static str {
+        match self {
+            WarningCode::HtmlDropped => "W001",
+            WarningCode::FootnoteDropped => "W002",
+            WarningCode::InlineImage => "W003",
+            WarningCode::LinkDropped => "W004",
+            WarningCode::UnsupportedElement => "W005",
+            WarningCode::ImagePlaceholder => "W006",
+        }
+    }
+}
+
+/// One warning, located by file and source span when they are known
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    pub code: WarningCode,
+    pub message: String,
+    pub file: Option<PathBuf>,
+    pub span: Option<SourceSpan>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file.display())?;
+        }
+        if let Some(span) = &self.span {
+            write!(f, "{}:{}:", span.line, span.column)?;
+        }
+        if self.file.is_some() || self.span.is_some() {
+            write!(f, " ")?;
+        }
+        write!(f, "warning[{}]: {}", self.code.code(), self.message)
+    }
+}
+
+/// Warnings collected while parsing and building, returned alongside the
+/// result so callers can report them or, with `--strict`, fail on them
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Diagnostics(Vec<Diagnostic>);
+
+impl Diagnostics {
+    pub fn warn(
+        &mut self,
+        code: WarningCode,
+        file: Option<&Path>,
+        span: Option<SourceSpan>,
+        message: impl Into<String>,
+    ) {
+        self.0.push(Diagnostic {
+            code,
+            message: message.into(),
+            file: file.map(Path::to_path_buf),
+            span,
+        });
+    }
+
+    /// Attribute warnings without a file to `file`
+    pub fn in_file(mut self, file: &Path) -> Self {
+        for diagnostic in &mut self.0 {
+            diagnostic.file.get_or_insert_with(|| file.to_path_buf());
+        }
+        self
+    }
+
+    /// The warnings about `file`
+    pub fn for_file(&self, file: &Path) -> Diagnostics {
+        Diagnostics(
+            self.0
+                .iter()
+                .filter(|diagnostic| diagnostic.file.as_deref() == Some(file))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    pub fn extend(&mut self, other: Diagnostics) {
+        self.0.extend(other.0);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    /// "1 warning" or "N warnings"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.len() {
+            1 => write!(f, "1 warning"),
+            count => write!(f, "{} warnings", count),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostics() {
+        let span = SourceSpan {
+            start: 10,
+            end: 20,
+            line: 3,
+            column: 5,
+        };
+        let mut diagnostics = Diagnostics::default();
+        diagnostics.warn(WarningCode::HtmlDropped, None, Some(span), "HTML dropped");
+        diagnostics.warn(
+            WarningCode::ImagePlaceholder,
+            Some(Path::new("b.md")),
+            None,
+            "image missing",
+        );
+        let diagnostics = diagnostics.in_file(Path::new("a.md"));
+
+        let lines: Vec<String> = diagnostics.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            lines,
+            [
+                "a.md:3:5: warning[W001]: HTML dropped",
+                "b.md: warning[W006]: image missing"
+            ]
+        );
+        assert_eq!(diagnostics.to_string(), "2 warnings");
+
+        let json = serde_json::to_value(&diagnostics).unwrap();
+        assert_eq!(json[0]["code"], "W001");
+        assert_eq!(json[0]["span"]["line"], 3);
+        assert_eq!(json[1]["file"], "b.md");
+    }
+}
@@ -1,5 +1,40 @@
+use crate::parser::markdown::SourceSpan;
+use crate::utils::diagnostics::Diagnostics;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
+/// Exit status of `check` and `lint` when they ran but found problems
+pub const ISSUES_FOUND_EXIT_CODE: i32 = 7;
+
+/// Broad kind of failure, which decides the exit code of the command line tool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCategory {
+    /// The deck could not be produced from otherwise valid input
+    Conversion,
+    /// Bad settings: command line values, templates and project config
+    Configuration,
+    /// Input files that are missing or malformed
+    Input,
+    /// Files that cannot be read or written
+    Io,
+    /// Warnings that failed the run under `--strict`
+    Warnings,
+}
+
+impl ErrorCategory {
+    /// Exit status for the category. 2 is left to command line usage errors.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ErrorCategory::Conversion => 1,
+            ErrorCategory::Configuration => 3,
+            ErrorCategory::Input => 4,
+            ErrorCategory::Io => 5,
+            ErrorCategory::Warnings => 6,
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("IO error: {0}")]
@@ -23,17 +58,39 @@ pub enum Error {
     #[error("Conversion error: {message}")]
     Conversion { message: String },
 
+    /// Warnings that `--strict` turns into a failure
+    #[error("{diagnostics} treated as errors (--strict)")]
+    Warnings { diagnostics: Diagnostics },
+
     #[error("XML processing error: {0}")]
     Xml(#[from] quick_xml::Error),
 
     #[error("Zip file error: {0}")]
     Zip(#[from] zip::result::ZipError),
 
-    #[error("JSON serialization error: {0}")]
+    #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
+    #[error("TOML parsing error: {}", .0.message().trim())]
+    Toml(#[from] toml::de::Error),
+
+    #[error("TOML serialization error: {0}")]
+    TomlSerialize(#[from] toml::ser::Error),
+
     #[error("Walkdir error: {0}")]
     WalkDir(#[from] walkdir::Error),
+
+    /// Another error with the file and position it happened at, and a hint
+    /// on how to fix it. Displays only the location, or the error itself when
+    /// there is none; the error is the source either way.
+    #[error("{}", context_label(.path.as_deref(), .span.as_ref(), source))]
+    Context {
+        path: Option<PathBuf>,
+        span: Option<SourceSpan>,
+        hint: Option<String>,
+        #[source]
+        source: Box<Error>,
+    },
 }
 
 impl Error {
@@ -68,4 +125,272 @@ impl Error {
             message: message.into(),
         }
     }
+
+    /// Attach the file the error happened in, unless one is already known
+    pub fn in_file(self, path: impl Into<PathBuf>) -> Self {
+        let (file, span, hint, source) = self.into_parts();
+        Self::Context {
+            path: file.or_else(|| Some(path.into())),
+            span,
+            hint,
+            source,
+        }
+    }
+
+    /// Attach the position in the file the error happened at
+    pub fn at(self, span: SourceSpan) -> Self {
+        let (path, _, hint, source) = self.into_parts();
+        Self::Context {
+            path,
+            span: Some(span),
+            hint,
+            source,
+        }
+    }
+
+    /// Attach a suggestion on how to fix the error
+    pub fn with_hint(self, hint: impl Into<String>) -> Self {
+        let (path, span, _, source) = self.into_parts();
+        Self::Context {
+            path,
+            span,
+            hint: Some(hint.into()),
+            source,
+        }
+    }
+
+    fn into_parts(
+        self,
+    ) -> (
+        Option<PathBuf>,
+        Option<SourceSpan>,
+        Option<String>,
+        Box<Error>,
+    ) {
+        match self {
+            Self::Context {
+                path,
+                span,
+                hint,
+                source,
+            } => (path, span, hint, source),
+            error => (None, None, None, Box::new(error)),
+        }
+    }
+
+    /// The error without the file, position and hint attached to it
+    pub fn root(&self) -> &Error {
+        match self {
+            Self::Context { source, .. } => source.root(),
+            error => error,
+        }
+    }
+
+    /// Stable code for the kind of error, e.g. `E004` for a missing file
+    pub fn code(&self) -> &'static str {
+        match self.root() {
+            Self::Io(_) => "E001",
+            Self::MarkdownParsing { .. } => "E002",
+            Self::PptxGeneration { .. } => "E003",
+            Self::FileNotFound { .. } => "E004",
+            Self::InvalidFileFormat { .. } => "E005",
+            Self::Configuration { .. } => "E006",
+            Self::Conversion { .. } => "E007",
+            Self::Warnings { .. } => "E008",
+            Self::Xml(_) => "E009",
+            Self::Zip(_) => "E010",
+            Self::Json(_) => "E011",
+            Self::WalkDir(_) => "E012",
+            Self::Toml(_) => "E013",
+            Self::TomlSerialize(_) => "E014",
+            Self::Context { .. } => unreachable!("root() never returns a context"),
+        }
+    }
+
+    pub fn category(&self) -> ErrorCategory {
+        match self.root() {
+            // Only templates and `md2pptx.toml` are parsed from TOML or JSON
+            Self::Configuration { .. } | Self::Toml(_) => ErrorCategory::Configuration,
+            Self::Json(err) if !err.is_io() => ErrorCategory::Configuration,
+            Self::MarkdownParsing { .. }
+            | Self::FileNotFound { .. }
+            | Self::InvalidFileFormat { .. } => ErrorCategory::Input,
+            // A file that is not a zip package is malformed input
+            Self::Zip(zip::result::ZipError::Io(_)) => ErrorCategory::Io,
+            Self::Zip(_) => ErrorCategory::Input,
+            Self::Io(_) | Self::WalkDir(_) => ErrorCategory::Io,
+            Self::Warnings { .. } => ErrorCategory::Warnings,
+            _ => ErrorCategory::Conversion,
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        self.category().exit_code()
+    }
+
+    /// One-line message: the location, if known, and the error itself
+    pub fn message(&self) -> String {
+        match self {
+            Self::Context { path, span, .. } if path.is_some() || span.is_some() => {
+                format!("{}: {}", self, self.root())
+            }
+            _ => self.root().to_string(),
+        }
+    }
+
+    /// The attached hint, or a general one for the kind of error
+    pub fn hint(&self) -> Option<String> {
+        if let Self::Context {
+            hint: Some(hint), ..
+        } = self
+        {
+            return Some(hint.clone());
+        }
+        match self.root() {
+            Self::FileNotFound { .. } => {
+                Some("check the path; relative paths start from the current directory".to_string())
+            }
+            Self::Warnings { .. } => {
+                Some("fix the warnings above, or leave out --strict to convert anyway".to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Multi-line report for people: the code and message, where it happened
+    /// with the offending source line, what caused it and a hint
+    pub fn render(&self) -> String {
+        let root = self.root();
+        let mut out = format!("error[{}]: {}\n", self.code(), root);
+        if let Self::Context {
+            path: Some(path),
+            span,
+            ..
+        } = self
+        {
+            let _ = writeln!(out, "  --> {}", location(Some(path), span.as_ref()));
+            if let Some(snippet) = span.as_ref().and_then(|span| source_snippet(path, span)) {
+                out.push_str(&snippet);
+            }
+        }
+
+        // The variants wrapping another library's error already show it in
+        // their message, so the causes start below it
+        let mut cause = std::error::Error::source(root).and_then(|error| error.source());
+        while let Some(error) = cause {
+            let _ = writeln!(out, "   = caused by: {}", error);
+            cause = error.source();
+        }
+        if let Some(hint) = self.hint() {
+            let _ = writeln!(out, "   = hint: {}", hint);
+        }
+        out
+    }
+}
+
+/// `path:line:column` for the parts that are known
+fn location(path: Option<&Path>, span: Option<&SourceSpan>) -> String {
+    match (path, span) {
+        (Some(path), Some(span)) => {
+            format!("{}:{}:{}", path.display(), span.line, span.column)
+        }
+        (Some(path), None) => path.display().to_string(),
+        (None, Some(span)) => format!("{}:{}", span.line, span.column),
+        (None, None) => String::new(),
+    }
+}
+
+/// The location of a context, or the message of its error when it has none
+fn context_label(path: Option<&Path>, span: Option<&SourceSpan>, source: &Error) -> String {
+    match (path, span) {
+        (None, None) => source.to_string(),
+        _ => location(path, span),
+    }
+}
+
+/// The source line a span starts on, underlined from its column
+fn source_snippet(path: &Path, span: &SourceSpan) -> Option<String> {
+    let source = std::fs::read_to_string(path).ok()?;
+    let line = source.lines().nth(span.line.checked_sub(1)?)?;
+    let before = span.column.saturating_sub(1);
+    let marked = source
+        .get(span.start..span.end)
+        .map_or(1, |text| {
+            text.lines().next().unwrap_or_default().chars().count()
+        })
+        .clamp(1, line.chars().count().saturating_sub(before).max(1));
+
+    let width = span.line.to_string().len();
+    let gutter = " ".repeat(width);
+    Some(format!(
+        "{gutter} |\n{} | {}\n{gutter} | {}{}\n",
+        span.line,
+        line,
+        " ".repeat(before),
+        "^".repeat(marked)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_codes_and_rendering() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("md2pptx.toml");
+        std::fs::write(&path, "[lint]\nmax_bulets = 3\n").unwrap();
+        let span = SourceSpan {
+            start: 7,
+            end: 17,
+            line: 2,
+            column: 1,
+        };
+        let error = Error::configuration("unknown field `max_bulets`")
+            .in_file(&path)
+            .at(span)
+            .with_hint("did you mean `max_bullets`?");
+
+        assert_eq!(error.code(), "E006");
+        assert_eq!(error.exit_code(), 3);
+        assert_eq!(
+            error.message(),
+            format!(
+                "{}:2:1: Configuration error: unknown field `max_bulets`",
+                path.display()
+            )
+        );
+        // The message of the cause is shown once, not again in the context
+        assert_eq!(error.to_string(), format!("{}:2:1", path.display()));
+        assert_eq!(
+            std::error::Error::source(&error).unwrap().to_string(),
+            "Configuration error: unknown field `max_bulets`"
+        );
+        assert_eq!(
+            error.render(),
+            format!(
+                "error[E006]: Configuration error: unknown field `max_bulets`\n  --> {}:2:1\n  |\n2 | max_bulets = 3\n  | ^^^^^^^^^^\n   = hint: did you mean `max_bullets`?\n",
+                path.display()
+            )
+        );
+
+        // A file attached closer to the cause wins
+        let io = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        let error = Error::from(io).in_file("a.md").in_file("deck");
+        assert!(error.message().starts_with("a.md: IO error"));
+        assert_eq!(error.category(), ErrorCategory::Io);
+        assert_eq!(
+            error.render(),
+            "error[E001]: IO error: denied\n  --> a.md\n"
+        );
+
+        // Without a location the context shows the error itself
+        let error = Error::markdown_parsing("No slides found").with_hint("add a heading");
+        assert_eq!(error.to_string(), "Markdown parsing error: No slides found");
+        assert_eq!(error.message(), error.to_string());
+
+        let error = Error::file_not_found("slides");
+        assert_eq!(error.exit_code(), 4);
+        assert!(error.render().contains("= hint: check the path"));
+    }
 }
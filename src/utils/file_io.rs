@@ -50,17 +50,17 @@ pub fn read_file_to_string(path: &Path) -> Result<String> {
         return Err(Error::file_not_found(path.display().to_string()));
     }
 
-    let content = fs::read_to_string(path)?;
+    let content = fs::read_to_string(path).map_err(|err| Error::from(err).in_file(path))?;
     Ok(content)
 }
 
 /// Write bytes to file
 pub fn write_file(path: &Path, content: &[u8]) -> Result<()> {
     if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
+        fs::create_dir_all(parent).map_err(|err| Error::from(err).in_file(parent))?;
     }
 
-    fs::write(path, content)?;
+    fs::write(path, content).map_err(|err| Error::from(err).in_file(path))?;
     Ok(())
 }
 
@@ -1,2 +1,4 @@
+pub mod config;
+pub mod diagnostics;
 pub mod error;
 pub mod file_io;
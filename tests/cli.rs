@@ -0,0 +1,31 @@
+use std::process::Command;
+
+#[test]
+fn test_json_messages_are_the_only_stdout() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("slides");
+    std::fs::create_dir(&input).unwrap();
+    std::fs::write(input.join("talk.md"), "# Talk\n\nBefore\n\n---\n\nAfter\n").unwrap();
+
+    for separate in [false, true] {
+        let output = if separate {
+            dir.path().join("decks")
+        } else {
+            dir.path().join("talk.pptx")
+        };
+        let mut command = Command::new(env!("CARGO_BIN_EXE_md2pptx"));
+        command
+            .arg(&input)
+            .arg(&output)
+            .args(["--message-format", "json"]);
+        if separate {
+            command.arg("--separate");
+        }
+        let result = command.output().unwrap();
+        assert!(result.status.success());
+
+        let warnings: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+        assert_eq!(warnings[0]["code"], "W005");
+        assert_eq!(warnings.as_array().unwrap().len(), 1);
+    }
+}